@@ -0,0 +1,420 @@
+use core::{Binding, Type, TypeDefinitionBody, TypePrinter, BUILTIN_TYPES};
+use lsp_types::{CompletionItem, CompletionItemKind, InsertTextFormat};
+use parser::{SyntaxKind, SyntaxNode, SyntaxNodePtr, SyntaxToken};
+
+use crate::semantics::{Semantics, Symbol};
+use crate::{position_to_offset, Document};
+
+/// Identifier inserted at the cursor, so that the code around it parses as a name.
+const PLACEHOLDER: &str = "__completion";
+
+const ITEM_KEYWORDS: [&str; 3] = ["def", "type", "open"];
+const EXPR_KEYWORDS: [&str; 2] = ["true", "false"];
+
+/// Suggests names visible at the position, depending on whether an expression
+/// or a type is expected there, and keywords starting items or statements.
+#[must_use]
+pub fn get_completions(
+    doc: &Document,
+    position: lsp_types::Position,
+) -> Option<Vec<CompletionItem>> {
+    let offset = position_to_offset(doc.get_line_index(), position)?;
+    let mut text = doc.text().to_string();
+    text.insert_str(offset.try_into().ok()?, PLACEHOLDER);
+    let sema = Semantics::new(&Document::new(text));
+    // Types come from the document as written, not influenced by the placeholder.
+    let original = Original {
+        sema: Semantics::new(doc),
+        offset,
+    };
+
+    let ident = sema.ident_at(offset)?;
+    if !ident.text().contains(PLACEHOLDER) {
+        return None;
+    }
+    let parent = ident.parent()?;
+    let items = match parent.kind() {
+        SyntaxKind::IDENT_EXPR => {
+            let mut items = expr_completions(&sema, &original, &parent);
+            if is_statement_start(&ident) {
+                items.push(keyword("let"));
+            }
+            items
+        }
+        SyntaxKind::TYPE_IDENT => type_completions(&sema, &original),
+        _ if is_module_item_start(&ident) => item_completions(),
+        _ => Vec::new(),
+    };
+    Some(items)
+}
+
+/// The document without the placeholder.
+struct Original {
+    sema: Semantics,
+    /// Where the placeholder is inserted.
+    offset: u32,
+}
+
+impl Original {
+    /// Finds the item declared by the identifier of the document with the placeholder.
+    fn symbol(&self, declaration: &SyntaxToken) -> Option<Symbol> {
+        let mut start = u32::from(declaration.text_range().start());
+        if start > self.offset {
+            start -= u32::try_from(PLACEHOLDER.len()).ok()?;
+        }
+        self.sema.resolve(&self.sema.ident_at(start)?)
+    }
+
+    fn binding_type(&self, sema: &Semantics, binding: Binding) -> Option<Type> {
+        if let Binding::Builtin(builtin) = binding {
+            return Some(builtin.typ());
+        }
+        match self.symbol(&sema.declaration(Symbol::Binding(binding))?)? {
+            Symbol::Binding(binding) => self.sema.binding_type(binding),
+            Symbol::TypeDefinition(_) | Symbol::TypeParam { .. } | Symbol::NamedType(_) => None,
+        }
+    }
+
+    fn alias_type(&self, sema: &Semantics, symbol: Symbol) -> Option<String> {
+        match self.symbol(&sema.declaration(symbol)?)? {
+            Symbol::TypeDefinition(idx) => {
+                let typ = self.sema.inference.type_of_type_definition(idx)?;
+                Some(TypePrinter::new(&self.sema.module).print(typ))
+            }
+            Symbol::Binding(_) | Symbol::TypeParam { .. } | Symbol::NamedType(_) => None,
+        }
+    }
+}
+
+fn expr_completions(
+    sema: &Semantics,
+    original: &Original,
+    ident_expr: &SyntaxNode,
+) -> Vec<CompletionItem> {
+    let Some(scope) = sema
+        .module
+        .source_map()
+        .syntax_expr(SyntaxNodePtr::new(ident_expr))
+        .and_then(|expr| sema.scopes.scope_of_expr(expr))
+    else {
+        return Vec::new();
+    };
+
+    let mut items: Vec<_> = sema
+        .scopes
+        .visible_names(scope)
+        .map(|(name, binding)| {
+            let typ = original.binding_type(sema, binding);
+            let kind = match (binding, &typ) {
+                (Binding::Constructor(_), _) => CompletionItemKind::ENUM_MEMBER,
+                (Binding::Builtin(_), _) | (_, Some(Type::Arrow(..))) => {
+                    CompletionItemKind::FUNCTION
+                }
+                _ => CompletionItemKind::VARIABLE,
+            };
+            CompletionItem {
+                label: sema.module.lookup_name(name).into(),
+                kind: Some(kind),
+                detail: typ.map(|typ| TypePrinter::new(&original.sema.module).print(&typ)),
+                ..Default::default()
+            }
+        })
+        .collect();
+    items.push(snippet("\\x -> …", "\\\\${1:x} -> $0", "lambda"));
+    items.push(snippet(
+        "if … { … } else { … }",
+        "if ${1:condition} {\n\t$2\n} else {\n\t$0\n}",
+        "if expression",
+    ));
+    items.push(snippet(
+        "match … { … }",
+        "match ${1:value} {\n\t${2:_} => $0,\n}",
+        "match expression",
+    ));
+    items.extend(EXPR_KEYWORDS.iter().map(|kw| keyword(kw)));
+    items
+}
+
+fn type_completions(sema: &Semantics, original: &Original) -> Vec<CompletionItem> {
+    let module = &sema.module;
+    let aliases =
+        module
+            .type_definitions()
+            .map(|(idx, type_definition)| match type_definition.defn {
+                TypeDefinitionBody::Alias(_) => CompletionItem {
+                    label: module.lookup_name(type_definition.name).into(),
+                    kind: Some(CompletionItemKind::STRUCT),
+                    detail: original.alias_type(sema, Symbol::TypeDefinition(idx)),
+                    ..Default::default()
+                },
+                TypeDefinitionBody::Sum(_) => CompletionItem {
+                    label: module.lookup_name(type_definition.name).into(),
+                    kind: Some(CompletionItemKind::ENUM),
+                    ..Default::default()
+                },
+            });
+    let builtins = BUILTIN_TYPES
+        .iter()
+        .filter(|_| module.has_prelude())
+        .map(|name| CompletionItem {
+            label: (*name).into(),
+            kind: Some(CompletionItemKind::STRUCT),
+            ..Default::default()
+        });
+    aliases.chain(builtins).collect()
+}
+
+fn item_completions() -> Vec<CompletionItem> {
+    let mut items: Vec<_> = ITEM_KEYWORDS.iter().map(|kw| keyword(kw)).collect();
+    items.push(snippet(
+        "def name params { … }",
+        "def ${1:name} ${2:x} {\n\t$0\n}",
+        "function definition",
+    ));
+    items
+}
+
+/// Statements start after the opening brace of a block or after a previous statement.
+fn is_statement_start(ident: &SyntaxToken) -> bool {
+    matches!(
+        previous_token(ident).map(|token| token.kind()),
+        Some(SyntaxKind::L_BRACE | SyntaxKind::SEMICOLON)
+    )
+}
+
+/// Module items start outside of any other item, where a lone name is a syntax error.
+fn is_module_item_start(ident: &SyntaxToken) -> bool {
+    ident
+        .parent_ancestors()
+        .all(|node| matches!(node.kind(), SyntaxKind::ERROR | SyntaxKind::MODULE))
+}
+
+fn previous_token(token: &SyntaxToken) -> Option<SyntaxToken> {
+    std::iter::successors(token.prev_token(), SyntaxToken::prev_token)
+        .find(|token| !token.kind().is_trivial())
+}
+
+fn keyword(keyword: &str) -> CompletionItem {
+    CompletionItem {
+        label: keyword.into(),
+        kind: Some(CompletionItemKind::KEYWORD),
+        ..Default::default()
+    }
+}
+
+fn snippet(label: &str, insert_text: &str, detail: &str) -> CompletionItem {
+    CompletionItem {
+        label: label.into(),
+        kind: Some(CompletionItemKind::SNIPPET),
+        detail: Some(detail.into()),
+        insert_text: Some(insert_text.into()),
+        insert_text_format: Some(InsertTextFormat::SNIPPET),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write;
+
+    use expect_test::{expect, Expect};
+
+    use super::get_completions;
+    use crate::fixture::cursor;
+
+    /// The cursor is placed at `$0`.
+    fn check(text: &str, expected: &Expect) {
+        let (doc, position) = cursor(text);
+
+        let mut actual = String::new();
+        for item in get_completions(&doc, position).unwrap_or_default() {
+            write!(actual, "{:?} {}", item.kind.unwrap(), item.label).unwrap();
+            if let Some(detail) = item.detail {
+                write!(actual, " : {detail}").unwrap();
+            }
+            actual.push('\n');
+        }
+        expected.assert_eq(&actual);
+    }
+
+    #[test]
+    fn complete_expr() {
+        check(
+            "def a = 1;\ndef f (x: int) { let y = x; (add $0) }",
+            &expect![[r"
+                Variable y : int
+                Variable x : int
+                Function f : int -> int -> int -> int
+                Variable a : int
+                Function lt : int -> int -> bool
+                Function eq : int -> int -> bool
+                Function div : int -> int -> int
+                Function mul : int -> int -> int
+                Function sub : int -> int -> int
+                Function add : int -> int -> int
+                Snippet \x -> … : lambda
+                Snippet if … { … } else { … } : if expression
+                Snippet match … { … } : match expression
+                Keyword true
+                Keyword false
+            "]],
+        );
+    }
+
+    #[test]
+    fn complete_with_prefix() {
+        check(
+            "def value = 1;\ndef f = va$0;",
+            &expect![[r"
+                Variable f : 'a
+                Variable value : int
+                Function lt : int -> int -> bool
+                Function eq : int -> int -> bool
+                Function div : int -> int -> int
+                Function mul : int -> int -> int
+                Function sub : int -> int -> int
+                Function add : int -> int -> int
+                Snippet \x -> … : lambda
+                Snippet if … { … } else { … } : if expression
+                Snippet match … { … } : match expression
+                Keyword true
+                Keyword false
+            "]],
+        );
+    }
+
+    #[test]
+    fn complete_lambda_param() {
+        check(
+            "def f = \\x -> $0;",
+            &expect![[r"
+                Variable x : 'a
+                Function f : 'a -> 'b
+                Function lt : int -> int -> bool
+                Function eq : int -> int -> bool
+                Function div : int -> int -> int
+                Function mul : int -> int -> int
+                Function sub : int -> int -> int
+                Function add : int -> int -> int
+                Snippet \x -> … : lambda
+                Snippet if … { … } else { … } : if expression
+                Snippet match … { … } : match expression
+                Keyword true
+                Keyword false
+            "]],
+        );
+    }
+
+    #[test]
+    fn complete_statement() {
+        check(
+            "def f { let y = 1; $0 }",
+            &expect![[r"
+                Variable y : int
+                Variable f : 'a
+                Function lt : int -> int -> bool
+                Function eq : int -> int -> bool
+                Function div : int -> int -> int
+                Function mul : int -> int -> int
+                Function sub : int -> int -> int
+                Function add : int -> int -> int
+                Snippet \x -> … : lambda
+                Snippet if … { … } else { … } : if expression
+                Snippet match … { … } : match expression
+                Keyword true
+                Keyword false
+                Keyword let
+            "]],
+        );
+    }
+
+    #[test]
+    fn complete_type() {
+        check(
+            "type t = int -> int;\ndef f (x: $0) = x;",
+            &expect![[r"
+                Struct t : int -> int
+                Struct int
+                Struct bool
+                Struct unit
+            "]],
+        );
+    }
+
+    #[test]
+    fn complete_constructors() {
+        check(
+            "type option a = None | Some a;\ndef x = (So$0 1);",
+            &expect![[r"
+                Variable x : 'a
+                EnumMember Some : 'a -> option 'a
+                EnumMember None : option 'a
+                Function lt : int -> int -> bool
+                Function eq : int -> int -> bool
+                Function div : int -> int -> int
+                Function mul : int -> int -> int
+                Function sub : int -> int -> int
+                Function add : int -> int -> int
+                Snippet \x -> … : lambda
+                Snippet if … { … } else { … } : if expression
+                Snippet match … { … } : match expression
+                Keyword true
+                Keyword false
+            "]],
+        );
+        check(
+            "type option a = None | Some a;\ntype t = op$0;",
+            &expect![[r"
+                Enum option
+                Struct t : {unknown}
+                Struct int
+                Struct bool
+                Struct unit
+            "]],
+        );
+    }
+
+    #[test]
+    fn complete_type_in_arrow() {
+        check(
+            "type t = int -> $0;",
+            &expect![[r"
+                Struct t : int -> 'a
+                Struct int
+                Struct bool
+                Struct unit
+            "]],
+        );
+    }
+
+    #[test]
+    fn complete_module_item() {
+        check(
+            "def a = 1;\n$0",
+            &expect![[r"
+                Keyword def
+                Keyword type
+                Keyword open
+                Snippet def name params { … } : function definition
+            "]],
+        );
+    }
+
+    #[test]
+    fn complete_module_item_with_prefix() {
+        check(
+            "de$0\ndef a = 1;",
+            &expect![[r"
+                Keyword def
+                Keyword type
+                Keyword open
+                Snippet def name params { … } : function definition
+            "]],
+        );
+    }
+
+    #[test]
+    fn no_completions_for_new_names() {
+        check("def a = 1;\ndef $0", &expect![[""]]);
+    }
+}
@@ -0,0 +1,241 @@
+use core::{Binding, TypeDefinitionIdx};
+use parser::{SyntaxKind, SyntaxToken};
+
+use crate::semantics::{name_token, Semantics, Symbol};
+use crate::{position_to_offset, text_range_to_lsp, Document};
+
+/// Finds the binder of the identifier at the position, or the type definition of a type name.
+/// Identifiers in `open` items point to the opened module,
+/// a `.rml` file next to the current one which is not required to exist.
+#[must_use]
+pub fn get_definition(
+    doc: &Document,
+    uri: &lsp_types::Url,
+    position: lsp_types::Position,
+) -> Option<lsp_types::GotoDefinitionResponse> {
+    let offset = position_to_offset(doc.get_line_index(), position)?;
+    let sema = Semantics::new(doc);
+    let ident = sema.ident_at(offset)?;
+
+    if ident.parent()?.kind() == SyntaxKind::OPEN {
+        let module_uri = uri.join(&format!("{}.rml", ident.text())).ok()?;
+        let start = lsp_types::Position::new(0, 0);
+        return Some(lsp_types::GotoDefinitionResponse::Scalar(
+            lsp_types::Location::new(module_uri, lsp_types::Range::new(start, start)),
+        ));
+    }
+
+    let symbol = sema.resolve(&ident)?;
+    let target = match symbol {
+        Symbol::Binding(binding) => name_token(&sema.binding_node(binding)?)?,
+        Symbol::TypeDefinition(type_definition) => {
+            name_token(&sema.type_definition_node(type_definition)?)?
+        }
+        Symbol::TypeParam { .. } => sema.declaration(symbol)?,
+        Symbol::NamedType(_) => return None,
+    };
+    Some(location(doc, uri, &target))
+}
+
+/// Finds the type definition of the type name at the position.
+/// For other identifiers, follows their type annotation if it names an alias.
+#[must_use]
+pub fn get_type_definition(
+    doc: &Document,
+    uri: &lsp_types::Url,
+    position: lsp_types::Position,
+) -> Option<lsp_types::request::GotoTypeDefinitionResponse> {
+    let offset = position_to_offset(doc.get_line_index(), position)?;
+    let sema = Semantics::new(doc);
+    let ident = sema.ident_at(offset)?;
+
+    let type_definition = match sema.resolve(&ident)? {
+        Symbol::Binding(binding) => annotated_alias(&sema, binding)?,
+        Symbol::TypeDefinition(type_definition) => type_definition,
+        Symbol::TypeParam { .. } | Symbol::NamedType(_) => return None,
+    };
+    let target = name_token(&sema.type_definition_node(type_definition)?)?;
+    Some(location(doc, uri, &target))
+}
+
+/// Alias naming the whole type of the binder in its annotation.
+fn annotated_alias(sema: &Semantics, binding: Binding) -> Option<TypeDefinitionIdx> {
+    let binder = sema.binding_node(binding)?;
+    let has_params = binder
+        .children()
+        .filter(|child| child.kind() == SyntaxKind::PARAMS)
+        .any(|params| params.children().next().is_some());
+    // With parameters, the annotation is of the return type only.
+    if has_params {
+        return None;
+    }
+
+    let annotation = binder
+        .children()
+        .find(|child| child.kind() == SyntaxKind::TYPE_ANNOTATION)?;
+    // Applied types like `option int` lead to the applied definition.
+    let mut type_ident = annotation
+        .children()
+        .find(|child| matches!(child.kind(), SyntaxKind::TYPE_IDENT | SyntaxKind::TYPE_APP))?;
+    while type_ident.kind() == SyntaxKind::TYPE_APP {
+        type_ident = type_ident.first_child()?;
+    }
+    if type_ident.kind() != SyntaxKind::TYPE_IDENT {
+        return None;
+    }
+    match sema.resolve(&name_token(&type_ident)?)? {
+        Symbol::TypeDefinition(type_definition) => Some(type_definition),
+        Symbol::Binding(_) | Symbol::TypeParam { .. } | Symbol::NamedType(_) => None,
+    }
+}
+
+fn location(
+    doc: &Document,
+    uri: &lsp_types::Url,
+    target: &SyntaxToken,
+) -> lsp_types::GotoDefinitionResponse {
+    lsp_types::GotoDefinitionResponse::Scalar(lsp_types::Location::new(
+        uri.clone(),
+        text_range_to_lsp(target.text_range(), doc),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use super::{get_definition, get_type_definition};
+    use crate::fixture::cursor;
+
+    /// The cursor is placed at `$0`.
+    fn check(text: &str, type_definition: bool, expected: &Expect) {
+        let uri = lsp_types::Url::parse("file:///project/main.rml").unwrap();
+        let (doc, position) = cursor(text);
+
+        let response = if type_definition {
+            get_type_definition(&doc, &uri, position)
+        } else {
+            get_definition(&doc, &uri, position)
+        };
+        let actual = match response {
+            Some(lsp_types::GotoDefinitionResponse::Scalar(location)) => {
+                let range = location.range;
+                format!(
+                    "{} {}:{}..{}:{}",
+                    location.uri,
+                    range.start.line,
+                    range.start.character,
+                    range.end.line,
+                    range.end.character
+                )
+            }
+            Some(response) => format!("{response:?}"),
+            None => "no definition".into(),
+        };
+        expected.assert_eq(&actual);
+    }
+
+    #[test]
+    fn definition_of_top_level() {
+        check(
+            "def a = b$0; def b = 1;",
+            false,
+            &expect!["file:///project/main.rml 0:15..0:16"],
+        );
+    }
+
+    #[test]
+    fn definition_of_let() {
+        check(
+            "def f { let y = 1; y$0 }",
+            false,
+            &expect!["file:///project/main.rml 0:12..0:13"],
+        );
+    }
+
+    #[test]
+    fn definition_of_param() {
+        check(
+            "def f x (y: int) = (x y$0);",
+            false,
+            &expect!["file:///project/main.rml 0:9..0:10"],
+        );
+    }
+
+    #[test]
+    fn definition_of_lambda_param() {
+        check(
+            "def f = \\x -> x$0;",
+            false,
+            &expect!["file:///project/main.rml 0:9..0:10"],
+        );
+    }
+
+    #[test]
+    fn definition_of_type_name() {
+        check(
+            "type t = int;\ndef f (x: t$0) = x;",
+            false,
+            &expect!["file:///project/main.rml 0:5..0:6"],
+        );
+    }
+
+    #[test]
+    fn definition_of_type_param() {
+        check(
+            "type a = int;\ntype box a = | Box a$0;",
+            false,
+            &expect!["file:///project/main.rml 1:9..1:10"],
+        );
+    }
+
+    #[test]
+    fn no_definition_of_builtin() {
+        check("def f = ad$0d;", false, &expect!["no definition"]);
+    }
+
+    #[test]
+    fn definition_of_opened_module() {
+        check(
+            "open li$0st;",
+            false,
+            &expect!["file:///project/list.rml 0:0..0:0"],
+        );
+    }
+
+    #[test]
+    fn type_definition_of_annotated_binding() {
+        check(
+            "type t = int;\ndef a : t = 1;\ndef b = a$0;",
+            true,
+            &expect!["file:///project/main.rml 0:5..0:6"],
+        );
+    }
+
+    #[test]
+    fn type_definition_of_type_name() {
+        check(
+            "type t = int;\ntype u = t$0 -> t;",
+            true,
+            &expect!["file:///project/main.rml 0:5..0:6"],
+        );
+    }
+
+    #[test]
+    fn no_type_definition_of_return_type() {
+        check(
+            "type t = int;\ndef f x : t = x;\ndef b = f$0;",
+            true,
+            &expect!["no definition"],
+        );
+    }
+
+    #[test]
+    fn type_definition_of_applied_annotation() {
+        check(
+            "type option a = None | Some a;\ndef a : option int = (Some 1);\ndef b = a$0;",
+            true,
+            &expect!["file:///project/main.rml 0:5..0:11"],
+        );
+    }
+}
@@ -1,16 +1,19 @@
+use core::{Expr, ExprIdx, LowerError, MatchError, Module, PatIdx, Scopes, TypeError, TypeExprIdx};
+
 use line_index::TextRange;
-use parser::SyntaxKind;
+use parser::{nodes, AstNode, SyntaxKind, SyntaxNode};
 
-use crate::{offset_to_position, Document};
+use crate::semantics::name_token;
+use crate::{text_range_to_lsp, trimmed_range, Document};
 
 /// # Panics
 ///
 /// Panics if parser produced different number of error nodes than messages.
 #[must_use]
-pub fn get_diagnostics(doc: &Document) -> Vec<lsp_types::Diagnostic> {
+pub fn get_diagnostics(doc: &Document, uri: &lsp_types::Url) -> Vec<lsp_types::Diagnostic> {
     let mut error_idx = 0;
     let parsed = doc.parsed();
-    parsed
+    let mut diagnostics: Vec<_> = parsed
         .syntax()
         .descendants_with_tokens()
         .filter(|node| node.kind() == SyntaxKind::ERROR || node.kind() == SyntaxKind::LEXING_ERROR)
@@ -23,7 +26,30 @@ pub fn get_diagnostics(doc: &Document) -> Vec<lsp_types::Diagnostic> {
             error_idx += 1;
             syntax_error_to_diagnostic(msg, node.text_range(), doc)
         })
-        .collect()
+        .collect();
+
+    let mut module = Module::new();
+    module.lower_module(&parsed.module());
+    let scopes = Scopes::new(&module);
+    let inference = core::infer(&module);
+
+    diagnostics.extend(
+        module
+            .lower_errors()
+            .iter()
+            .filter_map(|error| lower_error_to_diagnostic(error, &module, &parsed.syntax(), doc)),
+    );
+    diagnostics.extend(
+        inference.errors().iter().filter_map(|error| {
+            type_error_to_diagnostic(error, &module, &parsed.syntax(), doc, uri)
+        }),
+    );
+    diagnostics.extend(
+        core::check_matches(&module, &scopes, &inference)
+            .iter()
+            .filter_map(|error| match_error_to_diagnostic(error, &module, &parsed.syntax(), doc)),
+    );
+    diagnostics
 }
 
 fn syntax_error_to_diagnostic(
@@ -31,12 +57,378 @@ fn syntax_error_to_diagnostic(
     range: TextRange,
     doc: &Document,
 ) -> lsp_types::Diagnostic {
-    let line_index = &doc.get_line_index();
-    let start = offset_to_position(line_index, range.start().into());
-    let end = offset_to_position(line_index, range.end().into());
-    let range = lsp_types::Range::new(start, end);
+    let range = text_range_to_lsp(range, doc);
 
     let mut diagnostic = lsp_types::Diagnostic::new_simple(range, message.to_string());
     diagnostic.source = Some("Camlot".into());
     diagnostic
 }
+
+fn lower_error_to_diagnostic(
+    error: &LowerError,
+    module: &Module,
+    root: &SyntaxNode,
+    doc: &Document,
+) -> Option<lsp_types::Diagnostic> {
+    let (range, code) = match error {
+        LowerError::IntOutOfRange { expr } => {
+            (expr_range(module, root, *expr)?, "int-out-of-range")
+        }
+        LowerError::IntPatOutOfRange { pat } => {
+            (pat_range(module, root, *pat)?, "int-out-of-range")
+        }
+    };
+
+    Some(lsp_types::Diagnostic {
+        range: text_range_to_lsp(range, doc),
+        severity: Some(lsp_types::DiagnosticSeverity::ERROR),
+        code: Some(lsp_types::NumberOrString::String(code.into())),
+        source: Some("Camlot".into()),
+        message: error.message(),
+        ..Default::default()
+    })
+}
+
+fn type_error_to_diagnostic(
+    error: &TypeError,
+    module: &Module,
+    root: &SyntaxNode,
+    doc: &Document,
+    uri: &lsp_types::Url,
+) -> Option<lsp_types::Diagnostic> {
+    let range = if let Some(expr) = error.expr() {
+        expr_range(module, root, value_expr(module, expr))?
+    } else if let Some(pat) = error.pat() {
+        pat_range(module, root, pat)?
+    } else if let Some(type_expr) = error.type_expr() {
+        type_expr_range(module, root, type_expr)?
+    } else {
+        duplicate_range(error, module, root)?
+    };
+
+    let code = match error {
+        TypeError::Mismatch { .. } | TypeError::PatMismatch { .. } => "type-mismatch",
+        TypeError::UnboundVariable { .. } => "unbound-variable",
+        TypeError::InfiniteType { .. } => "infinite-type",
+        TypeError::UnboundConstructor { .. } => "unbound-constructor",
+        TypeError::ConstructorArity { .. } => "constructor-arity",
+        TypeError::TypeArity { .. } => "type-arity",
+        TypeError::UnboundType { .. } => "unbound-type",
+        TypeError::UnboundTypeVar { .. } => "unbound-type-variable",
+        TypeError::DuplicateDefinition { .. } | TypeError::DuplicateTypeDefinition { .. } => {
+            "duplicate-definition"
+        }
+    };
+
+    let related_information = match error {
+        TypeError::Mismatch {
+            annotation: Some(annotation),
+            ..
+        } => module
+            .source_map()
+            .type_expr_syntax(*annotation)
+            .map(|annotation| {
+                let range = trimmed_range(&annotation.to_node(root));
+                vec![lsp_types::DiagnosticRelatedInformation {
+                    location: lsp_types::Location::new(uri.clone(), text_range_to_lsp(range, doc)),
+                    message: "expected due to this annotation".into(),
+                }]
+            }),
+        _ => None,
+    };
+
+    Some(lsp_types::Diagnostic {
+        range: text_range_to_lsp(range, doc),
+        severity: Some(lsp_types::DiagnosticSeverity::ERROR),
+        code: Some(lsp_types::NumberOrString::String(code.into())),
+        source: Some("Camlot".into()),
+        message: error.message(module),
+        related_information,
+        ..Default::default()
+    })
+}
+
+/// Missing patterns are reported at the matched expression,
+/// unreachable arms are faded out as unnecessary code.
+fn match_error_to_diagnostic(
+    error: &MatchError,
+    module: &Module,
+    root: &SyntaxNode,
+    doc: &Document,
+) -> Option<lsp_types::Diagnostic> {
+    let (range, code, severity, tags) = match error {
+        MatchError::NonExhaustive { expr, .. } => {
+            let Expr::MatchExpr { scrutinee, .. } = module.get_expr(*expr) else {
+                return None;
+            };
+            let range = expr_range(module, root, *scrutinee)?;
+            (
+                range,
+                "non-exhaustive-match",
+                lsp_types::DiagnosticSeverity::ERROR,
+                None,
+            )
+        }
+        MatchError::RedundantArm { pat } => (
+            pat_range(module, root, *pat)?,
+            "unreachable-arm",
+            lsp_types::DiagnosticSeverity::WARNING,
+            Some(vec![lsp_types::DiagnosticTag::UNNECESSARY]),
+        ),
+    };
+
+    Some(lsp_types::Diagnostic {
+        range: text_range_to_lsp(range, doc),
+        severity: Some(severity),
+        code: Some(lsp_types::NumberOrString::String(code.into())),
+        source: Some("Camlot".into()),
+        message: error.message(),
+        tags,
+        ..Default::default()
+    })
+}
+
+/// Blocks are lowered to chains of let expressions,
+/// but their value comes from the trailing expression.
+fn value_expr(module: &Module, mut expr: ExprIdx) -> ExprIdx {
+    while let Expr::LetExpr(let_expr) = module.get_expr(expr) {
+        if module.source_map().expr_syntax(let_expr.body).is_none() {
+            break;
+        }
+        expr = let_expr.body;
+    }
+    expr
+}
+
+fn expr_range(module: &Module, root: &SyntaxNode, expr: ExprIdx) -> Option<TextRange> {
+    let node = module.source_map().expr_syntax(expr)?.to_node(root);
+
+    // Functions defined with parameters are lowered from the whole definition,
+    // which is too broad to be highlighted.
+    if let Some(ident) = nodes::Definition::cast(node.clone()).and_then(|def| def.ident_lit()) {
+        return Some(ident.text_range());
+    }
+    Some(trimmed_range(&node))
+}
+
+fn pat_range(module: &Module, root: &SyntaxNode, pat: PatIdx) -> Option<TextRange> {
+    let node = module.source_map().pat_syntax(pat)?.to_node(root);
+    Some(trimmed_range(&node))
+}
+
+fn type_expr_range(
+    module: &Module,
+    root: &SyntaxNode,
+    type_expr: TypeExprIdx,
+) -> Option<TextRange> {
+    let node = module
+        .source_map()
+        .type_expr_syntax(type_expr)?
+        .to_node(root);
+    Some(trimmed_range(&node))
+}
+
+/// Duplicates are reported at their name.
+fn duplicate_range(error: &TypeError, module: &Module, root: &SyntaxNode) -> Option<TextRange> {
+    let source_map = module.source_map();
+    let ptr = match error {
+        TypeError::DuplicateDefinition { binding, .. } => source_map.binding_syntax(*binding),
+        TypeError::DuplicateTypeDefinition {
+            type_definition, ..
+        } => source_map.type_definition_syntax(*type_definition),
+        _ => None,
+    }?;
+    Some(name_token(&ptr.to_node(root))?.text_range())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write;
+
+    use expect_test::{expect, Expect};
+
+    use super::get_diagnostics;
+    use crate::fixture::format_range;
+    use crate::Document;
+
+    fn check(text: &str, expected: &Expect) {
+        let uri = lsp_types::Url::parse("file:///test.rml").unwrap();
+        let doc = Document::new(text.to_string());
+        let mut actual = String::new();
+        for diagnostic in get_diagnostics(&doc, &uri) {
+            let code = match diagnostic.code {
+                Some(lsp_types::NumberOrString::String(code)) => code,
+                _ => "syntax".into(),
+            };
+            writeln!(
+                actual,
+                "{} {code}: {}",
+                format_range(diagnostic.range),
+                diagnostic.message
+            )
+            .unwrap();
+            for info in diagnostic.related_information.unwrap_or_default() {
+                writeln!(
+                    actual,
+                    "  {} {}",
+                    format_range(info.location.range),
+                    info.message
+                )
+                .unwrap();
+            }
+        }
+        expected.assert_eq(&actual);
+    }
+
+    #[test]
+    fn no_diagnostics_for_correct_module() {
+        check("def id x = x; def a = (id 42);", &expect![""]);
+    }
+
+    #[test]
+    fn no_diagnostics_for_prelude_functions() {
+        check(
+            "def b (x: int) : int {\n    let y = (add x 1);\n    y * 2\n}",
+            &expect![""],
+        );
+    }
+
+    #[test]
+    fn syntax_error() {
+        check(
+            "def f =",
+            &expect![[r"
+                0:7..0:7 syntax: Expected expression
+                0:7..0:7 syntax: Expected SEMICOLON but found EOF
+            "]],
+        );
+    }
+
+    #[test]
+    fn int_literal_out_of_range() {
+        check(
+            "def a = 99999999999999999999;\ndef b = 9223372036854775807;",
+            &expect![[r"
+                0:8..0:28 int-out-of-range: integer literal out of range
+            "]],
+        );
+    }
+
+    #[test]
+    fn int_pattern_out_of_range() {
+        check(
+            "def f x = match x { 99999999999999999999 => 1, _ => 0 };",
+            &expect![[r"
+                0:20..0:40 int-out-of-range: integer literal out of range
+            "]],
+        );
+    }
+
+    #[test]
+    fn mismatch_with_annotation() {
+        check(
+            "def f : int -> int = 1;",
+            &expect![[r"
+                0:21..0:22 type-mismatch: expected `int -> int`, found `int`
+                  0:8..0:18 expected due to this annotation
+            "]],
+        );
+    }
+
+    #[test]
+    fn mismatch_in_block_points_at_tail_expr() {
+        check(
+            "def f x : int -> int {\n    let y = 1;\n    y\n}",
+            &expect![[r"
+                2:4..2:5 type-mismatch: expected `int -> int`, found `int`
+                  0:10..0:20 expected due to this annotation
+            "]],
+        );
+    }
+
+    #[test]
+    fn mismatch_in_application() {
+        check(
+            "def f (x: int) = x;\ndef g = (f f);",
+            &expect![[r"
+                1:11..1:12 type-mismatch: expected `int`, found `int -> int`
+            "]],
+        );
+    }
+
+    #[test]
+    fn unbound_variable() {
+        check(
+            "def f = (g 1);",
+            &expect![[r"
+                0:9..0:10 unbound-variable: unbound variable `g`
+            "]],
+        );
+    }
+
+    #[test]
+    fn infinite_type() {
+        check(
+            "def f x = (x x);",
+            &expect![[r"
+                0:11..0:12 infinite-type: infinite type: `'a` occurs in `'a -> 'b`
+            "]],
+        );
+    }
+
+    #[test]
+    fn pattern_errors() {
+        check(
+            "type option a = None | Some a;\ndef f o = match o { Some => 1, Other x => 2, None => true };",
+            &expect![[r"
+                1:20..1:24 constructor-arity: constructor `Some` has 1 field, found 0
+                1:31..1:38 unbound-constructor: unbound constructor `Other`
+                1:53..1:57 type-mismatch: expected `int`, found `bool`
+            "]],
+        );
+    }
+
+    #[test]
+    fn non_exhaustive_match() {
+        check(
+            "type option a = None | Some a;\ndef f o = match o {\n    Some true => 1,\n};",
+            &expect![[r"
+                1:16..1:17 non-exhaustive-match: non-exhaustive match: missing patterns `None`, `Some false`
+            "]],
+        );
+    }
+
+    #[test]
+    fn unreachable_arm() {
+        check(
+            "def f b = match b {\n    true => 1,\n    _ => 2,\n    false => 3,\n};",
+            &expect![[r"
+                3:4..3:9 unreachable-arm: unreachable match arm
+            "]],
+        );
+    }
+
+    #[test]
+    fn type_expr_errors() {
+        check(
+            "type option a = None | Some a;\ntype t = 'b;\ndef f (x: option int int) = x;\ndef g (x: itn) = x;",
+            &expect![[r"
+                1:9..1:11 unbound-type-variable: unbound type variable `'b`
+                2:10..2:24 type-arity: expected 1 type argument, found 2
+                3:10..3:13 unbound-type: unbound type `itn`
+            "]],
+        );
+    }
+
+    #[test]
+    fn duplicate_definitions() {
+        check(
+            "type t = int;\ntype t = bool;\ntype option a = None | Some a;\ndef None = 1;\ndef x = 1;\ndef x y = 2;",
+            &expect![[r"
+                3:4..3:8 duplicate-definition: `None` is already defined
+                5:4..5:5 duplicate-definition: `x` is already defined
+                1:5..1:6 duplicate-definition: type `t` is already defined
+            "]],
+        );
+    }
+}
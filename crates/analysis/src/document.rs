@@ -1,26 +1,146 @@
 pub struct Document {
     line_index: line_index::LineIndex,
     text: String,
+    parse: parser::Parse,
 }
 
 impl Document {
     #[must_use]
     pub fn new(text: String) -> Document {
         let line_index = line_index::LineIndex::new(&text);
-        Document { line_index, text }
+        let parse = parser::parse(&text);
+        Document {
+            line_index,
+            text,
+            parse,
+        }
     }
 
     pub fn update(&mut self, text: String) {
+        self.parse = parser::parse(&text);
         self.text = text;
         self.line_index = line_index::LineIndex::new(&self.text);
     }
 
+    /// Applies a change sent by the editor, replacing the whole text if it has no range.
+    /// Positions past the end of their line or of the document are moved back to it.
+    /// Only the part of the syntax tree touched by the change is parsed again.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the document is larger than 4GiB.
+    pub fn apply_change(&mut self, change: lsp_types::TextDocumentContentChangeEvent) {
+        let Some(range) = change.range else {
+            self.update(change.text);
+            return;
+        };
+        let start = self.clamped_offset(range.start);
+        let end = self.clamped_offset(range.end).max(start);
+        let delete = line_index::TextRange::new(
+            line_index::TextSize::try_from(start).expect("document larger than 4GiB"),
+            line_index::TextSize::try_from(end).expect("document larger than 4GiB"),
+        );
+        self.parse = self.parse.reparse(delete, &change.text);
+        self.text.replace_range(start..end, &change.text);
+        self.line_index = line_index::LineIndex::new(&self.text);
+    }
+
     #[must_use]
-    pub fn parsed(&self) -> parser::Parse {
-        parser::parse(&self.text)
+    pub fn parsed(&self) -> &parser::Parse {
+        &self.parse
+    }
+
+    pub(crate) fn text(&self) -> &str {
+        &self.text
     }
 
     pub(crate) fn get_line_index(&self) -> &line_index::LineIndex {
         &self.line_index
     }
+
+    fn clamped_offset(&self, position: lsp_types::Position) -> usize {
+        let line_start = line_index::LineCol {
+            line: position.line,
+            col: 0,
+        };
+        let Some(line_start) = self.line_index.offset(line_start) else {
+            return self.text.len();
+        };
+        let line_start = usize::from(line_start).min(self.text.len());
+        let line_end = self.text[line_start..]
+            .find('\n')
+            .map_or(self.text.len(), |end| line_start + end);
+
+        let column = line_index::WideLineCol {
+            line: position.line,
+            col: position.character,
+        };
+        let column = self
+            .line_index
+            .to_utf8(line_index::WideEncoding::Utf16, column)
+            .map_or(usize::MAX, |column| column.col as usize);
+        let mut offset = line_start.saturating_add(column).min(line_end);
+        // A position inside a surrogate pair points into the middle of a character.
+        while !self.text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        offset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Document;
+
+    fn change(
+        (start_line, start_character): (u32, u32),
+        (end_line, end_character): (u32, u32),
+        text: &str,
+    ) -> lsp_types::TextDocumentContentChangeEvent {
+        lsp_types::TextDocumentContentChangeEvent {
+            range: Some(lsp_types::Range::new(
+                lsp_types::Position::new(start_line, start_character),
+                lsp_types::Position::new(end_line, end_character),
+            )),
+            range_length: None,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn changes_are_applied_in_order() {
+        let mut doc = Document::new("def a = 1;\ndef b = 2;\n".to_string());
+        doc.apply_change(change((1, 8), (1, 9), "(add a 3)"));
+        doc.apply_change(change((0, 4), (0, 5), "first"));
+        doc.apply_change(change((1, 0), (2, 0), ""));
+        doc.apply_change(change((0, 14), (0, 14), "\ndef c = a;"));
+        assert_eq!(doc.text(), "def first = 1;\ndef c = a;\n");
+        assert_eq!(
+            doc.parsed().debug_tree(),
+            parser::parse(doc.text()).debug_tree()
+        );
+    }
+
+    #[test]
+    fn columns_are_utf16() {
+        let mut doc = Document::new("def s = \"🦀\";\ndef t = s;".to_string());
+        doc.apply_change(change((0, 11), (0, 11), "ą"));
+        doc.apply_change(change((0, 9), (0, 9), "!"));
+        assert_eq!(doc.text(), "def s = \"!🦀ą\";\ndef t = s;");
+    }
+
+    #[test]
+    fn out_of_range_positions_are_clamped() {
+        let mut doc = Document::new("def a = 1;\ndef b = 2;".to_string());
+        doc.apply_change(change((0, 100), (0, 100), " # one"));
+        doc.apply_change(change((7, 0), (9, 0), "\n"));
+        assert_eq!(doc.text(), "def a = 1; # one\ndef b = 2;\n");
+
+        doc.apply_change(lsp_types::TextDocumentContentChangeEvent {
+            range: None,
+            range_length: None,
+            text: "def c = 3;".to_string(),
+        });
+        assert_eq!(doc.text(), "def c = 3;");
+    }
 }
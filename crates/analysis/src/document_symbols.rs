@@ -0,0 +1,196 @@
+use parser::{SyntaxKind, SyntaxNode};
+
+use crate::semantics::name_token;
+use crate::{text_range_to_lsp, trimmed_range, Document};
+
+/// Outline of the module: its items, with `let` bindings nested in the bodies they belong to
+/// and constructors in their types.
+/// Built from the syntax tree alone, so that broken files still have one.
+#[must_use]
+pub fn get_document_symbols(doc: &Document) -> Vec<lsp_types::DocumentSymbol> {
+    symbols_in(doc, &doc.parsed().syntax())
+}
+
+/// Symbols of the outermost items below the node, looking through errors and expressions.
+fn symbols_in(doc: &Document, node: &SyntaxNode) -> Vec<lsp_types::DocumentSymbol> {
+    let mut symbols = Vec::new();
+    for child in node.children() {
+        match symbol(doc, &child) {
+            Some(symbol) => symbols.push(symbol),
+            None => symbols.extend(symbols_in(doc, &child)),
+        }
+    }
+    symbols
+}
+
+#[allow(deprecated)]
+fn symbol(doc: &Document, node: &SyntaxNode) -> Option<lsp_types::DocumentSymbol> {
+    let has_params = || {
+        node.children()
+            .filter(|child| child.kind() == SyntaxKind::PARAMS)
+            .any(|params| params.children().next().is_some())
+    };
+    let has_variants = || {
+        node.children()
+            .any(|child| child.kind() == SyntaxKind::VARIANTS)
+    };
+    let kind = match node.kind() {
+        SyntaxKind::DEFINITION | SyntaxKind::LET_STMT if has_params() => {
+            lsp_types::SymbolKind::FUNCTION
+        }
+        SyntaxKind::DEFINITION => lsp_types::SymbolKind::CONSTANT,
+        SyntaxKind::LET_STMT => lsp_types::SymbolKind::VARIABLE,
+        SyntaxKind::TYPE_DEFINITION if has_variants() => lsp_types::SymbolKind::ENUM,
+        SyntaxKind::TYPE_DEFINITION => lsp_types::SymbolKind::STRUCT,
+        SyntaxKind::VARIANT => lsp_types::SymbolKind::ENUM_MEMBER,
+        SyntaxKind::OPEN => lsp_types::SymbolKind::MODULE,
+        _ => return None,
+    };
+    let name = name_token(node)?;
+
+    let children = match node.kind() {
+        SyntaxKind::DEFINITION | SyntaxKind::LET_STMT | SyntaxKind::TYPE_DEFINITION => {
+            symbols_in(doc, node)
+        }
+        _ => Vec::new(),
+    };
+    Some(lsp_types::DocumentSymbol {
+        name: name.text().to_string(),
+        detail: detail(node),
+        kind,
+        tags: None,
+        deprecated: None,
+        range: text_range_to_lsp(trimmed_range(node), doc),
+        selection_range: text_range_to_lsp(name.text_range(), doc),
+        children: Some(children),
+    })
+}
+
+/// Source of the parameters and the type annotation, of the aliased type
+/// or of the constructor fields, with whitespace collapsed.
+fn detail(node: &SyntaxNode) -> Option<String> {
+    let text: Vec<_> = node
+        .children()
+        .filter(|child| {
+            matches!(
+                child.kind(),
+                SyntaxKind::PARAMS
+                    | SyntaxKind::TYPE_ANNOTATION
+                    | SyntaxKind::TYPE_IDENT
+                    | SyntaxKind::TYPE_ARROW
+                    | SyntaxKind::TYPE_PAREN
+                    | SyntaxKind::TYPE_APP
+                    | SyntaxKind::TYPE_VAR
+            )
+        })
+        .map(|child| child.text().to_string())
+        .collect();
+    let detail = text
+        .join(" ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!detail.is_empty()).then_some(detail)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write;
+
+    use expect_test::{expect, Expect};
+
+    use super::get_document_symbols;
+    use crate::Document;
+
+    fn check(text: &str, expected: &Expect) {
+        fn render(buf: &mut String, symbols: &[lsp_types::DocumentSymbol], depth: usize) {
+            for symbol in symbols {
+                let range = symbol.range;
+                let selection = symbol.selection_range;
+                write!(
+                    buf,
+                    "{}{:?} {} {}:{}..{}:{} (name {}:{}..{}:{})",
+                    "  ".repeat(depth),
+                    symbol.kind,
+                    symbol.name,
+                    range.start.line,
+                    range.start.character,
+                    range.end.line,
+                    range.end.character,
+                    selection.start.line,
+                    selection.start.character,
+                    selection.end.line,
+                    selection.end.character,
+                )
+                .unwrap();
+                if let Some(detail) = &symbol.detail {
+                    write!(buf, " `{detail}`").unwrap();
+                }
+                buf.push('\n');
+                render(
+                    buf,
+                    symbol.children.as_deref().unwrap_or_default(),
+                    depth + 1,
+                );
+            }
+        }
+
+        let doc = Document::new(text.to_string());
+        let mut actual = String::new();
+        render(&mut actual, &get_document_symbols(&doc), 0);
+        expected.assert_eq(&actual);
+    }
+
+    #[test]
+    fn module_items() {
+        check(
+            "open list;\ntype fn = int -> (int -> int);\ndef a = 1;\ndef f x (y: int) : int = (add x y);",
+            &expect![[r"
+                Module list 0:0..0:10 (name 0:5..0:9)
+                Struct fn 1:0..1:30 (name 1:5..1:7) `int -> (int -> int)`
+                Constant a 2:0..2:10 (name 2:4..2:5)
+                Function f 3:0..3:35 (name 3:4..3:5) `x (y: int) : int`
+            "]],
+        );
+    }
+
+    #[test]
+    fn nested_lets() {
+        check(
+            "def f {\n    let g x = { let y = x; y };\n    let z = \\w -> { let v = w; v };\n    (g z)\n}",
+            &expect![[r"
+                Constant f 0:0..4:1 (name 0:4..0:5)
+                  Function g 1:4..1:31 (name 1:8..1:9) `x`
+                    Variable y 1:16..1:26 (name 1:20..1:21)
+                  Variable z 2:4..2:35 (name 2:8..2:9)
+                    Variable v 2:20..2:30 (name 2:24..2:25)
+            "]],
+        );
+    }
+
+    #[test]
+    fn sum_types() {
+        check(
+            "type shape = | Circle int\n  | Rect int (int -> int)\n  | Empty;",
+            &expect![[r"
+                Enum shape 0:0..2:10 (name 0:5..0:10)
+                  EnumMember Circle 0:13..0:25 (name 0:15..0:21) `int`
+                  EnumMember Rect 1:2..1:25 (name 1:4..1:8) `int (int -> int)`
+                  EnumMember Empty 2:2..2:9 (name 2:4..2:9)
+            "]],
+        );
+    }
+
+    #[test]
+    fn broken_file() {
+        check(
+            "def f x = ;\ntype t = ;\ndef g { let y = ; 1 }\ndef = 2;",
+            &expect![[r"
+                Function f 0:0..0:11 (name 0:4..0:5) `x`
+                Struct t 1:0..1:10 (name 1:5..1:6)
+                Constant g 2:0..2:21 (name 2:4..2:5)
+                  Variable y 2:8..2:17 (name 2:12..2:13)
+            "]],
+        );
+    }
+}
@@ -0,0 +1,142 @@
+use parser::{SyntaxKind, SyntaxNode, SyntaxToken};
+
+use crate::{offset_to_position, trimmed_range, Document};
+
+/// Folds for blocks, definitions spanning several lines and runs of line comments.
+/// Ranges cover whole lines, keeping visible the line closing a block.
+#[must_use]
+pub fn get_folding_ranges(doc: &Document) -> Vec<lsp_types::FoldingRange> {
+    let root = doc.parsed().syntax();
+    let line =
+        |offset: line_index::TextSize| offset_to_position(doc.get_line_index(), offset.into()).line;
+
+    let mut ranges = Vec::new();
+    for node in root.descendants() {
+        match node.kind() {
+            SyntaxKind::DEFINITION => {
+                let range = trimmed_range(&node);
+                ranges.push(fold(line(range.start()), line(range.end()), None));
+            }
+            SyntaxKind::BLOCK_EXPR => {
+                let range = trimmed_range(&node);
+                let closed =
+                    last_token(&node).is_some_and(|token| token.kind() == SyntaxKind::R_BRACE);
+                let end = line(range.end());
+                ranges.push(fold(
+                    line(range.start()),
+                    if closed { end.saturating_sub(1) } else { end },
+                    None,
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    let comments = root
+        .descendants_with_tokens()
+        .filter_map(parser::SyntaxElement::into_token)
+        .filter(|token| token.kind() == SyntaxKind::COMMENT && is_on_own_line(token));
+    let mut run: Option<(u32, u32)> = None;
+    for comment in comments {
+        let comment_line = line(comment.text_range().start());
+        run = match run {
+            Some((start, end)) if end + 1 == comment_line => Some((start, comment_line)),
+            Some((start, end)) => {
+                ranges.push(fold(start, end, Some(lsp_types::FoldingRangeKind::Comment)));
+                Some((comment_line, comment_line))
+            }
+            None => Some((comment_line, comment_line)),
+        };
+    }
+    if let Some((start, end)) = run {
+        ranges.push(fold(start, end, Some(lsp_types::FoldingRangeKind::Comment)));
+    }
+
+    ranges.retain(|range| range.start_line < range.end_line);
+    // A block opening on the first line of its definition is folded instead of it,
+    // leaving the closing brace visible.
+    ranges.sort_by_key(|range| (range.start_line, range.end_line));
+    ranges.dedup_by_key(|range| range.start_line);
+    ranges
+}
+
+fn fold(
+    start_line: u32,
+    end_line: u32,
+    kind: Option<lsp_types::FoldingRangeKind>,
+) -> lsp_types::FoldingRange {
+    lsp_types::FoldingRange {
+        start_line,
+        start_character: None,
+        end_line,
+        end_character: None,
+        kind,
+        collapsed_text: None,
+    }
+}
+
+fn last_token(node: &SyntaxNode) -> Option<SyntaxToken> {
+    std::iter::successors(node.last_token(), SyntaxToken::prev_token)
+        .find(|token| !token.kind().is_trivial())
+}
+
+/// Whether only whitespace precedes the comment on its line.
+fn is_on_own_line(comment: &SyntaxToken) -> bool {
+    let previous = comment.prev_token();
+    match previous {
+        None => true,
+        Some(token) if token.kind() == SyntaxKind::COMMENT => true,
+        Some(token) if token.kind() == SyntaxKind::WHITESPACE => {
+            token.text().contains('\n')
+                || token
+                    .prev_token()
+                    .is_none_or(|token| token.kind() == SyntaxKind::COMMENT)
+        }
+        Some(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write;
+
+    use expect_test::{expect, Expect};
+
+    use super::get_folding_ranges;
+    use crate::Document;
+
+    fn check(text: &str, expected: &Expect) {
+        let doc = Document::new(text.to_string());
+        let mut actual = String::new();
+        for range in get_folding_ranges(&doc) {
+            let kind = range
+                .kind
+                .map_or(String::new(), |kind| format!(" {kind:?}"));
+            writeln!(actual, "{}..{}{kind}", range.start_line, range.end_line).unwrap();
+        }
+        expected.assert_eq(&actual);
+    }
+
+    #[test]
+    fn blocks_and_definitions() {
+        check(
+            "def a = 1;\ndef f {\n    let g = {\n        1\n    };\n    g\n}\ndef h =\n    (add\n        1\n        2);",
+            &expect![[r"
+                1..5
+                2..3
+                7..10
+            "]],
+        );
+    }
+
+    #[test]
+    fn comment_runs() {
+        check(
+            "# first\n# second\ndef a = 1; # trailing\n# third\n\n# fourth\n# fifth\n",
+            &expect![[r"
+                0..1 Comment
+                5..6 Comment
+            "]],
+        );
+    }
+}
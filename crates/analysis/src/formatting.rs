@@ -0,0 +1,106 @@
+use line_index::{TextRange, TextSize};
+
+use crate::{position_to_offset, text_range_to_lsp, Document};
+
+/// Formats the whole document, `None` if it has syntax errors.
+#[must_use]
+pub fn get_formatting(doc: &Document) -> Option<Vec<lsp_types::TextEdit>> {
+    let formatted = formatter::format(doc.text())?;
+    if formatted == doc.text() {
+        return Some(Vec::new());
+    }
+    let range = TextRange::up_to(TextSize::of(doc.text()));
+    Some(vec![lsp_types::TextEdit::new(
+        text_range_to_lsp(range, doc),
+        formatted,
+    )])
+}
+
+/// Formats the items touching the range, `None` if the document has syntax errors.
+#[must_use]
+pub fn get_range_formatting(
+    doc: &Document,
+    range: lsp_types::Range,
+) -> Option<Vec<lsp_types::TextEdit>> {
+    let line_index = doc.get_line_index();
+    let start = position_to_offset(line_index, range.start)?;
+    let end = position_to_offset(line_index, range.end)?;
+    let range = TextRange::new(TextSize::new(start), TextSize::new(end));
+
+    let Some((range, formatted)) = formatter::format_range(doc.text(), range) else {
+        return formatter::format(doc.text()).map(|_| Vec::new());
+    };
+    if doc.text()[range] == formatted {
+        return Some(Vec::new());
+    }
+    Some(vec![lsp_types::TextEdit::new(
+        text_range_to_lsp(range, doc),
+        formatted,
+    )])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write;
+
+    use expect_test::{expect, Expect};
+
+    use super::{get_formatting, get_range_formatting};
+    use crate::fixture::format_range;
+    use crate::Document;
+
+    fn render(edits: Option<Vec<lsp_types::TextEdit>>) -> String {
+        let Some(edits) = edits else {
+            return "not formatted".into();
+        };
+        let mut actual = String::new();
+        for edit in edits {
+            writeln!(actual, "{} {:?}", format_range(edit.range), edit.new_text).unwrap();
+        }
+        actual
+    }
+
+    fn check_range(text: &str, expected: &Expect) {
+        let start = text.find("$0").unwrap();
+        let text = text.replacen("$0", "", 1);
+        let end = text.find("$0").unwrap();
+        let text = text.replacen("$0", "", 1);
+        let doc = Document::new(text);
+        let position = |offset: usize| {
+            crate::offset_to_position(doc.get_line_index(), offset.try_into().unwrap())
+        };
+        let range = lsp_types::Range::new(position(start), position(end));
+        expected.assert_eq(&render(get_range_formatting(&doc, range)));
+    }
+
+    #[test]
+    fn whole_document() {
+        let doc = Document::new("def a=1;\ndef b {a}".into());
+        expect![[r#"
+            0:0..1:9 "def a = 1;\ndef b {\n    a\n}\n"
+        "#]]
+        .assert_eq(&render(get_formatting(&doc)));
+    }
+
+    #[test]
+    fn formatted_document() {
+        let doc = Document::new("def a = 1;\n".into());
+        expect![""].assert_eq(&render(get_formatting(&doc)));
+    }
+
+    #[test]
+    fn document_with_errors() {
+        let doc = Document::new("def a = ;".into());
+        expect!["not formatted"].assert_eq(&render(get_formatting(&doc)));
+    }
+
+    #[test]
+    fn selected_items() {
+        check_range(
+            "def a=1;\ndef b=$02;\ndef c=$03;\ndef d=4;",
+            &expect![[r#"
+                1:0..2:8 "def b = 2;\ndef c = 3;"
+            "#]],
+        );
+    }
+}
@@ -0,0 +1,389 @@
+use std::fmt::Write;
+
+use core::{Binding, TypeDefinitionBody, TypeDefinitionIdx, TypeExpr, TypePrinter};
+use parser::{SyntaxKind, SyntaxNode};
+
+use crate::semantics::{Semantics, Symbol};
+use crate::{position_to_offset, text_range_to_lsp, Document};
+
+/// Describes the identifier at the position: its type, where it is bound
+/// and the aliases used in its annotations.
+#[must_use]
+pub fn get_hover(doc: &Document, position: lsp_types::Position) -> Option<lsp_types::Hover> {
+    let offset = position_to_offset(doc.get_line_index(), position)?;
+    let sema = Semantics::new(doc);
+    let ident = sema.ident_at(offset)?;
+
+    let contents = match sema.resolve(&ident)? {
+        Symbol::Binding(binding) => binding_hover(&sema, binding),
+        Symbol::TypeDefinition(type_definition) => {
+            code_block(&type_definition_signature(&sema, type_definition))
+        }
+        Symbol::TypeParam {
+            type_definition,
+            index,
+        } => {
+            let name = sema.module.get_type_definition(type_definition).params[index];
+            format!(
+                "{}\n---\nType parameter",
+                code_block(sema.module.lookup_name(name))
+            )
+        }
+        Symbol::NamedType(name) => code_block(&format!("type {}", sema.module.lookup_name(name))),
+    };
+
+    Some(lsp_types::Hover {
+        contents: lsp_types::HoverContents::Markup(lsp_types::MarkupContent {
+            kind: lsp_types::MarkupKind::Markdown,
+            value: contents,
+        }),
+        range: Some(text_range_to_lsp(ident.text_range(), doc)),
+    })
+}
+
+fn binding_hover(sema: &Semantics, binding: Binding) -> String {
+    let name = sema.binding_name(binding);
+    let typ = sema.binding_scheme(binding).map_or_else(
+        || "{unknown}".into(),
+        |scheme| TypePrinter::new(&sema.module).print_scheme(&scheme),
+    );
+    let (keyword, source) = match binding {
+        Binding::Builtin(_) => ("", "Builtin function"),
+        Binding::Definition(_) => ("def ", "Top-level definition"),
+        Binding::Constructor(_) => ("", "Constructor"),
+        Binding::Let(_) => ("let ", "Let binding"),
+        Binding::Param { .. } => ("", "Parameter"),
+        Binding::Pat(_) => ("", "Pattern binding"),
+    };
+
+    let mut signature = format!("{keyword}{name} : {typ}");
+    if let Binding::Constructor(constructor) = binding {
+        let parent = sema.module.get_constructor(constructor).parent;
+        write!(signature, "\n{}", type_definition_signature(sema, parent)).unwrap();
+    }
+    if let Some(node) = sema.binding_node(binding) {
+        for type_definition in aliases_in_annotations(sema, &node) {
+            write!(
+                signature,
+                "\n{}",
+                type_definition_signature(sema, type_definition)
+            )
+            .unwrap();
+        }
+    }
+    format!("{}\n---\n{source}", code_block(&signature))
+}
+
+/// Aliases named in the parameters and the type annotation of the binder,
+/// but not in its body.
+fn aliases_in_annotations(sema: &Semantics, binder: &SyntaxNode) -> Vec<TypeDefinitionIdx> {
+    let mut aliases = Vec::new();
+    let annotations = binder.children().filter(|child| {
+        matches!(
+            child.kind(),
+            SyntaxKind::PARAMS | SyntaxKind::TYPE_ANNOTATION
+        )
+    });
+    for annotation in annotations {
+        let idents = annotation
+            .descendants_with_tokens()
+            .filter_map(parser::SyntaxElement::into_token)
+            .filter(|token| token.kind() == SyntaxKind::IDENT);
+        for ident in idents {
+            if let Some(Symbol::TypeDefinition(idx)) = sema.resolve(&ident) {
+                if !aliases.contains(&idx) {
+                    aliases.push(idx);
+                }
+            }
+        }
+    }
+    aliases
+}
+
+/// Sum types and parameterized aliases are shown as declared,
+/// other aliases with the type they expand to.
+fn type_definition_signature(sema: &Semantics, type_definition: TypeDefinitionIdx) -> String {
+    let module = &sema.module;
+    let definition = module.get_type_definition(type_definition);
+    let mut signature = format!("type {}", module.lookup_name(definition.name));
+    for param in &definition.params {
+        write!(signature, " {}", module.lookup_name(*param)).unwrap();
+    }
+    match &definition.defn {
+        TypeDefinitionBody::Sum(constructors) => {
+            for (index, constructor) in constructors.iter().enumerate() {
+                let constructor = module.get_constructor(*constructor);
+                let separator = if index == 0 { " =" } else { " |" };
+                write!(
+                    signature,
+                    "{separator} {}",
+                    module.lookup_name(constructor.name)
+                )
+                .unwrap();
+                for field in &constructor.fields {
+                    match module.get_type_expr(*field) {
+                        TypeExpr::TypeArrow { .. } | TypeExpr::TypeApp { .. } => {
+                            write!(signature, " ({})", module.debug_type_expr(*field)).unwrap();
+                        }
+                        _ => write!(signature, " {}", module.debug_type_expr(*field)).unwrap(),
+                    }
+                }
+            }
+        }
+        // The expansion of a parameterized alias depends on its arguments.
+        TypeDefinitionBody::Alias(body) if !definition.params.is_empty() => {
+            write!(signature, " = {}", module.debug_type_expr(*body)).unwrap();
+        }
+        TypeDefinitionBody::Alias(_) => {
+            let expanded = sema
+                .inference
+                .type_of_type_definition(type_definition)
+                .map_or_else(
+                    || "{unknown}".into(),
+                    |typ| TypePrinter::new(module).print(typ),
+                );
+            write!(signature, " = {expanded}").unwrap();
+        }
+    }
+    signature
+}
+
+fn code_block(code: &str) -> String {
+    format!("```camlot\n{code}\n```")
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use super::get_hover;
+    use crate::fixture::{cursor, format_range};
+
+    /// The cursor is placed at `$0`.
+    fn check(text: &str, expected: &Expect) {
+        let (doc, position) = cursor(text);
+
+        let actual = get_hover(&doc, position).map_or_else(
+            || "no hover".into(),
+            |hover| {
+                let lsp_types::HoverContents::Markup(content) = hover.contents else {
+                    unreachable!()
+                };
+                format!(
+                    "{}\n{}\n",
+                    format_range(hover.range.unwrap()),
+                    content.value
+                )
+            },
+        );
+        expected.assert_eq(&actual);
+    }
+
+    #[test]
+    fn hover_definition_use() {
+        check(
+            "def id x = x; def a = (i$0d 42);",
+            &expect![[r"
+                0:23..0:25
+                ```camlot
+                def id : forall a. a -> a
+                ```
+                ---
+                Top-level definition
+            "]],
+        );
+    }
+
+    #[test]
+    fn hover_definition_name() {
+        check(
+            "def $0f (x: int) = x;",
+            &expect![[r"
+                0:4..0:5
+                ```camlot
+                def f : int -> int
+                ```
+                ---
+                Top-level definition
+            "]],
+        );
+    }
+
+    #[test]
+    fn hover_let() {
+        check(
+            "def f { let y = 1; y$0 }",
+            &expect![[r"
+                0:19..0:20
+                ```camlot
+                let y : int
+                ```
+                ---
+                Let binding
+            "]],
+        );
+    }
+
+    #[test]
+    fn hover_param() {
+        check(
+            "def f (x: int) (g: int -> bool) = (g x$0);",
+            &expect![[r"
+                0:37..0:38
+                ```camlot
+                x : int
+                ```
+                ---
+                Parameter
+            "]],
+        );
+    }
+
+    #[test]
+    fn hover_pattern_binding() {
+        check(
+            "type option a = None | Some a;\ndef f o = match o { Some x => x$0 + 1, None => 0 };",
+            &expect![[r"
+                1:30..1:31
+                ```camlot
+                x : int
+                ```
+                ---
+                Pattern binding
+            "]],
+        );
+    }
+
+    #[test]
+    fn hover_constructor() {
+        check(
+            "type option a = None | Some (a -> a);\ndef x = (So$0me 1);",
+            &expect![[r"
+                1:9..1:13
+                ```camlot
+                Some : forall a. (a -> a) -> option a
+                type option a = None | Some (a -> a)
+                ```
+                ---
+                Constructor
+            "]],
+        );
+        check(
+            "type option a = N$0one | Some (a -> a);",
+            &expect![[r"
+                0:16..0:20
+                ```camlot
+                None : forall a. option a
+                type option a = None | Some (a -> a)
+                ```
+                ---
+                Constructor
+            "]],
+        );
+    }
+
+    #[test]
+    fn hover_builtin() {
+        check(
+            "def f = $0add;",
+            &expect![[r"
+                0:8..0:11
+                ```camlot
+                add : int -> int -> int
+                ```
+                ---
+                Builtin function
+            "]],
+        );
+    }
+
+    #[test]
+    fn hover_shows_aliases() {
+        check(
+            "type fn = int -> int;\ndef apply (f: fn) (x: int) : int = (f x);\ndef a = (app$0ly);",
+            &expect![[r"
+                2:9..2:14
+                ```camlot
+                def apply : (int -> int) -> int -> int
+                type fn = int -> int
+                ```
+                ---
+                Top-level definition
+            "]],
+        );
+    }
+
+    #[test]
+    fn hover_type_alias() {
+        check(
+            "type t = int -> bool; def f (x: $0t) = x;",
+            &expect![[r"
+                0:32..0:33
+                ```camlot
+                type t = int -> bool
+                ```
+            "]],
+        );
+    }
+
+    #[test]
+    fn hover_type_param() {
+        check(
+            "type a = int;\ntype box a = | Box $0a;",
+            &expect![[r"
+                1:19..1:20
+                ```camlot
+                a
+                ```
+                ---
+                Type parameter
+            "]],
+        );
+    }
+
+    #[test]
+    fn hover_polymorphic_let() {
+        check(
+            "def f (x: int) { let k y = x; (k$0 true) }",
+            &expect![[r"
+                0:31..0:32
+                ```camlot
+                let k : forall a. a -> int
+                ```
+                ---
+                Let binding
+            "]],
+        );
+    }
+
+    #[test]
+    fn hover_parameterized_alias() {
+        check(
+            "type option a = None | Some a;\ntype endo a = a -> a;\ndef f (g: en$0do (option int)) = g;",
+            &expect![[r"
+                2:10..2:14
+                ```camlot
+                type endo a = a -> a
+                ```
+            "]],
+        );
+    }
+
+    #[test]
+    fn hover_builtin_type() {
+        check(
+            "def f (x: in$0t) = x;",
+            &expect![[r"
+                0:10..0:13
+                ```camlot
+                type int
+                ```
+            "]],
+        );
+    }
+
+    #[test]
+    fn no_hover_outside_identifiers() {
+        check("def f = 4$02;", &expect!["no hover"]);
+    }
+}
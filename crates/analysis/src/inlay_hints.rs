@@ -0,0 +1,268 @@
+use core::{Type, TypePrinter};
+use line_index::{TextRange, TextSize};
+use parser::{SyntaxKind, SyntaxNode};
+
+use crate::semantics::{name_token, Semantics, Symbol};
+use crate::signature_help::{application, param_names};
+use crate::{offset_to_position, position_to_offset, trimmed_range, Document};
+
+/// Kinds of hints shown by the editor.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct InlayHintsConfig {
+    /// Inferred types of bindings and parameters without annotations.
+    pub type_hints: bool,
+    /// Names of the parameters at the arguments of applications.
+    pub parameter_hints: bool,
+}
+
+impl Default for InlayHintsConfig {
+    fn default() -> Self {
+        Self {
+            type_hints: true,
+            parameter_hints: false,
+        }
+    }
+}
+
+/// Hints in the range: `: T` after binders without a type annotation
+/// and `x:` before arguments given to named parameters.
+#[must_use]
+pub fn get_inlay_hints(
+    doc: &Document,
+    range: lsp_types::Range,
+    config: &InlayHintsConfig,
+) -> Vec<lsp_types::InlayHint> {
+    let line_index = doc.get_line_index();
+    let (Some(start), Some(end)) = (
+        position_to_offset(line_index, range.start),
+        position_to_offset(line_index, range.end),
+    ) else {
+        return Vec::new();
+    };
+    let range = TextRange::new(TextSize::new(start), TextSize::new(end));
+
+    let sema = Semantics::new(doc);
+    let mut hints = Vec::new();
+    let nodes = sema
+        .root
+        .descendants()
+        .filter(|node| node.text_range().intersect(range).is_some());
+    for node in nodes {
+        match node.kind() {
+            SyntaxKind::LET_STMT | SyntaxKind::PARAM if config.type_hints => {
+                hints.extend(type_hint(&sema, &node));
+            }
+            SyntaxKind::PAREN_EXPR if config.parameter_hints => {
+                hints.extend(parameter_hints(&sema, &node));
+            }
+            _ => {}
+        }
+    }
+
+    hints
+        .into_iter()
+        .filter(|(offset, _, _)| range.contains_inclusive(*offset))
+        .map(|(offset, label, kind)| {
+            let is_type = kind == lsp_types::InlayHintKind::TYPE;
+            lsp_types::InlayHint {
+                position: offset_to_position(line_index, offset.into()),
+                label: lsp_types::InlayHintLabel::String(label),
+                kind: Some(kind),
+                text_edits: None,
+                tooltip: None,
+                padding_left: Some(false),
+                padding_right: Some(!is_type),
+                data: None,
+            }
+        })
+        .collect()
+}
+
+type Hint = (TextSize, String, lsp_types::InlayHintKind);
+
+/// Type of an unannotated binder, placed where its annotation would be.
+/// For bindings with parameters, this is the type of the result.
+fn type_hint(sema: &Semantics, binder: &SyntaxNode) -> Option<Hint> {
+    if binder
+        .children()
+        .any(|child| child.kind() == SyntaxKind::TYPE_ANNOTATION)
+    {
+        return None;
+    }
+    let name = name_token(binder)?;
+    let Symbol::Binding(binding) = sema.resolve(&name)? else {
+        return None;
+    };
+
+    let params: Vec<_> = binder
+        .children()
+        .filter(|child| child.kind() == SyntaxKind::PARAMS)
+        .flat_map(|params| params.children())
+        .collect();
+    let mut typ = sema.binding_type(binding)?;
+    for _ in &params {
+        let Type::Arrow(_, to) = typ else {
+            return None;
+        };
+        typ = *to;
+    }
+    if typ == Type::Error {
+        return None;
+    }
+
+    let offset = params.last().map_or_else(
+        || name.text_range().end(),
+        |param| trimmed_range(param).end(),
+    );
+    let label = format!(": {}", TypePrinter::new(&sema.module).print(&typ));
+    Some((offset, label, lsp_types::InlayHintKind::TYPE))
+}
+
+/// Names of the parameters before the arguments of the application,
+/// unless the argument is a variable of the same name.
+fn parameter_hints(sema: &Semantics, paren: &SyntaxNode) -> Vec<Hint> {
+    let Some((func, args)) = application(paren) else {
+        return Vec::new();
+    };
+    let Some(Symbol::Binding(binding)) = callee(sema, &func) else {
+        return Vec::new();
+    };
+
+    let names = param_names(sema, binding);
+    args.iter()
+        .zip(names)
+        .filter_map(|(arg, name)| {
+            let name = name?;
+            let is_same_variable = arg.kind() == SyntaxKind::IDENT_EXPR
+                && name_token(arg).is_some_and(|ident| ident.text() == name);
+            if is_same_variable {
+                return None;
+            }
+            let offset = trimmed_range(arg).start();
+            Some((
+                offset,
+                format!("{name}:"),
+                lsp_types::InlayHintKind::PARAMETER,
+            ))
+        })
+        .collect()
+}
+
+fn callee(sema: &Semantics, func: &SyntaxNode) -> Option<Symbol> {
+    if func.kind() != SyntaxKind::IDENT_EXPR {
+        return None;
+    }
+    sema.resolve(&name_token(func)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use super::{get_inlay_hints, InlayHintsConfig};
+    use crate::Document;
+
+    /// Renders the text with the hints inlined in brackets.
+    fn check(text: &str, config: InlayHintsConfig, expected: &Expect) {
+        let doc = Document::new(text.to_string());
+        let end = crate::offset_to_position(doc.get_line_index(), text.len().try_into().unwrap());
+        let range = lsp_types::Range::new(lsp_types::Position::new(0, 0), end);
+
+        let mut actual = text.to_string();
+        let hints = get_inlay_hints(&doc, range, &config);
+        for hint in hints.iter().rev() {
+            let offset = crate::position_to_offset(doc.get_line_index(), hint.position).unwrap();
+            let lsp_types::InlayHintLabel::String(label) = &hint.label else {
+                unreachable!()
+            };
+            actual.insert_str(offset as usize, &format!("[{label}]"));
+        }
+        expected.assert_eq(&actual);
+    }
+
+    const TYPES: InlayHintsConfig = InlayHintsConfig {
+        type_hints: true,
+        parameter_hints: false,
+    };
+
+    const PARAMETERS: InlayHintsConfig = InlayHintsConfig {
+        type_hints: false,
+        parameter_hints: true,
+    };
+
+    #[test]
+    fn let_binding() {
+        check(
+            "def f { let y = 1; let z : int = y; z }",
+            TYPES,
+            &expect!["def f { let y[: int] = 1; let z : int = y; z }"],
+        );
+    }
+
+    #[test]
+    fn let_function() {
+        check(
+            "def f { let g (x: int) = (add x 1); g }",
+            TYPES,
+            &expect!["def f { let g (x: int)[: int] = (add x 1); g }"],
+        );
+    }
+
+    #[test]
+    fn definition_params() {
+        check(
+            "def f x (y: int) = (add x y);",
+            TYPES,
+            &expect!["def f x[: int] (y: int) = (add x y);"],
+        );
+    }
+
+    #[test]
+    fn lambda_params() {
+        check(
+            "def f = \\x y -> (add x y);",
+            TYPES,
+            &expect!["def f = \\x[: int] y[: int] -> (add x y);"],
+        );
+    }
+
+    #[test]
+    fn no_hint_for_unknown_type() {
+        check(
+            "def f { let y = unknown; y }",
+            TYPES,
+            &expect!["def f { let y = unknown; y }"],
+        );
+    }
+
+    #[test]
+    fn parameter_names() {
+        check(
+            "def f (x: int) (y: int) = x;\ndef a y = (f (add 1 2) y);",
+            PARAMETERS,
+            &expect!["def f (x: int) (y: int) = x;\ndef a y = (f [x:](add 1 2) y);"],
+        );
+    }
+
+    #[test]
+    fn no_parameter_names_for_builtins() {
+        check(
+            "def a = (add 1 2);",
+            PARAMETERS,
+            &expect!["def a = (add 1 2);"],
+        );
+    }
+
+    #[test]
+    fn only_in_range() {
+        let text = "def f x = x;\ndef g y = y;";
+        let doc = Document::new(text.to_string());
+        let range = lsp_types::Range::new(
+            lsp_types::Position::new(1, 0),
+            lsp_types::Position::new(1, 12),
+        );
+        let hints = get_inlay_hints(&doc, range, &TYPES);
+        assert_eq!(hints.len(), 1);
+        assert_eq!(hints[0].position, lsp_types::Position::new(1, 7));
+    }
+}
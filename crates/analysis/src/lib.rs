@@ -1,23 +1,104 @@
 use std::ops::Range;
 
+mod completion;
+mod definition;
 mod diagnostics;
 mod document;
+mod document_symbols;
+mod folding_ranges;
+mod formatting;
+mod hover;
+mod inlay_hints;
+mod references;
+mod rename;
+mod selection_ranges;
 mod semantic_tokens;
+mod semantics;
+mod signature_help;
+mod workspace_symbols;
 
+pub use completion::*;
+pub use definition::*;
 pub use diagnostics::*;
 pub use document::*;
+pub use document_symbols::*;
+pub use folding_ranges::*;
+pub use formatting::*;
+pub use hover::*;
+pub use inlay_hints::*;
+pub use references::*;
+pub use rename::*;
+pub use selection_ranges::*;
 pub use semantic_tokens::*;
+pub use signature_help::*;
+pub use workspace_symbols::*;
 
 pub struct ErrorDiagnostic {
     pub range: Range<usize>,
     pub message: String,
 }
 
+/// Range of the node without leading and trailing trivia.
+fn trimmed_range(node: &parser::SyntaxNode) -> line_index::TextRange {
+    let mut tokens = node
+        .descendants_with_tokens()
+        .filter_map(parser::SyntaxElement::into_token)
+        .filter(|token| !token.kind().is_trivial());
+    let Some(first) = tokens.next() else {
+        return node.text_range();
+    };
+    let last = tokens.last().unwrap_or_else(|| first.clone());
+    line_index::TextRange::new(first.text_range().start(), last.text_range().end())
+}
+
+/// LSP positions count columns in UTF-16 code units.
 fn offset_to_position(line_index: &line_index::LineIndex, offset: u32) -> lsp_types::Position {
-    let end = line_index.line_col(line_index::TextSize::new(offset));
+    let line_col = line_index.line_col(line_index::TextSize::new(offset));
+    let end = line_index
+        .to_wide(line_index::WideEncoding::Utf16, line_col)
+        .expect("the column is on its line");
     lsp_types::Position::new(end.line, end.col)
 }
 
+fn text_range_to_lsp(range: line_index::TextRange, doc: &Document) -> lsp_types::Range {
+    let line_index = &doc.get_line_index();
+    let start = offset_to_position(line_index, range.start().into());
+    let end = offset_to_position(line_index, range.end().into());
+    lsp_types::Range::new(start, end)
+}
+
+fn position_to_offset(
+    line_index: &line_index::LineIndex,
+    position: lsp_types::Position,
+) -> Option<u32> {
+    let line_col = line_index::WideLineCol {
+        line: position.line,
+        col: position.character,
+    };
+    let line_col = line_index.to_utf8(line_index::WideEncoding::Utf16, line_col)?;
+    line_index.offset(line_col).map(Into::into)
+}
+
+#[cfg(test)]
+mod fixture {
+    use crate::{offset_to_position, Document};
+
+    /// Document of the text with the cursor placed at `$0`.
+    pub(crate) fn cursor(text: &str) -> (Document, lsp_types::Position) {
+        let offset = text.find("$0").expect("the text has a cursor");
+        let doc = Document::new(text.replace("$0", ""));
+        let position = offset_to_position(doc.get_line_index(), offset.try_into().unwrap());
+        (doc, position)
+    }
+
+    pub(crate) fn format_range(range: lsp_types::Range) -> String {
+        format!(
+            "{}:{}..{}:{}",
+            range.start.line, range.start.character, range.end.line, range.end.character
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -39,4 +120,18 @@ mod tests {
             lsp_types::Position::new(1, 0)
         );
     }
+
+    #[test]
+    fn columns_count_utf16_code_units() {
+        let line_index = line_index::LineIndex::new("\"ą🦀\" x");
+
+        assert_eq!(
+            offset_to_position(&line_index, 7),
+            lsp_types::Position::new(0, 4)
+        );
+        assert_eq!(
+            position_to_offset(&line_index, lsp_types::Position::new(0, 6)),
+            Some(9)
+        );
+    }
 }
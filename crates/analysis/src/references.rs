@@ -0,0 +1,196 @@
+use parser::SyntaxToken;
+
+use crate::semantics::{Semantics, Symbol};
+use crate::{position_to_offset, text_range_to_lsp, Document};
+
+/// Lists uses of the item named at the position, with its binding site if requested.
+#[must_use]
+pub fn get_references(
+    doc: &Document,
+    uri: &lsp_types::Url,
+    position: lsp_types::Position,
+    include_declaration: bool,
+) -> Option<Vec<lsp_types::Location>> {
+    let (sema, symbol) = symbol_at(doc, position)?;
+    let declaration = sema.declaration(symbol).filter(|_| include_declaration);
+    let locations = declaration
+        .into_iter()
+        .chain(sema.usages(symbol))
+        .map(|ident| {
+            lsp_types::Location::new(uri.clone(), text_range_to_lsp(ident.text_range(), doc))
+        })
+        .collect();
+    Some(locations)
+}
+
+/// Highlights the item named at the position: its binding site as a write, its uses as reads.
+#[must_use]
+pub fn get_document_highlights(
+    doc: &Document,
+    position: lsp_types::Position,
+) -> Option<Vec<lsp_types::DocumentHighlight>> {
+    let (sema, symbol) = symbol_at(doc, position)?;
+    let highlight = |ident: SyntaxToken, kind| lsp_types::DocumentHighlight {
+        range: text_range_to_lsp(ident.text_range(), doc),
+        kind: Some(kind),
+    };
+
+    let declaration = sema
+        .declaration(symbol)
+        .map(|ident| highlight(ident, lsp_types::DocumentHighlightKind::WRITE));
+    let usages = sema
+        .usages(symbol)
+        .into_iter()
+        .map(|ident| highlight(ident, lsp_types::DocumentHighlightKind::READ));
+    Some(declaration.into_iter().chain(usages).collect())
+}
+
+fn symbol_at(doc: &Document, position: lsp_types::Position) -> Option<(Semantics, Symbol)> {
+    let offset = position_to_offset(doc.get_line_index(), position)?;
+    let sema = Semantics::new(doc);
+    let symbol = sema.resolve(&sema.ident_at(offset)?)?;
+    Some((sema, symbol))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write;
+
+    use expect_test::{expect, Expect};
+
+    use super::{get_document_highlights, get_references};
+    use crate::fixture::{cursor, format_range};
+
+    /// Lists the highlights of the item at `$0`, then its references without the declaration.
+    fn check(text: &str, expected: &Expect) {
+        let uri = lsp_types::Url::parse("file:///test.rml").unwrap();
+        let (doc, position) = cursor(text);
+
+        let mut actual = String::new();
+        for highlight in get_document_highlights(&doc, position).unwrap_or_default() {
+            let kind = match highlight.kind {
+                Some(lsp_types::DocumentHighlightKind::WRITE) => "write",
+                _ => "read",
+            };
+            writeln!(actual, "{} {kind}", format_range(highlight.range)).unwrap();
+        }
+        let references = get_references(&doc, &uri, position, false).unwrap_or_default();
+        let references: Vec<_> = references
+            .into_iter()
+            .map(|location| format_range(location.range))
+            .collect();
+        writeln!(actual, "references: {}", references.join(", ")).unwrap();
+        expected.assert_eq(&actual);
+    }
+
+    #[test]
+    fn references_of_definition() {
+        check(
+            "def a$0 = 1;\ndef b = a + a;\ndef c = (b a);",
+            &expect![[r"
+                0:4..0:5 write
+                1:8..1:9 read
+                1:12..1:13 read
+                2:11..2:12 read
+                references: 1:8..1:9, 1:12..1:13, 2:11..2:12
+            "]],
+        );
+    }
+
+    #[test]
+    fn references_from_use_site() {
+        check(
+            "def f x = x;\ndef g = (f$0 (f 1));",
+            &expect![[r"
+                0:4..0:5 write
+                1:9..1:10 read
+                1:12..1:13 read
+                references: 1:9..1:10, 1:12..1:13
+            "]],
+        );
+    }
+
+    #[test]
+    fn references_of_let() {
+        check(
+            "def f { let x = 1; let y = x; x$0 + y }",
+            &expect![[r"
+                0:12..0:13 write
+                0:27..0:28 read
+                0:30..0:31 read
+                references: 0:27..0:28, 0:30..0:31
+            "]],
+        );
+    }
+
+    #[test]
+    fn references_respect_shadowing() {
+        check(
+            "def f x$0 { let y = x; let x = y; x }",
+            &expect![[r"
+                0:6..0:7 write
+                0:18..0:19 read
+                references: 0:18..0:19
+            "]],
+        );
+    }
+
+    #[test]
+    fn references_of_lambda_param() {
+        check(
+            "def f = \\x$0 -> (add x x);",
+            &expect![[r"
+                0:9..0:10 write
+                0:19..0:20 read
+                0:21..0:22 read
+                references: 0:19..0:20, 0:21..0:22
+            "]],
+        );
+    }
+
+    #[test]
+    fn references_of_type_alias() {
+        check(
+            "type t$0 = int;\ndef f (x: t) : t = x;",
+            &expect![[r"
+                0:5..0:6 write
+                1:10..1:11 read
+                1:15..1:16 read
+                references: 1:10..1:11, 1:15..1:16
+            "]],
+        );
+    }
+
+    #[test]
+    fn operators_are_not_references() {
+        check(
+            "def f = 1 + (ad$0d 1 2);",
+            &expect![[r"
+                0:13..0:16 read
+                references: 0:13..0:16
+            "]],
+        );
+    }
+
+    #[test]
+    fn references_in_patterns() {
+        check(
+            "type option a = None | So$0me a;\ndef f o = match o { Some x => (Some x), None => None };",
+            &expect![[r"
+                0:23..0:27 write
+                1:20..1:24 read
+                1:31..1:35 read
+                references: 1:20..1:24, 1:31..1:35
+            "]],
+        );
+        check(
+            "type option a = None | Some a;\ndef f o = match o { Some x$0 => x + x, None => 0 };",
+            &expect![[r"
+                1:25..1:26 write
+                1:30..1:31 read
+                1:34..1:35 read
+                references: 1:30..1:31, 1:34..1:35
+            "]],
+        );
+    }
+}
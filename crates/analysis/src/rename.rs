@@ -0,0 +1,576 @@
+use std::collections::HashMap;
+
+use core::{Binding, Builtin};
+use line_index::TextRange;
+use parser::{SyntaxKind, SyntaxToken};
+
+use crate::semantics::{Semantics, Symbol};
+use crate::{position_to_offset, text_range_to_lsp, Document};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RenameError {
+    /// There is no identifier at the position.
+    NoIdentifier,
+    /// Builtins and types which are not aliases cannot be renamed.
+    NotRenamable {
+        name: String,
+    },
+    InvalidName {
+        name: String,
+    },
+    /// Another top-level item, or a builtin, already has the new name.
+    Collision {
+        name: String,
+    },
+    /// A use of the renamed item would refer to another binding of the new name.
+    Captured {
+        name: String,
+    },
+    /// A use of another binding of the new name would refer to the renamed item.
+    Shadows {
+        name: String,
+    },
+}
+
+impl RenameError {
+    #[must_use]
+    pub fn message(&self) -> String {
+        match self {
+            RenameError::NoIdentifier => "no identifier to rename".into(),
+            RenameError::NotRenamable { name } => format!("`{name}` cannot be renamed"),
+            RenameError::InvalidName { name } => format!("`{name}` is not a valid identifier"),
+            RenameError::Collision { name } => format!("`{name}` is already defined"),
+            RenameError::Captured { name } => {
+                format!("a use of the item would be captured by another binding of `{name}`")
+            }
+            RenameError::Shadows { name } => {
+                format!("the new name would shadow an existing binding of `{name}`")
+            }
+        }
+    }
+}
+
+/// Checks whether the item at the position can be renamed, returning the range of its name.
+///
+/// # Errors
+///
+/// Returns an error if there is no renamable item at the position.
+pub fn prepare_rename(
+    doc: &Document,
+    position: lsp_types::Position,
+) -> Result<lsp_types::PrepareRenameResponse, RenameError> {
+    let offset =
+        position_to_offset(doc.get_line_index(), position).ok_or(RenameError::NoIdentifier)?;
+    let sema = Semantics::new(doc);
+    let ident = sema.ident_at(offset).ok_or(RenameError::NoIdentifier)?;
+    renamable_symbol(&sema, &ident)?;
+
+    Ok(lsp_types::PrepareRenameResponse::RangeWithPlaceholder {
+        range: text_range_to_lsp(ident.text_range(), doc),
+        placeholder: ident.text().to_string(),
+    })
+}
+
+/// Renames the item at the position in the document and, for top-level items,
+/// in the given documents which open the document's module.
+///
+/// # Errors
+///
+/// Returns an error if the item cannot be renamed, or if the new name would change
+/// what any identifier refers to.
+pub fn rename(
+    doc: &Document,
+    uri: &lsp_types::Url,
+    position: lsp_types::Position,
+    new_name: &str,
+    workspace: &[(lsp_types::Url, &Document)],
+) -> Result<lsp_types::WorkspaceEdit, RenameError> {
+    let offset =
+        position_to_offset(doc.get_line_index(), position).ok_or(RenameError::NoIdentifier)?;
+    let sema = Semantics::new(doc);
+    let ident = sema.ident_at(offset).ok_or(RenameError::NoIdentifier)?;
+    let symbol = renamable_symbol(&sema, &ident)?;
+    let old_name = ident.text().to_string();
+
+    if !parser::is_identifier(new_name) {
+        return Err(RenameError::InvalidName {
+            name: new_name.into(),
+        });
+    }
+    if new_name == old_name {
+        return Ok(lsp_types::WorkspaceEdit::default());
+    }
+    check_collision(&sema, symbol, new_name)?;
+
+    let mut changes = HashMap::new();
+    let renamed: Vec<_> = sema
+        .declaration(symbol)
+        .into_iter()
+        .chain(sema.usages(symbol))
+        .map(|ident| ident.text_range())
+        .collect();
+    check_resolution_preserved(doc, &renamed, &old_name, new_name)?;
+    changes.insert(uri.clone(), text_edits(doc, &renamed, new_name));
+
+    let is_top_level = matches!(
+        symbol,
+        Symbol::Binding(Binding::Definition(_) | Binding::Constructor(_))
+            | Symbol::TypeDefinition(_)
+    );
+    if is_top_level {
+        for (other_uri, other_doc) in workspace {
+            if other_uri == uri || !opens_module(other_doc, uri) {
+                continue;
+            }
+            let renamed = external_usages(other_doc, &old_name);
+            if renamed.is_empty() {
+                continue;
+            }
+            check_resolution_preserved(other_doc, &renamed, &old_name, new_name)?;
+            changes.insert(other_uri.clone(), text_edits(other_doc, &renamed, new_name));
+        }
+    }
+
+    Ok(lsp_types::WorkspaceEdit {
+        changes: Some(changes),
+        ..Default::default()
+    })
+}
+
+fn renamable_symbol(sema: &Semantics, ident: &SyntaxToken) -> Result<Symbol, RenameError> {
+    match sema.resolve(ident) {
+        Some(Symbol::Binding(Binding::Builtin(_)) | Symbol::NamedType(_)) | None => {
+            Err(RenameError::NotRenamable {
+                name: ident.text().into(),
+            })
+        }
+        Some(symbol) => Ok(symbol),
+    }
+}
+
+/// Top-level items share a namespace with each other, and definitions and constructors
+/// with the builtins, which are also the targets of operators.
+fn check_collision(sema: &Semantics, symbol: Symbol, new_name: &str) -> Result<(), RenameError> {
+    let module = &sema.module;
+    let collides = match symbol {
+        Symbol::Binding(Binding::Definition(_) | Binding::Constructor(_)) => {
+            module
+                .definitions()
+                .any(|(_, definition)| module.lookup_name(definition.name) == new_name)
+                || module
+                    .constructors()
+                    .any(|(_, constructor)| module.lookup_name(constructor.name) == new_name)
+                || Builtin::ALL
+                    .iter()
+                    .any(|builtin| builtin.name() == new_name)
+        }
+        Symbol::TypeDefinition(_) => module
+            .type_definitions()
+            .any(|(_, type_definition)| module.lookup_name(type_definition.name) == new_name),
+        Symbol::TypeParam {
+            type_definition, ..
+        } => module
+            .get_type_definition(type_definition)
+            .params
+            .iter()
+            .any(|param| module.lookup_name(*param) == new_name),
+        Symbol::Binding(_) | Symbol::NamedType(_) => false,
+    };
+    if collides {
+        return Err(RenameError::Collision {
+            name: new_name.into(),
+        });
+    }
+    Ok(())
+}
+
+/// What an identifier refers to, stable across renaming.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Target {
+    /// Index of the identifier declaring the item, among all identifiers of the document.
+    Declaration(usize),
+    Builtin(Builtin),
+    NamedType(String),
+    Unresolved,
+}
+
+fn targets(sema: &Semantics) -> Vec<Target> {
+    let idents: Vec<_> = idents(sema).collect();
+    let index_of = |token: &SyntaxToken| idents.iter().position(|ident| ident == token);
+    idents
+        .iter()
+        .map(|ident| match sema.resolve(ident) {
+            None => Target::Unresolved,
+            Some(Symbol::Binding(Binding::Builtin(builtin))) => Target::Builtin(builtin),
+            Some(Symbol::NamedType(name)) => {
+                Target::NamedType(sema.module.lookup_name(name).into())
+            }
+            Some(symbol) => sema
+                .declaration(symbol)
+                .and_then(|declaration| index_of(&declaration))
+                .map_or(Target::Unresolved, Target::Declaration),
+        })
+        .collect()
+}
+
+fn idents(sema: &Semantics) -> impl Iterator<Item = SyntaxToken> {
+    sema.root
+        .descendants_with_tokens()
+        .filter_map(parser::SyntaxElement::into_token)
+        .filter(|token| token.kind() == SyntaxKind::IDENT)
+}
+
+/// Renames the identifiers in a copy of the document,
+/// and compares what every identifier refers to before and after.
+fn check_resolution_preserved(
+    doc: &Document,
+    renamed: &[TextRange],
+    old_name: &str,
+    new_name: &str,
+) -> Result<(), RenameError> {
+    let sema = Semantics::new(doc);
+    let renamed_indices: Vec<_> = idents(&sema)
+        .enumerate()
+        .filter(|(_, ident)| renamed.contains(&ident.text_range()))
+        .map(|(index, _)| index)
+        .collect();
+    let mut expected = targets(&sema);
+    for &index in &renamed_indices {
+        if expected[index] == Target::NamedType(old_name.into()) {
+            expected[index] = Target::NamedType(new_name.into());
+        }
+    }
+
+    let mut text = doc.text().to_string();
+    for range in renamed.iter().rev() {
+        text.replace_range(std::ops::Range::<usize>::from(*range), new_name);
+    }
+    let actual = targets(&Semantics::new(&Document::new(text)));
+
+    let changed = expected
+        .iter()
+        .zip(&actual)
+        .position(|(expected, actual)| expected != actual);
+    match changed {
+        None => Ok(()),
+        Some(index) if renamed_indices.contains(&index) => Err(RenameError::Captured {
+            name: new_name.into(),
+        }),
+        Some(_) => Err(RenameError::Shadows {
+            name: new_name.into(),
+        }),
+    }
+}
+
+/// Whether the document has an `open` item naming the module of the file at `uri`.
+fn opens_module(doc: &Document, uri: &lsp_types::Url) -> bool {
+    let Some(module_name) = uri
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .and_then(|file| file.strip_suffix(".rml"))
+    else {
+        return false;
+    };
+    let sema = Semantics::new(doc);
+    let opens = sema
+        .module
+        .opens()
+        .any(|(_, open)| sema.module.lookup_name(open.path) == module_name);
+    opens
+}
+
+/// Names not bound in the document, which refer to items of the opened modules.
+fn external_usages(doc: &Document, name: &str) -> Vec<TextRange> {
+    let sema = Semantics::new(doc);
+    idents(&sema)
+        .filter(|ident| ident.text() == name)
+        .filter(|ident| {
+            let parent_kind = ident.parent().map(|parent| parent.kind());
+            match sema.resolve(ident) {
+                None => parent_kind == Some(SyntaxKind::IDENT_EXPR),
+                Some(Symbol::NamedType(_)) => true,
+                Some(_) => false,
+            }
+        })
+        .map(|ident| ident.text_range())
+        .collect()
+}
+
+fn text_edits(doc: &Document, renamed: &[TextRange], new_name: &str) -> Vec<lsp_types::TextEdit> {
+    renamed
+        .iter()
+        .map(|range| lsp_types::TextEdit::new(text_range_to_lsp(*range, doc), new_name.into()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write;
+
+    use expect_test::{expect, Expect};
+
+    use super::{prepare_rename, rename};
+    use crate::fixture::cursor;
+    use crate::Document;
+
+    /// Renames the item at `$0` in `main.rml` to `new_name`; other files are given by name.
+    fn check_with_files(text: &str, files: &[(&str, &str)], new_name: &str, expected: &Expect) {
+        let uri = lsp_types::Url::parse("file:///project/main.rml").unwrap();
+        let (doc, position) = cursor(text);
+
+        let others: Vec<_> = files
+            .iter()
+            .map(|(name, text)| {
+                let uri = uri.join(name).unwrap();
+                (uri, Document::new((*text).to_string()))
+            })
+            .collect();
+        let workspace: Vec<_> = others.iter().map(|(uri, doc)| (uri.clone(), doc)).collect();
+
+        let actual = match rename(&doc, &uri, position, new_name, &workspace) {
+            Ok(edit) => {
+                let mut changes: Vec<_> = edit.changes.unwrap().into_iter().collect();
+                changes.sort_by(|(a, _), (b, _)| a.cmp(b));
+                let mut actual = String::new();
+                for (uri, edits) in changes {
+                    let name = uri
+                        .path_segments()
+                        .unwrap()
+                        .next_back()
+                        .unwrap()
+                        .to_string();
+                    let text = if uri.as_str().ends_with("main.rml") {
+                        doc.text().to_string()
+                    } else {
+                        files
+                            .iter()
+                            .find(|(file, _)| *file == name)
+                            .unwrap()
+                            .1
+                            .to_string()
+                    };
+                    writeln!(actual, "{name}: {}", apply(&text, &edits)).unwrap();
+                }
+                actual
+            }
+            Err(error) => format!("error: {}\n", error.message()),
+        };
+        expected.assert_eq(&actual);
+    }
+
+    fn check(text: &str, new_name: &str, expected: &Expect) {
+        check_with_files(text, &[], new_name, expected);
+    }
+
+    fn apply(text: &str, edits: &[lsp_types::TextEdit]) -> String {
+        let doc = Document::new(text.to_string());
+        let mut text = text.to_string();
+        let mut edits = edits.to_vec();
+        edits.sort_by_key(|edit| (edit.range.start.line, edit.range.start.character));
+        for edit in edits.iter().rev() {
+            let start = crate::position_to_offset(doc.get_line_index(), edit.range.start).unwrap();
+            let end = crate::position_to_offset(doc.get_line_index(), edit.range.end).unwrap();
+            text.replace_range(start as usize..end as usize, &edit.new_text);
+        }
+        text
+    }
+
+    #[test]
+    fn rename_definition() {
+        check(
+            "def a$0 = 1;\ndef b = a + (add a 1);",
+            "c",
+            &expect![[r"
+                main.rml: def c = 1;
+                def b = c + (add c 1);
+            "]],
+        );
+    }
+
+    #[test]
+    fn rename_param_from_use() {
+        check(
+            "def f x y = (add x$0 y);",
+            "z",
+            &expect![[r"
+                main.rml: def f z y = (add z y);
+            "]],
+        );
+    }
+
+    #[test]
+    fn rename_let() {
+        check(
+            "def f { let x = 1; let y = x; x$0 }",
+            "z",
+            &expect![[r"
+                main.rml: def f { let z = 1; let y = z; z }
+            "]],
+        );
+    }
+
+    #[test]
+    fn rename_type_alias() {
+        check(
+            "type t$0 = int;\ndef f (x: t) : t = x;",
+            "number",
+            &expect![[r"
+                main.rml: type number = int;
+                def f (x: number) : number = x;
+            "]],
+        );
+    }
+
+    #[test]
+    fn rename_alias_shadowed_by_type_param() {
+        check(
+            "type a$0 = int;\ntype box a = | Box a;\ndef f (x: a) = x;",
+            "b",
+            &expect![[r"
+                main.rml: type b = int;
+                type box a = | Box a;
+                def f (x: b) = x;
+            "]],
+        );
+    }
+
+    #[test]
+    fn rename_type_param() {
+        check(
+            "type a = int;\ntype pair a b = | Pair a$0 b;",
+            "c",
+            &expect![[r"
+                main.rml: type a = int;
+                type pair c b = | Pair c b;
+            "]],
+        );
+    }
+
+    #[test]
+    fn refuse_type_param_collision() {
+        check(
+            "type pair a$0 b = | Pair a b;",
+            "b",
+            &expect![[r"
+                error: `b` is already defined
+            "]],
+        );
+    }
+
+    #[test]
+    fn refuse_builtin() {
+        check(
+            "def f = ad$0d;",
+            "plus",
+            &expect![[r"
+                error: `add` cannot be renamed
+            "]],
+        );
+    }
+
+    #[test]
+    fn refuse_invalid_name() {
+        check(
+            "def f$0 = 1;",
+            "let",
+            &expect![[r"
+                error: `let` is not a valid identifier
+            "]],
+        );
+    }
+
+    #[test]
+    fn refuse_top_level_collision() {
+        check(
+            "def a$0 = 1;\ndef b = 2;",
+            "b",
+            &expect![[r"
+                error: `b` is already defined
+            "]],
+        );
+    }
+
+    #[test]
+    fn refuse_builtin_collision() {
+        check(
+            "def plus$0 x y = 1;\ndef b = 1 + 2;",
+            "add",
+            &expect![[r"
+                error: `add` is already defined
+            "]],
+        );
+    }
+
+    #[test]
+    fn refuse_capture_by_inner_binding() {
+        check(
+            "def f x$0 { let y = 1; (add x y) }",
+            "y",
+            &expect![[r"
+                error: a use of the item would be captured by another binding of `y`
+            "]],
+        );
+    }
+
+    #[test]
+    fn refuse_shadowing_outer_binding() {
+        check(
+            "def f x { let y$0 = 1; (add x y) }",
+            "x",
+            &expect![[r"
+                error: the new name would shadow an existing binding of `x`
+            "]],
+        );
+    }
+
+    #[test]
+    fn rename_across_files() {
+        check_with_files(
+            "def a$0 = 1;",
+            &[
+                (
+                    "user.rml",
+                    "open main;\ndef b = a;\ndef c x { let a = x; a }",
+                ),
+                ("other.rml", "def b = a;"),
+            ],
+            "value",
+            &expect![[r"
+                main.rml: def value = 1;
+                user.rml: open main;
+                def b = value;
+                def c x { let a = x; a }
+            "]],
+        );
+    }
+
+    #[test]
+    fn refuse_capture_in_other_file() {
+        check_with_files(
+            "def a$0 = 1;",
+            &[("user.rml", "open main;\ndef b x = (add x a);")],
+            "x",
+            &expect![[r"
+                error: a use of the item would be captured by another binding of `x`
+            "]],
+        );
+    }
+
+    #[test]
+    fn prepare() {
+        let doc = Document::new("def f x = (g x);".to_string());
+        let response = prepare_rename(&doc, lsp_types::Position::new(0, 13)).unwrap();
+        let lsp_types::PrepareRenameResponse::RangeWithPlaceholder { range, placeholder } =
+            response
+        else {
+            unreachable!()
+        };
+        assert_eq!(placeholder, "x");
+        assert_eq!(range.start, lsp_types::Position::new(0, 13));
+
+        let error = prepare_rename(&doc, lsp_types::Position::new(0, 11)).unwrap_err();
+        assert_eq!(error.message(), "`g` cannot be renamed");
+        let error = prepare_rename(&doc, lsp_types::Position::new(0, 10)).unwrap_err();
+        assert_eq!(error.message(), "no identifier to rename");
+    }
+}
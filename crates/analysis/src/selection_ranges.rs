@@ -0,0 +1,123 @@
+use line_index::{TextRange, TextSize};
+use parser::SyntaxKind;
+
+use crate::{position_to_offset, text_range_to_lsp, trimmed_range, Document};
+
+/// Ranges growing from the token at each position along its enclosing nodes,
+/// like an identifier, the application it is part of, its statement, block and definition.
+#[must_use]
+pub fn get_selection_ranges(
+    doc: &Document,
+    positions: &[lsp_types::Position],
+) -> Vec<lsp_types::SelectionRange> {
+    let root = doc.parsed().syntax();
+    positions
+        .iter()
+        .map(|&position| {
+            let ranges = position_to_offset(doc.get_line_index(), position)
+                .map(TextSize::new)
+                .filter(|offset| *offset <= root.text_range().end())
+                .map(|offset| {
+                    let tokens = root.token_at_offset(offset);
+                    let token = [tokens.clone().right_biased(), tokens.left_biased()]
+                        .into_iter()
+                        .flatten()
+                        .find(|token| !token.kind().is_trivial())
+                        .or_else(|| root.token_at_offset(offset).right_biased());
+                    let mut ranges: Vec<TextRange> = Vec::new();
+                    if let Some(token) = token {
+                        let token_range = if token.kind().is_trivial() {
+                            TextRange::empty(offset)
+                        } else {
+                            token.text_range()
+                        };
+                        ranges.push(token_range);
+                        for node in token.parent_ancestors() {
+                            let range = if node.kind() == SyntaxKind::MODULE {
+                                node.text_range()
+                            } else {
+                                trimmed_range(&node)
+                            };
+                            if ranges
+                                .last()
+                                .is_none_or(|last| *last != range && range.contains_range(*last))
+                            {
+                                ranges.push(range);
+                            }
+                        }
+                    }
+                    ranges
+                })
+                .unwrap_or_default();
+
+            let empty = lsp_types::Range::new(position, position);
+            ranges
+                .iter()
+                .rev()
+                .fold(None, |parent, range| {
+                    Some(lsp_types::SelectionRange {
+                        range: text_range_to_lsp(*range, doc),
+                        parent: parent.map(Box::new),
+                    })
+                })
+                .unwrap_or(lsp_types::SelectionRange {
+                    range: empty,
+                    parent: None,
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use super::get_selection_ranges;
+    use crate::fixture::cursor;
+
+    /// The cursor is placed at `$0`; the selected texts are listed from the innermost.
+    fn check(text: &str, expected: &Expect) {
+        let (doc, position) = cursor(text);
+
+        let mut actual = String::new();
+        let mut selection = get_selection_ranges(&doc, &[position]).pop();
+        while let Some(range) = selection {
+            let start = crate::position_to_offset(doc.get_line_index(), range.range.start).unwrap();
+            let end = crate::position_to_offset(doc.get_line_index(), range.range.end).unwrap();
+            actual.push_str(&doc.text()[start as usize..end as usize]);
+            actual.push('\n');
+            selection = range.parent.map(|parent| *parent);
+        }
+        expected.assert_eq(&actual);
+    }
+
+    #[test]
+    fn from_identifier_to_definition() {
+        check(
+            "def f {\n    let y = 1;\n    (add y$0 2);\n    y\n}\ndef g = 1;",
+            &expect![[r"
+                y
+                add y
+                add y 2
+                (add y 2)
+                (add y 2);
+                {
+                    let y = 1;
+                    (add y 2);
+                    y
+                }
+                def f {
+                    let y = 1;
+                    (add y 2);
+                    y
+                }
+                def f {
+                    let y = 1;
+                    (add y 2);
+                    y
+                }
+                def g = 1;
+            "]],
+        );
+    }
+}
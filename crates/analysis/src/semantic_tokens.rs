@@ -1,6 +1,8 @@
-use line_index::LineCol;
+use core::Binding;
+use line_index::{LineCol, WideEncoding};
 use parser::{nodes, AstNode, SyntaxKind, SyntaxNode, SyntaxToken};
 
+use crate::semantics::{Semantics, Symbol};
 use crate::Document;
 
 pub const SUPPORTED_TOKENS: &[lsp_types::SemanticTokenType] = &[
@@ -14,6 +16,8 @@ pub const SUPPORTED_TOKENS: &[lsp_types::SemanticTokenType] = &[
     lsp_types::SemanticTokenType::NUMBER,
     lsp_types::SemanticTokenType::COMMENT,
     lsp_types::SemanticTokenType::NAMESPACE,
+    lsp_types::SemanticTokenType::ENUM_MEMBER,
+    lsp_types::SemanticTokenType::TYPE_PARAMETER,
 ];
 
 fn token_index(kind: &lsp_types::SemanticTokenType) -> u32 {
@@ -28,26 +32,31 @@ fn token_index(kind: &lsp_types::SemanticTokenType) -> u32 {
 
 struct SemanticTokensBuilder<'a> {
     line_index: &'a line_index::LineIndex,
+    sema: &'a Semantics,
     prev_pos: line_index::LineCol,
 }
 
 impl<'a> SemanticTokensBuilder<'a> {
-    fn new(line_index: &'a line_index::LineIndex) -> Self {
+    fn new(line_index: &'a line_index::LineIndex, sema: &'a Semantics) -> Self {
         Self {
             line_index,
+            sema,
             prev_pos: line_index::LineCol { line: 0, col: 0 },
         }
     }
 
     fn next(&mut self, token: &SyntaxToken) -> Option<lsp_types::SemanticToken> {
-        let start = token.text_range().start();
-        let end = token.text_range().end();
-        let length = (end - start).into();
+        let length = token.text().encode_utf16().count().try_into().ok()?;
 
         let prev = self.prev_pos;
-        let start = self.line_index.line_col(start);
+        let start = self.line_index.line_col(token.text_range().start());
+        let start = self.line_index.to_wide(WideEncoding::Utf16, start)?;
+        let start = LineCol {
+            line: start.line,
+            col: start.col,
+        };
 
-        if let Some(kind) = get_semantic_token_type(token) {
+        if let Some(kind) = get_semantic_token_type(self.sema, token) {
             self.prev_pos = start;
             Some(make_semantic_token(prev, start, length, &kind))
         } else {
@@ -58,10 +67,10 @@ impl<'a> SemanticTokensBuilder<'a> {
 
 #[must_use]
 pub fn get_semantic_tokens(doc: &Document) -> Vec<lsp_types::SemanticToken> {
-    let mut builder = SemanticTokensBuilder::new(doc.get_line_index());
+    let sema = Semantics::new(doc);
+    let mut builder = SemanticTokensBuilder::new(doc.get_line_index(), &sema);
 
-    doc.parsed()
-        .syntax()
+    sema.root
         .descendants_with_tokens()
         .filter_map(|node| node.as_token().and_then(|token| builder.next(token)))
         .collect()
@@ -75,8 +84,8 @@ fn make_semantic_token(
 ) -> lsp_types::SemanticToken {
     let delta = line_col_delta(prev, start);
     lsp_types::SemanticToken {
-        delta_line: delta.line as u32,
-        delta_start: delta.col as u32,
+        delta_line: delta.line,
+        delta_start: delta.col,
         length,
         token_type: token_index(kind),
         token_modifiers_bitset: 0,
@@ -96,7 +105,10 @@ fn line_col_delta(prev: line_index::LineCol, next: line_index::LineCol) -> line_
     }
 }
 
-fn get_semantic_token_type(token: &SyntaxToken) -> Option<lsp_types::SemanticTokenType> {
+fn get_semantic_token_type(
+    sema: &Semantics,
+    token: &SyntaxToken,
+) -> Option<lsp_types::SemanticTokenType> {
     let parent = token.parent();
     let parent = parent.as_ref();
     match token.kind() {
@@ -105,13 +117,29 @@ fn get_semantic_token_type(token: &SyntaxToken) -> Option<lsp_types::SemanticTok
         SyntaxKind::COMMENT => Some(lsp_types::SemanticTokenType::COMMENT),
         SyntaxKind::INT => Some(lsp_types::SemanticTokenType::NUMBER),
         SyntaxKind::STRING => Some(lsp_types::SemanticTokenType::STRING),
+        SyntaxKind::QUOTE_IDENT => Some(lsp_types::SemanticTokenType::TYPE_PARAMETER),
         SyntaxKind::IDENT => match parent.map(SyntaxNode::kind) {
+            Some(SyntaxKind::TYPE_IDENT)
+                if matches!(sema.resolve(token), Some(Symbol::TypeParam { .. })) =>
+            {
+                Some(lsp_types::SemanticTokenType::TYPE_PARAMETER)
+            }
             Some(SyntaxKind::TYPE_IDENT | SyntaxKind::TYPE_DEFINITION) => {
                 Some(lsp_types::SemanticTokenType::TYPE)
             }
             Some(SyntaxKind::OPEN) => Some(lsp_types::SemanticTokenType::NAMESPACE),
             Some(SyntaxKind::PARAM) => Some(lsp_types::SemanticTokenType::PARAMETER),
             Some(SyntaxKind::DEFINITION) => Some(lsp_types::SemanticTokenType::FUNCTION),
+            Some(SyntaxKind::VARIANT) => Some(lsp_types::SemanticTokenType::ENUM_MEMBER),
+            Some(SyntaxKind::TYPE_PARAM) => Some(lsp_types::SemanticTokenType::TYPE_PARAMETER),
+            Some(SyntaxKind::IDENT_EXPR | SyntaxKind::IDENT_PAT)
+                if matches!(
+                    sema.resolve(token),
+                    Some(Symbol::Binding(Binding::Constructor(_)))
+                ) =>
+            {
+                Some(lsp_types::SemanticTokenType::ENUM_MEMBER)
+            }
             Some(SyntaxKind::LET_STMT)
                 if parent.is_some_and(|n| {
                     nodes::LetStmt::cast(n.clone()).is_some_and(|let_stmt| {
@@ -225,6 +253,60 @@ mod tests {
         .assert_eq(&actual);
     }
 
+    #[test]
+    fn test_get_semantic_tokens_in_sum_type() {
+        let text = "type option a = None | Some a;\ndef x = (Some 1);";
+        let document = Document::new(text.to_string());
+        let tokens = get_semantic_tokens(&document);
+
+        let actual = debug_print_tokens(tokens.as_slice());
+
+        expect![[r"
+            0:0..4 - keyword
+            0:5..11 - type
+            0:7..8 - typeParameter
+            0:2..3 - operator
+            0:2..6 - enumMember
+            0:5..6 - operator
+            0:2..6 - enumMember
+            0:5..6 - typeParameter
+            0:1..2 - operator
+            1:0..3 - keyword
+            0:4..5 - function
+            0:2..3 - operator
+            0:2..3 - operator
+            0:1..5 - enumMember
+            0:5..6 - number
+            0:1..2 - operator
+            0:1..2 - operator
+        "]]
+        .assert_eq(&actual);
+    }
+
+    #[test]
+    fn test_get_semantic_tokens_in_type_app() {
+        let text = "def f (x: option 'a) = x;";
+        let document = Document::new(text.to_string());
+        let tokens = get_semantic_tokens(&document);
+
+        let actual = debug_print_tokens(tokens.as_slice());
+
+        expect![[r"
+            0:0..3 - keyword
+            0:4..5 - function
+            0:2..3 - operator
+            0:1..2 - parameter
+            0:1..2 - operator
+            0:2..8 - type
+            0:7..9 - typeParameter
+            0:2..3 - operator
+            0:2..3 - operator
+            0:2..3 - variable
+            0:1..2 - operator
+        "]]
+        .assert_eq(&actual);
+    }
+
     #[test]
     fn when_prev_ln_eq_next_ln_then_delta_ln_is_0_and_delta_col_is_next_col_minus_prev_col() {
         let prev = line_index::LineCol { line: 2, col: 1 };
@@ -0,0 +1,275 @@
+use core::{
+    infer, Binding, Expr, InferenceResult, Module, Name, Pat, PatIdx, Scheme, Scopes, Type,
+    TypeDefinitionIdx, TypeExpr,
+};
+
+use parser::{SyntaxKind, SyntaxNode, SyntaxNodePtr, SyntaxToken};
+
+use crate::Document;
+
+/// What an identifier in the source refers to.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum Symbol {
+    Binding(Binding),
+    TypeDefinition(TypeDefinitionIdx),
+    /// A parameter of a type definition, like `a` in `type option a`.
+    TypeParam {
+        type_definition: TypeDefinitionIdx,
+        index: usize,
+    },
+    /// A type name which is not an alias, like `int`.
+    NamedType(Name),
+}
+
+/// A document parsed, lowered and type-checked, with identifiers resolved.
+pub(crate) struct Semantics {
+    pub(crate) root: SyntaxNode,
+    pub(crate) module: Module,
+    pub(crate) scopes: Scopes,
+    pub(crate) inference: InferenceResult,
+}
+
+impl Semantics {
+    pub(crate) fn new(doc: &Document) -> Self {
+        let parsed = doc.parsed();
+        let mut module = Module::new();
+        module.lower_module(&parsed.module());
+        let scopes = Scopes::new(&module);
+        let inference = infer(&module);
+        Self {
+            root: parsed.syntax(),
+            module,
+            scopes,
+            inference,
+        }
+    }
+
+    /// Identifier touching the offset, preferring the one on the right.
+    pub(crate) fn ident_at(&self, offset: u32) -> Option<SyntaxToken> {
+        let offset = line_index::TextSize::new(offset);
+        if offset > self.root.text_range().end() {
+            return None;
+        }
+        let tokens = self.root.token_at_offset(offset);
+        [tokens.clone().right_biased(), tokens.left_biased()]
+            .into_iter()
+            .flatten()
+            .find(|token| token.kind() == SyntaxKind::IDENT)
+    }
+
+    /// Resolves an identifier, both where a name is bound and where it is used.
+    pub(crate) fn resolve(&self, ident: &SyntaxToken) -> Option<Symbol> {
+        let parent = ident.parent()?;
+        let ptr = SyntaxNodePtr::new(&parent);
+        let source_map = self.module.source_map();
+        match parent.kind() {
+            SyntaxKind::IDENT_EXPR => {
+                let expr = source_map.syntax_expr(ptr)?;
+                self.scopes.resolution(expr).map(Symbol::Binding)
+            }
+            SyntaxKind::IDENT_PAT => {
+                let pat = source_map.syntax_pat(ptr)?;
+                self.resolve_pat(pat).map(Symbol::Binding)
+            }
+            SyntaxKind::DEFINITION
+            | SyntaxKind::VARIANT
+            | SyntaxKind::LET_STMT
+            | SyntaxKind::PARAM => source_map.syntax_binding(ptr).map(Symbol::Binding),
+            SyntaxKind::TYPE_DEFINITION => source_map
+                .syntax_type_definition(ptr)
+                .map(Symbol::TypeDefinition),
+            SyntaxKind::TYPE_PARAM => {
+                let params = parent.parent()?;
+                let index = params.children().position(|param| param == parent)?;
+                let type_definition =
+                    source_map.syntax_type_definition(SyntaxNodePtr::new(&params.parent()?))?;
+                Some(Symbol::TypeParam {
+                    type_definition,
+                    index,
+                })
+            }
+            SyntaxKind::TYPE_IDENT => {
+                let type_expr = source_map.syntax_type_expr(ptr)?;
+                let TypeExpr::IdentTypeExpr { name } = self.module.get_type_expr(type_expr) else {
+                    return None;
+                };
+                let type_definition = parent
+                    .ancestors()
+                    .find(|node| node.kind() == SyntaxKind::TYPE_DEFINITION)
+                    .and_then(|node| source_map.syntax_type_definition(SyntaxNodePtr::new(&node)));
+                if let Some(param) =
+                    type_definition.and_then(|idx| self.resolve_type_param(idx, *name))
+                {
+                    return Some(param);
+                }
+                Some(self.resolve_type_name(*name))
+            }
+            _ => None,
+        }
+    }
+
+    /// Identifier patterns match a constructor in scope, or else bind the matched value.
+    fn resolve_pat(&self, pat: PatIdx) -> Option<Binding> {
+        if let Some(constructor) = self.scopes.pat_constructor(pat) {
+            return Some(Binding::Constructor(constructor));
+        }
+        match self.module.get_pat(pat) {
+            Pat::Ident { args, .. } if args.is_empty() => Some(Binding::Pat(pat)),
+            _ => None,
+        }
+    }
+
+    /// Parameters of a type definition shadow the aliases in its body, as in type inference.
+    fn resolve_type_param(&self, type_definition: TypeDefinitionIdx, name: Name) -> Option<Symbol> {
+        let params = &self.module.get_type_definition(type_definition).params;
+        let index = params.iter().position(|param| *param == name)?;
+        Some(Symbol::TypeParam {
+            type_definition,
+            index,
+        })
+    }
+
+    /// Type names refer to the first alias with that name, as in type inference.
+    pub(crate) fn resolve_type_name(&self, name: Name) -> Symbol {
+        self.module
+            .type_definitions()
+            .find(|(_, type_definition)| type_definition.name == name)
+            .map_or(Symbol::NamedType(name), |(idx, _)| {
+                Symbol::TypeDefinition(idx)
+            })
+    }
+
+    pub(crate) fn binding_name(&self, binding: Binding) -> &str {
+        match binding {
+            Binding::Builtin(builtin) => builtin.name(),
+            Binding::Definition(definition) => self
+                .module
+                .lookup_name(self.module.get_definition(definition).name),
+            Binding::Constructor(constructor) => self
+                .module
+                .lookup_name(self.module.get_constructor(constructor).name),
+            Binding::Let(expr) => match self.module.get_expr(expr) {
+                Expr::LetExpr(let_expr) => self.module.lookup_name(let_expr.name),
+                _ => "",
+            },
+            Binding::Param { owner, index } => match self.module.get_expr(owner) {
+                Expr::LambdaExpr(lambda) => self.module.lookup_name(lambda.param.name),
+                Expr::LetExpr(let_expr) => self.module.lookup_name(let_expr.params[index].name),
+                _ => "",
+            },
+            Binding::Pat(pat) => match self.module.get_pat(pat) {
+                Pat::Ident { name, .. } => self.module.lookup_name(*name),
+                _ => "",
+            },
+        }
+    }
+
+    pub(crate) fn binding_type(&self, binding: Binding) -> Option<Type> {
+        match binding {
+            Binding::Builtin(builtin) => Some(builtin.typ()),
+            Binding::Definition(definition) => {
+                self.inference.type_of_definition(definition).cloned()
+            }
+            Binding::Constructor(constructor) => {
+                self.inference.type_of_constructor(constructor).cloned()
+            }
+            Binding::Let(expr) => self.inference.type_of_let(expr).cloned(),
+            Binding::Param { owner, index } => self.inference.type_of_param(owner, index).cloned(),
+            Binding::Pat(pat) => self.inference.type_of_pat(pat).cloned(),
+        }
+    }
+
+    /// Type of the binding with the variables it is polymorphic in,
+    /// which are only those of definitions, lets and constructors.
+    pub(crate) fn binding_scheme(&self, binding: Binding) -> Option<Scheme> {
+        let scheme = match binding {
+            Binding::Definition(definition) => self.inference.scheme_of_definition(definition),
+            Binding::Constructor(constructor) => self.inference.scheme_of_constructor(constructor),
+            Binding::Let(expr) => self.inference.scheme_of_let(expr),
+            _ => None,
+        };
+        scheme
+            .cloned()
+            .or_else(|| self.binding_type(binding).map(Scheme::mono))
+    }
+
+    /// Node binding the name, `None` for builtins.
+    pub(crate) fn binding_node(&self, binding: Binding) -> Option<SyntaxNode> {
+        let ptr = self.module.source_map().binding_syntax(binding)?;
+        Some(ptr.to_node(&self.root))
+    }
+
+    pub(crate) fn type_definition_node(
+        &self,
+        type_definition: TypeDefinitionIdx,
+    ) -> Option<SyntaxNode> {
+        let ptr = self
+            .module
+            .source_map()
+            .type_definition_syntax(type_definition)?;
+        Some(ptr.to_node(&self.root))
+    }
+
+    /// Identifier binding the symbol, `None` for builtins and types which are not aliases.
+    pub(crate) fn declaration(&self, symbol: Symbol) -> Option<SyntaxToken> {
+        let node = match symbol {
+            Symbol::Binding(binding) => self.binding_node(binding)?,
+            Symbol::TypeDefinition(type_definition) => {
+                self.type_definition_node(type_definition)?
+            }
+            Symbol::TypeParam {
+                type_definition,
+                index,
+            } => self
+                .type_definition_node(type_definition)?
+                .descendants()
+                .filter(|node| node.kind() == SyntaxKind::TYPE_PARAM)
+                .nth(index)?,
+            Symbol::NamedType(_) => return None,
+        };
+        name_token(&node)
+    }
+
+    /// Identifiers referring to the symbol, in source order, without its declaration.
+    pub(crate) fn usages(&self, symbol: Symbol) -> Vec<SyntaxToken> {
+        let source_map = self.module.source_map();
+        let mut usages: Vec<_> = match symbol {
+            // Operators are lowered to synthesized references to builtins, which are not names.
+            Symbol::Binding(binding) => {
+                let references = self
+                    .scopes
+                    .references(binding)
+                    .filter(|expr| !source_map.is_synthesized(*expr))
+                    .filter_map(|expr| source_map.expr_syntax(expr));
+                let patterns = match binding {
+                    Binding::Constructor(constructor) => self
+                        .scopes
+                        .constructor_patterns(constructor)
+                        .filter_map(|pat| source_map.pat_syntax(pat))
+                        .collect(),
+                    _ => Vec::new(),
+                };
+                references
+                    .chain(patterns)
+                    .filter_map(|ptr| name_token(&ptr.to_node(&self.root)))
+                    .collect()
+            }
+            Symbol::TypeDefinition(_) | Symbol::TypeParam { .. } | Symbol::NamedType(_) => self
+                .root
+                .descendants()
+                .filter(|node| node.kind() == SyntaxKind::TYPE_IDENT)
+                .filter_map(|node| name_token(&node))
+                .filter(|ident| self.resolve(ident) == Some(symbol))
+                .collect(),
+        };
+        usages.sort_by_key(|ident| ident.text_range().start());
+        usages
+    }
+}
+
+/// Identifier naming the binder, constructor, pattern, type definition or opened module.
+pub(crate) fn name_token(node: &SyntaxNode) -> Option<SyntaxToken> {
+    node.children_with_tokens()
+        .filter_map(parser::SyntaxElement::into_token)
+        .find(|token| token.kind() == SyntaxKind::IDENT)
+}
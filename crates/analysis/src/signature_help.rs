@@ -0,0 +1,231 @@
+use std::fmt::Write;
+
+use core::{Binding, Type, TypePrinter};
+use line_index::TextSize;
+use parser::{SyntaxKind, SyntaxNode};
+
+use crate::semantics::{name_token, Semantics, Symbol};
+use crate::{position_to_offset, trimmed_range, Document};
+
+/// Shows the parameters of the function applied in the parenthesized expression
+/// around the position, with the one being typed active.
+#[must_use]
+pub fn get_signature_help(
+    doc: &Document,
+    position: lsp_types::Position,
+) -> Option<lsp_types::SignatureHelp> {
+    let offset = TextSize::new(position_to_offset(doc.get_line_index(), position)?);
+    let sema = Semantics::new(doc);
+    if offset > sema.root.text_range().end() {
+        return None;
+    }
+    let paren = enclosing_paren(&sema.root, offset)?;
+
+    let (func, args) = application(&paren)?;
+    let active_parameter = args
+        .iter()
+        .filter(|arg| trimmed_range(arg).end() < offset)
+        .count();
+
+    if func.kind() != SyntaxKind::IDENT_EXPR {
+        return None;
+    }
+    let Symbol::Binding(binding) = sema.resolve(&name_token(&func)?)? else {
+        return None;
+    };
+    let signature = signature(&sema, binding)?;
+
+    Some(lsp_types::SignatureHelp {
+        signatures: vec![signature],
+        active_signature: Some(0),
+        active_parameter: u32::try_from(active_parameter).ok(),
+    })
+}
+
+/// Innermost parenthesized expression whose parentheses surround the offset.
+fn enclosing_paren(root: &SyntaxNode, offset: TextSize) -> Option<SyntaxNode> {
+    let token = root.token_at_offset(offset).left_biased()?;
+    token
+        .parent_ancestors()
+        .filter(|node| node.kind() == SyntaxKind::PAREN_EXPR)
+        .find(|paren| {
+            let child_start = |kind| {
+                paren
+                    .children_with_tokens()
+                    .find(|child| child.kind() == kind)
+                    .map(|child| child.text_range().start())
+            };
+            let after_l_paren =
+                child_start(SyntaxKind::L_PAREN).is_some_and(|start| start < offset);
+            let before_r_paren =
+                child_start(SyntaxKind::R_PAREN).is_none_or(|start| offset <= start);
+            after_l_paren && before_r_paren
+        })
+}
+
+/// Function and arguments of the application in the parenthesized expression.
+/// Without arguments, the function is the whole expression.
+pub(crate) fn application(paren: &SyntaxNode) -> Option<(SyntaxNode, Vec<SyntaxNode>)> {
+    let mut spine: Vec<_> = std::iter::successors(
+        paren
+            .children()
+            .find(|child| child.kind() != SyntaxKind::ERROR),
+        |node| {
+            (node.kind() == SyntaxKind::APP_EXPR)
+                .then(|| node.first_child())
+                .flatten()
+        },
+    )
+    .collect();
+    let func = spine.pop()?;
+    let args = spine
+        .iter()
+        .rev()
+        .filter_map(SyntaxNode::last_child)
+        .collect();
+    Some((func, args))
+}
+
+/// Names of the parameters in the binder, `None` for the ones without a name.
+pub(crate) fn param_names(sema: &Semantics, binding: Binding) -> Vec<Option<String>> {
+    sema.binding_node(binding)
+        .into_iter()
+        .flat_map(|binder| binder.children())
+        .filter(|child| child.kind() == SyntaxKind::PARAMS)
+        .flat_map(|params| params.children())
+        .map(|param| name_token(&param).map(|name| name.text().to_string()))
+        .collect()
+}
+
+/// Renders the binding like a definition, `f (x: int) (y: int) : int`,
+/// with `_` for parameters without a name in the source.
+fn signature(sema: &Semantics, binding: Binding) -> Option<lsp_types::SignatureInformation> {
+    let names = param_names(sema, binding);
+
+    let mut typ = sema.binding_type(binding)?;
+    let mut param_types = Vec::new();
+    while names.is_empty() || param_types.len() < names.len() {
+        let Type::Arrow(from, to) = typ else {
+            break;
+        };
+        param_types.push(*from);
+        typ = *to;
+    }
+
+    let mut printer = TypePrinter::new(&sema.module);
+    let mut label = sema.binding_name(binding).to_string();
+    let mut parameters = Vec::new();
+    for (index, param_type) in param_types.iter().enumerate() {
+        let name = names.get(index).and_then(Option::as_deref).unwrap_or("_");
+        label.push(' ');
+        let start = u32::try_from(label.len()).ok()?;
+        write!(label, "({name}: {})", printer.print(param_type)).unwrap();
+        let end = u32::try_from(label.len()).ok()?;
+        parameters.push(lsp_types::ParameterInformation {
+            label: lsp_types::ParameterLabel::LabelOffsets([start, end]),
+            documentation: None,
+        });
+    }
+    write!(label, " : {}", printer.print(&typ)).unwrap();
+
+    Some(lsp_types::SignatureInformation {
+        label,
+        documentation: None,
+        parameters: Some(parameters),
+        active_parameter: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use super::get_signature_help;
+    use crate::fixture::cursor;
+
+    /// The cursor is placed at `$0`; the active parameter is shown in brackets.
+    fn check(text: &str, expected: &Expect) {
+        let (doc, position) = cursor(text);
+
+        let actual = match get_signature_help(&doc, position) {
+            Some(help) => {
+                let signature = &help.signatures[0];
+                let mut label = signature.label.clone();
+                let active = help.active_parameter.unwrap() as usize;
+                let parameters = signature.parameters.as_ref().unwrap();
+                if let Some(lsp_types::ParameterInformation {
+                    label: lsp_types::ParameterLabel::LabelOffsets([start, end]),
+                    ..
+                }) = parameters.get(active)
+                {
+                    label.insert(*end as usize, ']');
+                    label.insert(*start as usize, '[');
+                }
+                label
+            }
+            None => "no signature".into(),
+        };
+        expected.assert_eq(&actual);
+    }
+
+    #[test]
+    fn first_argument() {
+        check(
+            "def f (x: int) (y: bool) = x;\ndef a = (f $0);",
+            &expect!["f [(x: int)] (y: bool) : int"],
+        );
+    }
+
+    #[test]
+    fn second_argument() {
+        check(
+            "def f (x: int) (y: bool) = x;\ndef a = (f 1 $0);",
+            &expect!["f (x: int) [(y: bool)] : int"],
+        );
+    }
+
+    #[test]
+    fn touching_argument() {
+        check(
+            "def f (x: int) (y: bool) = x;\ndef a = (f 12$0);",
+            &expect!["f [(x: int)] (y: bool) : int"],
+        );
+    }
+
+    #[test]
+    fn nested_application() {
+        check(
+            "def f x y = (add x y);\ndef a = (f (add 1 $0) 2);",
+            &expect!["add (_: int) [(_: int)] : int"],
+        );
+    }
+
+    #[test]
+    fn after_nested_application() {
+        check(
+            "def f x y = (add x y);\ndef a = (f (add 1 2) $0);",
+            &expect!["f (x: int) [(y: int)] : int"],
+        );
+    }
+
+    #[test]
+    fn unnamed_parameters() {
+        check(
+            "def inc = (add 1);\ndef a = (inc $0);",
+            &expect!["inc [(_: int)] : int"],
+        );
+    }
+
+    #[test]
+    fn let_function() {
+        check(
+            "def a { let g (x: int) = x; (g $0) }",
+            &expect!["g [(x: int)] : int"],
+        );
+    }
+
+    #[test]
+    fn outside_application() {
+        check("def f x = x;\ndef a = f$0;", &expect!["no signature"]);
+    }
+}
@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+
+use crate::{get_document_symbols, Document};
+
+/// Most symbols returned for a single query.
+const LIMIT: usize = 128;
+
+/// Top-level definitions, types and constructors of every module in the workspace,
+/// opened in the editor or not.
+#[derive(Default, Debug)]
+pub struct SymbolIndex {
+    files: HashMap<lsp_types::Url, Vec<IndexedSymbol>>,
+}
+
+#[derive(Debug)]
+struct IndexedSymbol {
+    name: String,
+    kind: lsp_types::SymbolKind,
+    range: lsp_types::Range,
+}
+
+impl SymbolIndex {
+    /// Indexes the module, replacing symbols previously found in it.
+    pub fn insert(&mut self, uri: lsp_types::Url, doc: &Document) {
+        let symbols = get_document_symbols(doc)
+            .into_iter()
+            .filter(|symbol| symbol.kind != lsp_types::SymbolKind::MODULE)
+            .flat_map(|mut symbol| {
+                let constructors = match symbol.kind {
+                    lsp_types::SymbolKind::ENUM => symbol.children.take().unwrap_or_default(),
+                    _ => Vec::new(),
+                };
+                std::iter::once(symbol).chain(constructors)
+            })
+            .map(|symbol| IndexedSymbol {
+                name: symbol.name,
+                kind: symbol.kind,
+                range: symbol.selection_range,
+            })
+            .collect();
+        self.files.insert(uri, symbols);
+    }
+
+    pub fn remove(&mut self, uri: &lsp_types::Url) {
+        self.files.remove(uri);
+    }
+
+    /// Symbols whose names contain the characters of the query in order, ignoring case.
+    /// Closer matches come first: exact names, then prefixes, then substrings.
+    #[must_use]
+    #[allow(deprecated)]
+    pub fn search(&self, query: &str) -> Vec<lsp_types::SymbolInformation> {
+        let query = query.to_lowercase();
+        let mut matches: Vec<_> = self
+            .files
+            .iter()
+            .flat_map(|(uri, symbols)| symbols.iter().map(move |symbol| (uri, symbol)))
+            .filter_map(|(uri, symbol)| Some((fuzzy_score(&query, &symbol.name)?, uri, symbol)))
+            .collect();
+        matches.sort_by(|(score_a, uri_a, a), (score_b, uri_b, b)| {
+            score_a
+                .cmp(score_b)
+                .then_with(|| a.name.len().cmp(&b.name.len()))
+                .then_with(|| a.name.cmp(&b.name))
+                .then_with(|| uri_a.cmp(uri_b))
+                .then_with(|| a.range.start.cmp(&b.range.start))
+        });
+
+        matches
+            .into_iter()
+            .take(LIMIT)
+            .map(|(_, uri, symbol)| lsp_types::SymbolInformation {
+                name: symbol.name.clone(),
+                kind: symbol.kind,
+                tags: None,
+                deprecated: None,
+                location: lsp_types::Location::new(uri.clone(), symbol.range),
+                container_name: None,
+            })
+            .collect()
+    }
+}
+
+/// Lower is better, `None` when the name does not match.
+/// The query is expected to be lowercase.
+fn fuzzy_score(query: &str, name: &str) -> Option<u8> {
+    let name = name.to_lowercase();
+    if name == query {
+        return Some(0);
+    }
+    if name.starts_with(query) {
+        return Some(1);
+    }
+    if name.contains(query) {
+        return Some(2);
+    }
+    let mut chars = name.chars();
+    query
+        .chars()
+        .all(|wanted| chars.any(|c| c == wanted))
+        .then_some(3)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write;
+
+    use expect_test::{expect, Expect};
+
+    use super::SymbolIndex;
+    use crate::Document;
+
+    fn index(files: &[(&str, &str)]) -> SymbolIndex {
+        let mut index = SymbolIndex::default();
+        for (name, text) in files {
+            let uri = lsp_types::Url::parse(&format!("file:///project/{name}")).unwrap();
+            index.insert(uri, &Document::new((*text).to_string()));
+        }
+        index
+    }
+
+    fn check(index: &SymbolIndex, query: &str, expected: &Expect) {
+        let mut actual = String::new();
+        for symbol in index.search(query) {
+            let start = symbol.location.range.start;
+            writeln!(
+                actual,
+                "{:?} {} {}:{}:{}",
+                symbol.kind, symbol.name, symbol.location.uri, start.line, start.character
+            )
+            .unwrap();
+        }
+        expected.assert_eq(&actual);
+    }
+
+    #[test]
+    fn fuzzy_matches_across_files() {
+        let index = index(&[
+            (
+                "list.rml",
+                "type list_length = int;\ndef length l = 0;\ntype size = | Length int;",
+            ),
+            (
+                "main.rml",
+                "open list;\ndef main = (length 1);\ndef len x = x;",
+            ),
+        ]);
+        check(
+            &index,
+            "len",
+            &expect![[r"
+                Function len file:///project/main.rml:2:4
+                EnumMember Length file:///project/list.rml:2:14
+                Function length file:///project/list.rml:1:4
+                Struct list_length file:///project/list.rml:0:5
+            "]],
+        );
+        check(
+            &index,
+            "mn",
+            &expect![[r"
+                Constant main file:///project/main.rml:1:4
+            "]],
+        );
+    }
+
+    #[test]
+    fn updated_and_removed_files() {
+        let mut index = index(&[("a.rml", "def foo = 1;"), ("b.rml", "def foobar = 2;")]);
+        let a = lsp_types::Url::parse("file:///project/a.rml").unwrap();
+        let b = lsp_types::Url::parse("file:///project/b.rml").unwrap();
+        index.insert(a, &Document::new("def fizz = 1;".into()));
+        index.remove(&b);
+        check(
+            &index,
+            "f",
+            &expect![[r"
+                Constant fizz file:///project/a.rml:0:4
+            "]],
+        );
+    }
+}
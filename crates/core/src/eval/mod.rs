@@ -0,0 +1,541 @@
+mod value;
+
+use std::rc::Rc;
+
+use la_arena::ArenaMap;
+
+pub use value::{Closure, Value, ValueDisplay};
+
+use crate::hir::{Binding, DefinitionIdx, Expr, ExprIdx, Literal, Pat, PatIdx, Scopes};
+use crate::{Builtin, Module, Name};
+use value::Env;
+
+/// Nesting of evaluated expressions, outside tail position, after which evaluation is aborted.
+const MAX_DEPTH: usize = 10_000;
+/// Stack space left below which evaluation continues on a newly allocated stack segment.
+const RED_ZONE: usize = 64 * 1024;
+/// Size of the stack segments allocated for deeply nested evaluation.
+const STACK_SEGMENT: usize = 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EvalError {
+    /// The expression is missing from the source.
+    MissingExpr {
+        expr: ExprIdx,
+    },
+    UnboundVariable {
+        expr: ExprIdx,
+        name: Name,
+    },
+    /// The definition needs its own value to be computed, as in `def a = a;`.
+    CyclicDefinition {
+        definition: DefinitionIdx,
+    },
+    /// A value was used against its type, which only happens in programs with type errors.
+    IllTyped {
+        expr: ExprIdx,
+    },
+    DivisionByZero {
+        expr: ExprIdx,
+    },
+    StackOverflow {
+        expr: ExprIdx,
+    },
+    /// None of the arms of the match expression matches the value.
+    NoMatchingArm {
+        expr: ExprIdx,
+    },
+}
+
+impl EvalError {
+    #[must_use]
+    pub fn message(&self, module: &Module) -> String {
+        match self {
+            EvalError::MissingExpr { .. } => "missing expression".into(),
+            EvalError::UnboundVariable { name, .. } => {
+                format!("unbound variable `{}`", module.lookup_name(*name))
+            }
+            EvalError::CyclicDefinition { definition } => format!(
+                "definition `{}` depends on its own value",
+                module.lookup_name(module.get_definition(*definition).name)
+            ),
+            EvalError::IllTyped { .. } => "ill-typed expression".into(),
+            EvalError::DivisionByZero { .. } => "division by zero".into(),
+            EvalError::StackOverflow { .. } => "stack overflow".into(),
+            EvalError::NoMatchingArm { .. } => "no match arm matches the value".into(),
+        }
+    }
+}
+
+/// Evaluates expressions of a module, caching values of its definitions.
+pub struct Interpreter<'a> {
+    module: &'a Module,
+    scopes: Scopes,
+    /// `None` for definitions being evaluated, to detect cycles.
+    globals: ArenaMap<DefinitionIdx, Option<Value>>,
+    depth: usize,
+}
+
+impl<'a> Interpreter<'a> {
+    #[must_use]
+    pub fn new(module: &'a Module) -> Self {
+        Self {
+            module,
+            scopes: Scopes::new(module),
+            globals: ArenaMap::default(),
+            depth: 0,
+        }
+    }
+
+    /// # Errors
+    ///
+    /// Returns an error if evaluation of the definition fails.
+    pub fn eval_definition(&mut self, definition: DefinitionIdx) -> Result<Value, EvalError> {
+        match self.globals.get(definition) {
+            Some(Some(value)) => return Ok(value.clone()),
+            Some(None) => return Err(EvalError::CyclicDefinition { definition }),
+            None => {}
+        }
+
+        self.globals.insert(definition, None);
+        let defn = self.module.get_definition(definition).defn;
+        let result = self.eval(defn, &Env::default());
+        match &result {
+            Ok(value) => self.globals.insert(definition, Some(value.clone())),
+            Err(_) => self.globals.remove(definition),
+        };
+        result
+    }
+
+    /// Evaluates an expression which is not nested in any function or let.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if evaluation of the expression fails.
+    pub fn eval_expr(&mut self, expr: ExprIdx) -> Result<Value, EvalError> {
+        self.eval(expr, &Env::default())
+    }
+
+    fn eval(&mut self, expr: ExprIdx, env: &Env) -> Result<Value, EvalError> {
+        if self.depth == MAX_DEPTH {
+            return Err(EvalError::StackOverflow { expr });
+        }
+        self.depth += 1;
+        let result = stacker::maybe_grow(RED_ZONE, STACK_SEGMENT, || {
+            self.eval_tail(expr, env.clone())
+        });
+        self.depth -= 1;
+        result
+    }
+
+    /// Evaluates an expression, looping instead of nesting on the expressions in tail
+    /// position, so that tail calls run in constant space.
+    fn eval_tail(&mut self, mut expr: ExprIdx, mut env: Env) -> Result<Value, EvalError> {
+        loop {
+            (expr, env) = match self.module.get_expr(expr) {
+                Expr::Missing => return Err(EvalError::MissingExpr { expr }),
+                Expr::LiteralExpr(Literal::IntLiteral(value)) => return Ok(Value::Int(*value)),
+                Expr::LiteralExpr(Literal::BoolLiteral(value)) => return Ok(Value::Bool(*value)),
+                Expr::IdentExpr { name } => return self.eval_ident(expr, *name, &env),
+                Expr::BuiltinExpr(builtin) => return Ok(Value::Builtin(*builtin, Rc::new([]))),
+                Expr::AppExpr { func, arg } => {
+                    let func = self.eval(*func, &env)?;
+                    let arg = self.eval(*arg, &env)?;
+                    match self.apply(expr, &func, arg)? {
+                        Applied::Value(value) => return Ok(value),
+                        Applied::Body(body, env) => (body, env),
+                    }
+                }
+                Expr::IfExpr {
+                    condition,
+                    then_branch,
+                    else_branch,
+                } => match self.eval(*condition, &env)? {
+                    Value::Bool(true) => (*then_branch, env),
+                    Value::Bool(false) => (*else_branch, env),
+                    _ => return Err(EvalError::IllTyped { expr: *condition }),
+                },
+                Expr::MatchExpr { scrutinee, arms } => {
+                    let value = self.eval(*scrutinee, &env)?;
+                    arms.iter()
+                        .find_map(|arm| Some((arm.expr, self.match_pat(arm.pat, &value, &env)?)))
+                        .ok_or(EvalError::NoMatchingArm { expr })?
+                }
+                Expr::LambdaExpr(lambda) => {
+                    return Ok(Value::Closure(Rc::new(Closure {
+                        owner: expr,
+                        arity: 1,
+                        body: lambda.body,
+                        env,
+                        applied: 0,
+                    })))
+                }
+                Expr::LetExpr(let_expr) => {
+                    let value = if let_expr.params.is_empty() {
+                        self.eval(let_expr.defn, &env)?
+                    } else {
+                        Value::Closure(Rc::new(Closure {
+                            owner: expr,
+                            arity: let_expr.params.len(),
+                            body: let_expr.defn,
+                            env: env.clone(),
+                            applied: 0,
+                        }))
+                    };
+                    (let_expr.body, env.bind(Binding::Let(expr), value))
+                }
+            };
+        }
+    }
+
+    /// Environment extended with the names bound by the pattern, `None` if it does not match.
+    fn match_pat(&self, pat: PatIdx, value: &Value, env: &Env) -> Option<Env> {
+        match (self.module.get_pat(pat), value) {
+            (Pat::Wildcard, _) => Some(env.clone()),
+            (Pat::Literal(Literal::IntLiteral(expected)), Value::Int(actual)) => {
+                (expected == actual).then(|| env.clone())
+            }
+            (Pat::Literal(Literal::BoolLiteral(expected)), Value::Bool(actual)) => {
+                (expected == actual).then(|| env.clone())
+            }
+            (Pat::Ident { args, .. }, _) => match (self.scopes.pat_constructor(pat), value) {
+                (None, _) if args.is_empty() => Some(env.bind(Binding::Pat(pat), value.clone())),
+                (Some(expected), Value::Constructor(actual, fields))
+                    if expected == *actual && args.len() == fields.len() =>
+                {
+                    args.iter()
+                        .zip(fields.iter())
+                        .try_fold(env.clone(), |env, (arg, field)| {
+                            self.match_pat(*arg, field, &env)
+                        })
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn eval_ident(&mut self, expr: ExprIdx, name: Name, env: &Env) -> Result<Value, EvalError> {
+        match self.scopes.resolution(expr) {
+            Some(Binding::Builtin(builtin)) => Ok(Value::Builtin(builtin, Rc::new([]))),
+            Some(Binding::Definition(definition)) => self.eval_definition(definition),
+            Some(Binding::Constructor(constructor)) => {
+                Ok(Value::Constructor(constructor, Rc::new([])))
+            }
+            Some(binding) => env
+                .lookup(binding)
+                .cloned()
+                .ok_or(EvalError::UnboundVariable { expr, name }),
+            None => Err(EvalError::UnboundVariable { expr, name }),
+        }
+    }
+
+    fn apply(&mut self, expr: ExprIdx, func: &Value, arg: Value) -> Result<Applied, EvalError> {
+        match func {
+            Value::Closure(closure) => {
+                let env = closure.env.bind(
+                    Binding::Param {
+                        owner: closure.owner,
+                        index: closure.applied,
+                    },
+                    arg,
+                );
+                if closure.applied + 1 < closure.arity {
+                    return Ok(Applied::Value(Value::Closure(Rc::new(Closure {
+                        env,
+                        applied: closure.applied + 1,
+                        ..**closure
+                    }))));
+                }
+                Ok(Applied::Body(closure.body, env))
+            }
+            Value::Builtin(builtin, args) => {
+                let mut args = args.to_vec();
+                args.push(arg);
+                if args.len() < 2 {
+                    return Ok(Applied::Value(Value::Builtin(*builtin, args.into())));
+                }
+                apply_builtin(expr, *builtin, &args).map(Applied::Value)
+            }
+            Value::Constructor(constructor, fields) => {
+                if fields.len() == self.module.get_constructor(*constructor).fields.len() {
+                    return Err(EvalError::IllTyped { expr });
+                }
+                let mut fields = fields.to_vec();
+                fields.push(arg);
+                Ok(Applied::Value(Value::Constructor(
+                    *constructor,
+                    fields.into(),
+                )))
+            }
+            Value::Int(_) | Value::Bool(_) | Value::Unit => Err(EvalError::IllTyped { expr }),
+        }
+    }
+}
+
+/// Outcome of applying a function to an argument.
+enum Applied {
+    Value(Value),
+    /// The function is saturated, and its body is left to evaluate in this environment.
+    Body(ExprIdx, Env),
+}
+
+fn apply_builtin(expr: ExprIdx, builtin: Builtin, args: &[Value]) -> Result<Value, EvalError> {
+    let [Value::Int(lhs), Value::Int(rhs)] = args else {
+        return Err(EvalError::IllTyped { expr });
+    };
+    let (lhs, rhs) = (*lhs, *rhs);
+    Ok(match builtin {
+        Builtin::Add => Value::Int(lhs.wrapping_add(rhs)),
+        Builtin::Sub => Value::Int(lhs.wrapping_sub(rhs)),
+        Builtin::Mul => Value::Int(lhs.wrapping_mul(rhs)),
+        Builtin::Div if rhs == 0 => return Err(EvalError::DivisionByZero { expr }),
+        Builtin::Div => Value::Int(lhs.wrapping_div(rhs)),
+        Builtin::Eq => Value::Bool(lhs == rhs),
+        Builtin::Lt => Value::Bool(lhs < rhs),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write;
+
+    use expect_test::{expect, Expect};
+
+    use super::Interpreter;
+    use crate::Module;
+
+    fn check(text: &str, expected: &Expect) {
+        let mut module = Module::new();
+        module.lower_module(&parser::parse(text).module());
+        let mut interpreter = Interpreter::new(&module);
+
+        let mut actual = String::new();
+        for (idx, definition) in module.definitions() {
+            let name = module.lookup_name(definition.name);
+            match interpreter.eval_definition(idx) {
+                Ok(value) => writeln!(actual, "{name} = {}", value.display(&module)).unwrap(),
+                Err(error) => writeln!(actual, "{name}: {}", error.message(&module)).unwrap(),
+            }
+        }
+        expected.assert_eq(&actual);
+    }
+
+    #[test]
+    fn eval_arithmetic() {
+        check(
+            "def a = 1 + 2 * 3 - 8 / 2;",
+            &expect![[r"
+                a = 3
+            "]],
+        );
+    }
+
+    #[test]
+    fn operators_are_not_shadowed() {
+        check(
+            "def add a b = 100; def a = 1 + 2; def b { let sub x y = 0; 5 - 3 }",
+            &expect![[r"
+                add = <function>
+                a = 3
+                b = 2
+            "]],
+        );
+    }
+
+    #[test]
+    fn eval_comparison() {
+        check(
+            "def a = (lt 1 2); def b = (eq 1 2);",
+            &expect![[r"
+                a = true
+                b = false
+            "]],
+        );
+    }
+
+    #[test]
+    fn eval_curried_application() {
+        check(
+            "def const x y = x; def k = (const 1); def a = (k 2);",
+            &expect![[r"
+                const = <function>
+                k = <function>
+                a = 1
+            "]],
+        );
+    }
+
+    #[test]
+    fn eval_partial_builtin() {
+        check(
+            "def inc = (add 1); def a = (inc 41);",
+            &expect![[r"
+                inc = <builtin add>
+                a = 42
+            "]],
+        );
+    }
+
+    #[test]
+    fn eval_if() {
+        check(
+            "def abs x = if (lt x 0) { 0 - x } else { x }; def a = (abs (sub 0 3)); def b = (abs 4);",
+            &expect![[r"
+                abs = <function>
+                a = 3
+                b = 4
+            "]],
+        );
+    }
+
+    #[test]
+    fn eval_constructors() {
+        check(
+            "type option a = None | Some a; def a = None; def b = (Some (Some 1)); def c = Some;",
+            &expect![[r"
+                a = None
+                b = Some (Some 1)
+                c = <constructor Some>
+            "]],
+        );
+    }
+
+    #[test]
+    fn eval_match() {
+        check(
+            "type option a = None | Some a; \
+            def get o d = match o { Some (Some x) => x, Some None => d, None => 0 }; \
+            def a = (get (Some (Some 1)) 2); \
+            def b = (get (Some None) 2); \
+            def c = (get None 2); \
+            def d = match 3 { 1 => false, 2 => false, _ => true };",
+            &expect![[r"
+                get = <function>
+                a = 1
+                b = 2
+                c = 0
+                d = true
+            "]],
+        );
+    }
+
+    #[test]
+    fn eval_match_without_matching_arm() {
+        check(
+            "def a = match 3 { 1 => true, 2 => false };",
+            &expect![[r"
+                a: no match arm matches the value
+            "]],
+        );
+    }
+
+    #[test]
+    fn eval_block() {
+        check(
+            "def a { let double x = x * 2; let y = (double 4); y + 1 }",
+            &expect![[r"
+                a = 9
+            "]],
+        );
+    }
+
+    #[test]
+    fn closures_capture_environment() {
+        check(
+            "def adder x = \\y -> x + y; def a = ((adder 1) 2);",
+            &expect![[r"
+                adder = <function>
+                a = 3
+            "]],
+        );
+    }
+
+    #[test]
+    fn definitions_are_mutually_recursive() {
+        check(
+            "def a = (f 1); def f x = (g x); def g x = x + b; def b = 10;",
+            &expect![[r"
+                a = 11
+                f = <function>
+                g = <function>
+                b = 10
+            "]],
+        );
+    }
+
+    #[test]
+    fn report_cyclic_definition() {
+        check(
+            "def a = b; def b = a + 1;",
+            &expect![[r"
+                a: definition `a` depends on its own value
+                b: definition `b` depends on its own value
+            "]],
+        );
+    }
+
+    #[test]
+    fn report_division_by_zero() {
+        check(
+            "def a = 1 / (sub 1 1);",
+            &expect![[r"
+                a: division by zero
+            "]],
+        );
+    }
+
+    #[test]
+    fn eval_deep_recursion() {
+        check(
+            "def count n = if (eq n 0) { 0 } else { 1 + (count (n - 1)) }; def a = (count 5000);",
+            &expect![[r"
+                count = <function>
+                a = 5000
+            "]],
+        );
+    }
+
+    #[test]
+    fn tail_calls_do_not_nest() {
+        check(
+            "def sum n acc = match n { 0 => acc, _ => { let m = n - 1; (sum m (acc + n)) } }; \
+            def even n = if (eq n 0) { true } else { (odd (n - 1)) }; \
+            def odd n = if (eq n 0) { false } else { (even (n - 1)) }; \
+            def a = (sum 200000 0); \
+            def b = (even 200001);",
+            &expect![[r"
+                sum = <function>
+                even = <function>
+                odd = <function>
+                a = 20000100000
+                b = false
+            "]],
+        );
+    }
+
+    #[test]
+    fn display_deep_values() {
+        let text = "type list = | Nil | Cons int list; \
+            def build n acc = if (eq n 0) { acc } else { (build (n - 1) (Cons n acc)) }; \
+            def l = (build 100000 Nil);";
+        let mut module = Module::new();
+        module.lower_module(&parser::parse(text).module());
+        let (l, _) = module.definitions().last().unwrap();
+        let value = Interpreter::new(&module).eval_definition(l).unwrap();
+        let displayed = value.display(&module).to_string();
+        assert!(displayed.starts_with("Cons 1 (Cons 2 (Cons 3"));
+        assert!(displayed.ends_with(&format!("(Cons 100000 Nil){}", ")".repeat(99_998))));
+    }
+
+    #[test]
+    fn report_infinite_recursion() {
+        check(
+            "def f x = 1 + (f x); def a = (f 1);",
+            &expect![[r"
+                f = <function>
+                a: stack overflow
+            "]],
+        );
+    }
+}
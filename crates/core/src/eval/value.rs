@@ -0,0 +1,135 @@
+use std::fmt;
+use std::rc::Rc;
+
+use super::{RED_ZONE, STACK_SEGMENT};
+use crate::hir::{Binding, ConstructorIdx, ExprIdx};
+use crate::{Builtin, Module};
+
+#[derive(Clone, Debug)]
+pub enum Value {
+    Int(i64),
+    Bool(bool),
+    Unit,
+    Closure(Rc<Closure>),
+    /// A builtin function applied to fewer arguments than it takes.
+    Builtin(Builtin, Rc<[Value]>),
+    /// A value of a sum type, or its constructor applied to fewer fields than it has.
+    Constructor(ConstructorIdx, Rc<[Value]>),
+}
+
+/// Fields are dropped with an explicit stack, as recursion could overflow the native one
+/// for values as nested as long lists.
+impl Drop for Value {
+    fn drop(&mut self) {
+        let mut values = Vec::new();
+        take_fields(self, &mut values);
+        while let Some(mut value) = values.pop() {
+            take_fields(&mut value, &mut values);
+        }
+    }
+}
+
+/// Moves out the fields of a constructor value which is not shared.
+fn take_fields(value: &mut Value, values: &mut Vec<Value>) {
+    if let Value::Constructor(_, fields) = value {
+        if let Some(fields) = Rc::get_mut(fields) {
+            values.extend(
+                fields
+                    .iter_mut()
+                    .map(|field| std::mem::replace(field, Value::Unit)),
+            );
+        }
+    }
+}
+
+/// A lambda or a let with parameters, together with its environment.
+#[derive(Debug)]
+pub struct Closure {
+    /// The lambda or let expression whose parameters are bound on application.
+    pub(super) owner: ExprIdx,
+    pub(super) arity: usize,
+    pub(super) body: ExprIdx,
+    /// Environment with the parameters applied so far.
+    pub(super) env: Env,
+    pub(super) applied: usize,
+}
+
+impl Value {
+    #[must_use]
+    pub fn display<'a>(&'a self, module: &'a Module) -> ValueDisplay<'a> {
+        ValueDisplay {
+            value: self,
+            module,
+        }
+    }
+}
+
+pub struct ValueDisplay<'a> {
+    value: &'a Value,
+    module: &'a Module,
+}
+
+impl fmt::Display for ValueDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.value {
+            Value::Int(value) => write!(f, "{value}"),
+            Value::Bool(value) => write!(f, "{value}"),
+            Value::Unit => f.write_str("()"),
+            Value::Closure(_) => f.write_str("<function>"),
+            Value::Builtin(builtin, _) => write!(f, "<builtin {}>", builtin.name()),
+            // Fields nest as deeply as the data structure, like evaluation does.
+            Value::Constructor(constructor, fields) => {
+                stacker::maybe_grow(RED_ZONE, STACK_SEGMENT, || {
+                    let constructor = self.module.get_constructor(*constructor);
+                    let name = self.module.lookup_name(constructor.name);
+                    if fields.len() < constructor.fields.len() {
+                        return write!(f, "<constructor {name}>");
+                    }
+                    f.write_str(name)?;
+                    for field in fields.iter() {
+                        let field = field.display(self.module);
+                        match field.value {
+                            Value::Constructor(_, fields) if !fields.is_empty() => {
+                                write!(f, " ({field})")?;
+                            }
+                            _ => write!(f, " {field}")?,
+                        }
+                    }
+                    Ok(())
+                })
+            }
+        }
+    }
+}
+
+/// Values of local bindings, shared between closures.
+#[derive(Clone, Debug, Default)]
+pub(super) struct Env(Option<Rc<EnvEntry>>);
+
+#[derive(Debug)]
+pub(super) struct EnvEntry {
+    binding: Binding,
+    value: Value,
+    parent: Env,
+}
+
+impl Env {
+    pub(super) fn bind(&self, binding: Binding, value: Value) -> Self {
+        Self(Some(Rc::new(EnvEntry {
+            binding,
+            value,
+            parent: self.clone(),
+        })))
+    }
+
+    pub(super) fn lookup(&self, binding: Binding) -> Option<&Value> {
+        let mut env = self;
+        while let Some(entry) = &env.0 {
+            if entry.binding == binding {
+                return Some(&entry.value);
+            }
+            env = &entry.parent;
+        }
+        None
+    }
+}
@@ -1,32 +1,37 @@
 use crate::hir::{
-    Definition, DefinitionIdx, Expr, ExprIdx, Open, OpenIdx, Param, TypeDefinition,
+    Constructor, ConstructorIdx, Definition, DefinitionIdx, Expr, ExprIdx, Literal, LowerError,
+    MatchArm, Open, OpenIdx, Param, Pat, PatIdx, TypeDefinition, TypeDefinitionBody,
     TypeDefinitionIdx, TypeExpr, TypeExprIdx,
 };
-use crate::Name;
-use parser::{nodes as ast, AstToken};
+use crate::{Builtin, Name};
+use parser::{nodes as ast, AstNode, AstToken, SyntaxNodePtr};
 
 use super::module::Module;
+use super::source_map::SourceMap;
 
 impl Module {
     pub fn lower_module(&mut self, ast: &ast::Module) {
         ast.module_items().for_each(|ast| match ast {
             ast::ModuleItem::Definition(ast) => {
                 let definition = self.lower_definition(&ast);
-                self.definitions.alloc(definition);
+                let idx = self.definitions.alloc(definition);
+                self.source_map
+                    .insert_definition(idx, SyntaxNodePtr::new(ast.syntax()));
             }
             ast::ModuleItem::Open(ast) => {
                 let open = self.lower_open(&ast);
                 self.opens.alloc(open);
             }
             ast::ModuleItem::TypeDefinition(ast) => {
-                let type_definition = self.lower_type_definition(&ast);
-                self.type_definitions.alloc(type_definition);
+                let idx = self.lower_type_definition(&ast);
+                self.source_map
+                    .insert_type_definition(idx, SyntaxNodePtr::new(ast.syntax()));
             }
         });
     }
 
     fn lower_definition(&mut self, ast: &ast::Definition) -> Definition {
-        let params = self.lower_params(ast.params());
+        let (params, param_sources) = self.lower_params(ast.params());
         let body = ast.def_body();
         let body = {
             if let Some(block) = body.as_ref().and_then(ast::DefBody::block_expr) {
@@ -37,19 +42,19 @@ impl Module {
             }
         };
 
-        let defn = if params.is_empty() {
-            body
+        let (defn, typ) = if params.is_empty() {
+            (body, self.lower_type_annotation(ast.type_annotation()))
         } else {
-            let body = self.alloc_expr(body);
             let return_type = self.lower_type_annotation(ast.type_annotation());
-            let return_type = self.alloc_type_expr(return_type);
-            self.curry(body, &params, return_type)
+            let source = SyntaxNodePtr::new(ast.syntax());
+            let defn = self.curry(body, &params, &param_sources, return_type, source);
+            (defn, self.alloc_type_expr(TypeExpr::Missing))
         };
-        let defn = self.alloc_expr(defn);
 
         Definition {
             name: self.lower_ident(ast.ident_lit()),
             defn,
+            typ,
         }
     }
 
@@ -59,19 +64,67 @@ impl Module {
         }
     }
 
-    fn lower_type_definition(&mut self, ast: &ast::TypeDefinition) -> TypeDefinition {
-        let defn = self.lower_type_expr(ast.type_expr());
-        let defn = self.alloc_type_expr(defn);
+    fn lower_type_definition(&mut self, ast: &ast::TypeDefinition) -> TypeDefinitionIdx {
+        let name = self.lower_ident(ast.ident_lit());
+        let params = ast
+            .type_params()
+            .map(|ast| {
+                ast.type_params()
+                    .map(|param| self.lower_ident(param.ident_lit()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let Some(variants) = ast.variants() else {
+            let defn = TypeDefinitionBody::Alias(self.lower_type_expr(ast.type_expr()));
+            return self
+                .type_definitions
+                .alloc(TypeDefinition { name, params, defn });
+        };
 
-        TypeDefinition {
+        // Constructors refer to their type, so it is allocated before them.
+        let idx = self.type_definitions.alloc(TypeDefinition {
+            name,
+            params,
+            defn: TypeDefinitionBody::Sum(Box::default()),
+        });
+        let constructors = variants
+            .variants()
+            .map(|variant| self.lower_constructor(idx, &variant))
+            .collect();
+        self.type_definitions[idx].defn = TypeDefinitionBody::Sum(constructors);
+        idx
+    }
+
+    fn lower_constructor(
+        &mut self,
+        parent: TypeDefinitionIdx,
+        ast: &ast::Variant,
+    ) -> ConstructorIdx {
+        let constructor = Constructor {
             name: self.lower_ident(ast.ident_lit()),
-            defn,
-        }
+            parent,
+            fields: ast
+                .fields()
+                .map(|field| self.lower_type_expr(Some(field)))
+                .collect(),
+        };
+        let idx = self.constructors.alloc(constructor);
+        self.source_map
+            .insert_constructor(idx, SyntaxNodePtr::new(ast.syntax()));
+        idx
     }
 
-    fn lower_params(&mut self, ast: Option<ast::Params>) -> Box<[Param]> {
-        ast.map(|ast| ast.params().map(|ast| self.lower_param(&ast)).collect())
-            .unwrap_or_default()
+    /// Lowers the parameters, returning them along with their syntax.
+    fn lower_params(&mut self, ast: Option<ast::Params>) -> (Box<[Param]>, Vec<SyntaxNodePtr>) {
+        let (params, sources): (Vec<_>, _) = ast
+            .map(|ast| {
+                ast.params()
+                    .map(|ast| (self.lower_param(&ast), SyntaxNodePtr::new(ast.syntax())))
+                    .unzip()
+            })
+            .unwrap_or_default();
+        (params.into(), sources)
     }
 
     fn lower_param(&mut self, ast: &ast::Param) -> Param {
@@ -81,96 +134,175 @@ impl Module {
                 .text(),
         );
         let typ = self.lower_type_annotation(ast.type_annotation());
-        Param {
-            name,
-            typ: self.alloc_type_expr(typ),
-        }
+        Param { name, typ }
     }
 
-    fn lower_type_annotation(&mut self, ast: Option<ast::TypeAnnotation>) -> TypeExpr {
-        ast.map_or(TypeExpr::Missing, |ast| {
-            self.lower_type_expr(ast.type_expr())
-        })
+    fn lower_type_annotation(&mut self, ast: Option<ast::TypeAnnotation>) -> TypeExprIdx {
+        match ast {
+            Some(ast) => self.lower_type_expr(ast.type_expr()),
+            None => self.alloc_type_expr(TypeExpr::Missing),
+        }
     }
 
-    fn lower_type_expr(&mut self, type_expr: Option<ast::TypeExpr>) -> TypeExpr {
-        if type_expr.is_none() {
-            return TypeExpr::Missing;
-        }
-        match type_expr.unwrap() {
+    fn lower_type_expr(&mut self, type_expr: Option<ast::TypeExpr>) -> TypeExprIdx {
+        let Some(type_expr) = type_expr else {
+            return self.alloc_type_expr(TypeExpr::Missing);
+        };
+        let source = SyntaxNodePtr::new(type_expr.syntax());
+        let lowered = match type_expr {
             ast::TypeExpr::TypeIdent(ast) => ast.ident_lit().map_or(TypeExpr::Missing, |name| {
                 let name = self.name(name.text());
                 TypeExpr::IdentTypeExpr { name }
             }),
+            ast::TypeExpr::TypeVar(ast) => {
+                ast.quote_ident_lit().map_or(TypeExpr::Missing, |name| {
+                    let name = self.name(&name.text()[1..]);
+                    TypeExpr::TypeVar { name }
+                })
+            }
+            ast::TypeExpr::TypeApp(ast) => {
+                let func = self.lower_type_expr(ast.func());
+                let arg = self.lower_type_expr(ast.arg());
+                TypeExpr::TypeApp { func, arg }
+            }
             ast::TypeExpr::TypeArrow(ast) => {
                 let from = self.lower_type_expr(ast.from());
-                let from = self.alloc_type_expr(from);
-
                 let to = self.lower_type_expr(ast.to());
-                let to = self.alloc_type_expr(to);
                 TypeExpr::TypeArrow { from, to }
             }
-            ast::TypeExpr::TypeParen(ast) => self.lower_type_expr(ast.type_expr()),
-        }
-    }
-
-    fn curry(&mut self, body: ExprIdx, params: &[Param], return_type: TypeExprIdx) -> Expr {
-        let empty_param = Param {
-            name: self.empty_name(),
-            typ: self.alloc_type_expr(TypeExpr::Missing),
+            ast::TypeExpr::TypeParen(ast) => return self.lower_type_expr(ast.type_expr()),
         };
-        let tail_param = params.last().cloned().unwrap_or(empty_param);
-
-        let body = Expr::lambda_expr(tail_param, return_type, body);
-
-        params.iter().rev().skip(1).fold(body, |body, param| {
-            let body = self.alloc_expr(body);
+        self.alloc_type_expr_with_source(lowered, source)
+    }
+
+    fn curry(
+        &mut self,
+        body: ExprIdx,
+        params: &[Param],
+        param_sources: &[SyntaxNodePtr],
+        return_type: TypeExprIdx,
+        source: SyntaxNodePtr,
+    ) -> ExprIdx {
+        if params.is_empty() {
+            let empty_param = Param {
+                name: self.empty_name(),
+                typ: self.alloc_type_expr(TypeExpr::Missing),
+            };
+            let lambda = Expr::lambda_expr(empty_param, return_type, body);
+            return self.alloc_expr_with_source(lambda, source);
+        }
 
-            Expr::lambda_expr(param.clone(), self.alloc_type_expr(TypeExpr::Missing), body)
-        })
+        let (mut body, mut return_type) = (body, return_type);
+        for (index, param) in params.iter().enumerate().rev() {
+            let lambda = Expr::lambda_expr(param.clone(), return_type, body);
+            body = self.alloc_expr(lambda);
+            if index == 0 {
+                self.source_map.insert_expr(body, source);
+            } else {
+                self.source_map.insert_synthesized_expr(body, source);
+                return_type = self.alloc_type_expr(TypeExpr::Missing);
+            }
+            self.source_map.insert_param(body, 0, param_sources[index]);
+        }
+        body
     }
 
-    fn lower_expr(&mut self, expr: Option<ast::Expr>) -> Expr {
-        if expr.is_none() {
-            return Expr::Missing;
-        }
-        match expr.unwrap() {
+    fn lower_expr(&mut self, expr: Option<ast::Expr>) -> ExprIdx {
+        let Some(expr) = expr else {
+            return self.alloc_expr(Expr::Missing);
+        };
+        let source = SyntaxNodePtr::new(expr.syntax());
+        let lowered = match expr {
             ast::Expr::IdentExpr(ast) => ast.ident_lit().map_or(Expr::Missing, |ident| {
                 Expr::ident_expr(self.name(ident.text()))
             }),
             ast::Expr::ParenExpr(ast) => {
-                if let Some(app) = ast.app_expr() {
+                return if let Some(app) = ast.app_expr() {
                     self.lower_app(&app)
                 } else {
                     self.lower_expr(ast.expr())
-                }
+                };
             }
-            ast::Expr::LiteralExpr(ast) => {
-                ast.literal().map_or(Expr::Missing, |lit| match lit.kind() {
+            ast::Expr::LiteralExpr(ast) => match ast.literal() {
+                None => Expr::Missing,
+                Some(lit) => match lit.kind() {
                     ast::LiteralKind::Int => {
-                        Expr::int_expr(lit.syntax().text().parse().expect("Invalid int literal"))
+                        if let Ok(value) = lit.syntax().text().parse() {
+                            Expr::int_expr(value)
+                        } else {
+                            let idx = self.alloc_expr_with_source(Expr::Missing, source);
+                            self.errors.push(LowerError::IntOutOfRange { expr: idx });
+                            return idx;
+                        }
                     }
-
-                    ast::LiteralKind::DummyKw => unreachable!(),
-                })
-            }
+                    ast::LiteralKind::TrueKw => Expr::bool_expr(true),
+                    ast::LiteralKind::FalseKw => Expr::bool_expr(false),
+                },
+            },
             ast::Expr::LambdaExpr(ast) => {
-                let params = self.lower_params(ast.params());
-
+                let (params, param_sources) = self.lower_params(ast.params());
                 let body = self.lower_expr(ast.body());
-                let body = self.alloc_expr(body);
-
                 let return_type = self.lower_type_annotation(ast.type_annotation());
-                let return_type = self.alloc_type_expr(return_type);
 
-                self.curry(body, &params, return_type)
+                return self.curry(body, &params, &param_sources, return_type, source);
             }
-            ast::Expr::BlockExpr(ast) => self.lower_block(&ast),
-            ast::Expr::BinaryExpr(_) => todo!("Binary expressions are not yet supported"),
-        }
+            ast::Expr::IfExpr(ast) => Expr::IfExpr {
+                condition: self.lower_expr(ast.condition()),
+                then_branch: self.lower_expr(ast.then_branch()),
+                else_branch: self.lower_expr(ast.else_branch()),
+            },
+            ast::Expr::MatchExpr(ast) => Expr::MatchExpr {
+                scrutinee: self.lower_expr(ast.scrutinee()),
+                arms: ast
+                    .arms()
+                    .map(|arm| MatchArm {
+                        pat: self.lower_pat(arm.pat()),
+                        expr: self.lower_expr(arm.expr()),
+                    })
+                    .collect(),
+            },
+            ast::Expr::BlockExpr(ast) => return self.lower_block(&ast),
+            ast::Expr::BinaryExpr(ast) => return self.lower_binary(&ast),
+        };
+        self.alloc_expr_with_source(lowered, source)
+    }
+
+    fn lower_pat(&mut self, pat: Option<ast::Pat>) -> PatIdx {
+        let Some(pat) = pat else {
+            return self.alloc_pat(Pat::Missing);
+        };
+        let source = SyntaxNodePtr::new(pat.syntax());
+        let lowered = match pat {
+            ast::Pat::WildcardPat(_) => Pat::Wildcard,
+            ast::Pat::LiteralPat(ast) => match ast.literal() {
+                None => Pat::Missing,
+                Some(lit) => match lit.kind() {
+                    ast::LiteralKind::Int => {
+                        if let Ok(value) = lit.syntax().text().parse() {
+                            Pat::Literal(Literal::IntLiteral(value))
+                        } else {
+                            let idx = self.alloc_pat(Pat::Missing);
+                            self.source_map.insert_pat(idx, source);
+                            self.errors.push(LowerError::IntPatOutOfRange { pat: idx });
+                            return idx;
+                        }
+                    }
+                    ast::LiteralKind::TrueKw => Pat::Literal(Literal::BoolLiteral(true)),
+                    ast::LiteralKind::FalseKw => Pat::Literal(Literal::BoolLiteral(false)),
+                },
+            },
+            ast::Pat::IdentPat(ast) => ast.ident_lit().map_or(Pat::Missing, |ident| Pat::Ident {
+                name: self.name(ident.text()),
+                args: ast.args().map(|arg| self.lower_pat(Some(arg))).collect(),
+            }),
+            ast::Pat::ParenPat(ast) => return self.lower_pat(ast.pat()),
+        };
+        let idx = self.alloc_pat(lowered);
+        self.source_map.insert_pat(idx, source);
+        idx
     }
 
-    fn lower_app(&mut self, app: &ast::AppExpr) -> Expr {
+    fn lower_app(&mut self, app: &ast::AppExpr) -> ExprIdx {
         let func = {
             if let Some(app) = app.app_func() {
                 self.lower_app(&app)
@@ -178,12 +310,39 @@ impl Module {
                 self.lower_expr(app.func())
             }
         };
+        let arg = self.lower_expr(app.arg());
+
+        let source = SyntaxNodePtr::new(app.syntax());
+        self.alloc_expr_with_source(Expr::AppExpr { func, arg }, source)
+    }
+
+    /// Binary expressions are applications of the builtin function named after the operator.
+    fn lower_binary(&mut self, ast: &ast::BinaryExpr) -> ExprIdx {
+        let source = SyntaxNodePtr::new(ast.syntax());
+        let lhs = self.lower_expr(ast.lhs());
+        let rhs = self.lower_expr(ast.rhs());
+
+        let func = ast.infix_symbol().map_or(Expr::Missing, |symbol| {
+            Expr::BuiltinExpr(match symbol.kind() {
+                ast::InfixSymbolKind::Plus => Builtin::Add,
+                ast::InfixSymbolKind::Minus => Builtin::Sub,
+                ast::InfixSymbolKind::Star => Builtin::Mul,
+                ast::InfixSymbolKind::Slash => Builtin::Div,
+            })
+        });
         let func = self.alloc_expr(func);
+        self.source_map.insert_synthesized_expr(func, source);
 
-        let arg = self.lower_expr(app.arg());
-        let arg = self.alloc_expr(arg);
+        let partial = self.alloc_expr(Expr::AppExpr { func, arg: lhs });
+        self.source_map.insert_synthesized_expr(partial, source);
 
-        Expr::AppExpr { func, arg }
+        self.alloc_expr_with_source(
+            Expr::AppExpr {
+                func: partial,
+                arg: rhs,
+            },
+            source,
+        )
     }
 
     fn lower_ident(&mut self, ident: Option<parser::SyntaxToken>) -> Name {
@@ -191,53 +350,79 @@ impl Module {
         self.names.intern(name)
     }
 
-    fn lower_stmt(&mut self, ast: ast::Stmt, cont: ExprIdx) -> Expr {
+    fn lower_stmt(&mut self, ast: &ast::Stmt, cont: ExprIdx) -> ExprIdx {
+        let source = SyntaxNodePtr::new(ast.syntax());
         match ast {
             ast::Stmt::ExprStmt(ast) => {
                 let expr = self.lower_expr(ast.expr());
-                let expr = self.alloc_expr(expr);
-                Expr::let_expr(
+                let lowered = Expr::let_expr(
                     self.empty_name(),
                     vec![].into(),
                     self.alloc_type_expr(TypeExpr::Missing),
                     expr,
                     cont,
-                )
+                );
+                let idx = self.alloc_expr(lowered);
+                self.source_map.insert_synthesized_expr(idx, source);
+                idx
             }
             ast::Stmt::LetStmt(ast) => {
                 let name = self.lower_ident(ast.ident_lit());
 
-                let params = self.lower_params(ast.params());
+                let (params, param_sources) = self.lower_params(ast.params());
 
                 let return_type = self.lower_type_annotation(ast.type_annotation());
-                let return_type = self.alloc_type_expr(return_type);
 
                 let defn = self.lower_expr(ast.def());
-                let defn = self.alloc_expr(defn);
 
-                Expr::let_expr(name, params, return_type, defn, cont)
+                let lowered = Expr::let_expr(name, params, return_type, defn, cont);
+                let idx = self.alloc_expr_with_source(lowered, source);
+                for (index, param_source) in param_sources.into_iter().enumerate() {
+                    self.source_map.insert_param(idx, index, param_source);
+                }
+                idx
             }
         }
     }
 
-    fn lower_block(&mut self, ast: &ast::BlockExpr) -> Expr {
+    fn lower_block(&mut self, ast: &ast::BlockExpr) -> ExprIdx {
         let tail_expr = self.lower_expr(ast.tail_expr());
 
         let stmts: Vec<_> = ast.statements().collect();
-        stmts.iter().rev().fold(tail_expr, |body, stmt| {
-            let body = self.alloc_expr(body);
-            self.lower_stmt(stmt.clone(), body)
-        })
+        stmts
+            .iter()
+            .rev()
+            .fold(tail_expr, |body, stmt| self.lower_stmt(stmt, body))
     }
 
     fn alloc_expr(&mut self, expr: Expr) -> ExprIdx {
         self.expressions.alloc(expr)
     }
 
+    fn alloc_expr_with_source(&mut self, expr: Expr, source: SyntaxNodePtr) -> ExprIdx {
+        let idx = self.alloc_expr(expr);
+        self.source_map.insert_expr(idx, source);
+        idx
+    }
+
+    fn alloc_pat(&mut self, pat: Pat) -> PatIdx {
+        self.patterns.alloc(pat)
+    }
+
     fn alloc_type_expr(&mut self, type_expr: TypeExpr) -> TypeExprIdx {
         self.type_expressions.alloc(type_expr)
     }
 
+    fn alloc_type_expr_with_source(
+        &mut self,
+        type_expr: TypeExpr,
+        source: SyntaxNodePtr,
+    ) -> TypeExprIdx {
+        let idx = self.alloc_type_expr(type_expr);
+        self.source_map.insert_type_expr(idx, source);
+        idx
+    }
+
     fn empty_name(&mut self) -> Name {
         self.names.intern(String::new())
     }
@@ -246,32 +431,95 @@ impl Module {
         self.names.intern(name.into())
     }
 
-    pub(crate) fn get_expr(&self, idx: ExprIdx) -> &Expr {
+    #[must_use]
+    pub fn get_expr(&self, idx: ExprIdx) -> &Expr {
         &self.expressions[idx]
     }
 
-    pub(crate) fn get_type_expr(&self, idx: TypeExprIdx) -> &TypeExpr {
+    #[must_use]
+    pub fn get_pat(&self, idx: PatIdx) -> &Pat {
+        &self.patterns[idx]
+    }
+
+    #[must_use]
+    pub fn get_type_expr(&self, idx: TypeExprIdx) -> &TypeExpr {
         &self.type_expressions[idx]
     }
 
-    pub(crate) fn get_definition(&self, idx: DefinitionIdx) -> &Definition {
+    #[must_use]
+    pub fn get_definition(&self, idx: DefinitionIdx) -> &Definition {
         &self.definitions[idx]
     }
 
-    pub(crate) fn get_open(&self, idx: OpenIdx) -> &Open {
+    #[must_use]
+    pub fn get_open(&self, idx: OpenIdx) -> &Open {
         &self.opens[idx]
     }
 
-    pub(crate) fn get_type_definition(&self, idx: TypeDefinitionIdx) -> &TypeDefinition {
+    #[must_use]
+    pub fn get_type_definition(&self, idx: TypeDefinitionIdx) -> &TypeDefinition {
         &self.type_definitions[idx]
     }
+
+    #[must_use]
+    pub fn get_constructor(&self, idx: ConstructorIdx) -> &Constructor {
+        &self.constructors[idx]
+    }
+
+    pub fn exprs(&self) -> impl Iterator<Item = (ExprIdx, &Expr)> {
+        self.expressions.iter()
+    }
+
+    pub fn definitions(&self) -> impl Iterator<Item = (DefinitionIdx, &Definition)> {
+        self.definitions.iter()
+    }
+
+    pub fn opens(&self) -> impl Iterator<Item = (OpenIdx, &Open)> {
+        self.opens.iter()
+    }
+
+    pub fn type_definitions(&self) -> impl Iterator<Item = (TypeDefinitionIdx, &TypeDefinition)> {
+        self.type_definitions.iter()
+    }
+
+    pub fn constructors(&self) -> impl Iterator<Item = (ConstructorIdx, &Constructor)> {
+        self.constructors.iter()
+    }
+
+    #[must_use]
+    pub fn lookup_name(&self, name: Name) -> &str {
+        self.names.lookup(name)
+    }
+
+    #[must_use]
+    pub fn has_prelude(&self) -> bool {
+        !self.prelude.is_empty()
+    }
+
+    pub fn prelude(&self) -> impl Iterator<Item = (Name, Builtin)> + '_ {
+        self.prelude.iter().copied()
+    }
+
+    #[must_use]
+    pub fn source_map(&self) -> &SourceMap {
+        &self.source_map
+    }
+
+    /// Errors found while lowering the module.
+    #[must_use]
+    pub fn lower_errors(&self) -> &[LowerError] {
+        &self.errors
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::hir::module::{expr_deep_eq, type_expr_deep_eq};
 
-    use super::{Definition, Expr, Module, Param, TypeExpr};
+    use super::{
+        Builtin, Constructor, Definition, Expr, Literal, MatchArm, Module, Param, Pat,
+        TypeDefinition, TypeDefinitionBody, TypeExpr,
+    };
 
     fn unannotated_param(module: &mut Module, name: &str) -> Param {
         Param {
@@ -329,6 +577,7 @@ mod tests {
         let definition = Definition {
             name: expected_module.name("f"),
             defn,
+            typ: expected_module.alloc_type_expr(TypeExpr::Missing),
         };
 
         expected_module.definitions.alloc(definition);
@@ -358,6 +607,7 @@ mod tests {
         let definition = Definition {
             name: expected_module.name("f"),
             defn,
+            typ: expected_module.alloc_type_expr(TypeExpr::Missing),
         };
         expected_module.definitions.alloc(definition);
 
@@ -386,12 +636,90 @@ mod tests {
         let definition = Definition {
             name: expected_module.name("f"),
             defn,
+            typ: expected_module.alloc_type_expr(TypeExpr::Missing),
         };
         expected_module.definitions.alloc(definition);
 
         assert_eq!(actual_module, expected_module);
     }
 
+    #[test]
+    fn lower_sum_type() {
+        let module = parser::parse("type option a = None | Some a;").module();
+        let mut actual_module = Module::default();
+        actual_module.lower_module(&module);
+
+        let mut expected_module = Module::default();
+        let option = expected_module.name("option");
+        let a = expected_module.name("a");
+        let parent = expected_module.type_definitions.alloc(TypeDefinition {
+            name: option,
+            params: Box::new([a]),
+            defn: TypeDefinitionBody::Sum(Box::default()),
+        });
+        let name = expected_module.name("None");
+        let none = expected_module.constructors.alloc(Constructor {
+            name,
+            parent,
+            fields: Box::default(),
+        });
+        let field = expected_module.alloc_type_expr(TypeExpr::IdentTypeExpr { name: a });
+        let name = expected_module.name("Some");
+        let some = expected_module.constructors.alloc(Constructor {
+            name,
+            parent,
+            fields: Box::new([field]),
+        });
+        expected_module.type_definitions[parent].defn =
+            TypeDefinitionBody::Sum(Box::new([none, some]));
+
+        assert_eq!(actual_module, expected_module);
+    }
+
+    #[test]
+    fn lower_if() {
+        let mut module = Module::default();
+        let condition = module.alloc_expr(Expr::bool_expr(true));
+        let then_branch = module.alloc_expr(Expr::int_expr(1));
+        let else_branch = module.alloc_expr(Expr::int_expr(2));
+        module.alloc_expr(Expr::IfExpr {
+            condition,
+            then_branch,
+            else_branch,
+        });
+        check_expr("if true { 1 } else { 2 }", &module);
+    }
+
+    #[test]
+    fn lower_match() {
+        let mut module = Module::default();
+        let x = module.name("x");
+        let some = module.name("Some");
+        let scrutinee = module.alloc_expr(Expr::ident_expr(x));
+        let wildcard = module.alloc_pat(Pat::Wildcard);
+        let some_pat = module.alloc_pat(Pat::Ident {
+            name: some,
+            args: Box::new([wildcard]),
+        });
+        let one = module.alloc_expr(Expr::int_expr(1));
+        let zero_pat = module.alloc_pat(Pat::Literal(Literal::IntLiteral(0)));
+        let two = module.alloc_expr(Expr::int_expr(2));
+        module.alloc_expr(Expr::MatchExpr {
+            scrutinee,
+            arms: Box::new([
+                MatchArm {
+                    pat: some_pat,
+                    expr: one,
+                },
+                MatchArm {
+                    pat: zero_pat,
+                    expr: two,
+                },
+            ]),
+        });
+        check_expr("match x { Some (_) => 1, 0 => 2 }", &module);
+    }
+
     #[test]
     fn lower_ident() {
         let mut module = Module::default();
@@ -431,6 +759,37 @@ mod tests {
         check_expr("(f y z)", &module);
     }
 
+    #[test]
+    fn lower_binary_as_app() {
+        let mut module = Module::default();
+
+        let x = module.name("x");
+        let lhs = module.alloc_expr(Expr::ident_expr(x));
+        let rhs = module.alloc_expr(Expr::int_expr(1));
+        let func = module.alloc_expr(Expr::BuiltinExpr(Builtin::Add));
+        let func = module.alloc_expr(Expr::AppExpr { func, arg: lhs });
+        module.alloc_expr(Expr::AppExpr { func, arg: rhs });
+
+        check_expr("x + 1", &module);
+    }
+
+    #[test]
+    fn lower_binary_precedence() {
+        let mut module = Module::default();
+
+        let x = module.alloc_expr(Expr::int_expr(1));
+        let y = module.alloc_expr(Expr::int_expr(2));
+        let z = module.alloc_expr(Expr::int_expr(3));
+        let func = module.alloc_expr(Expr::BuiltinExpr(Builtin::Mul));
+        let func = module.alloc_expr(Expr::AppExpr { func, arg: y });
+        let product = module.alloc_expr(Expr::AppExpr { func, arg: z });
+        let func = module.alloc_expr(Expr::BuiltinExpr(Builtin::Sub));
+        let func = module.alloc_expr(Expr::AppExpr { func, arg: x });
+        module.alloc_expr(Expr::AppExpr { func, arg: product });
+
+        check_expr("1 - 2 * 3", &module);
+    }
+
     #[test]
     fn lower_lambda() {
         let mut module = Module::default();
@@ -465,6 +824,7 @@ mod tests {
         let definition = Definition {
             name: expected_module.name("f"),
             defn: lambda,
+            typ: expected_module.alloc_type_expr(TypeExpr::Missing),
         };
         expected_module.definitions.alloc(definition);
 
@@ -586,6 +946,7 @@ mod tests {
         let definition = Definition {
             name: expected_module.name("f"),
             defn,
+            typ: expected_module.alloc_type_expr(TypeExpr::Missing),
         };
         expected_module.definitions.alloc(definition);
 
@@ -618,7 +979,8 @@ mod tests {
             tail_expr,
         ));
 
-        let definition = Definition { name: f, defn };
+        let typ = expected_module.alloc_type_expr(TypeExpr::Missing);
+        let definition = Definition { name: f, defn, typ };
         expected_module.definitions.alloc(definition);
 
         assert_eq!(actual_module, expected_module);
@@ -1,24 +1,34 @@
 mod lower;
 mod module;
+mod pretty;
+mod scope;
+mod source_map;
 
 pub use module::Module;
+pub use scope::{Binding, Scope, ScopeIdx, Scopes};
+pub use source_map::{ExprSource, SourceMap};
 
 use la_arena::Idx;
 
-use crate::intern::Interned;
+use crate::{intern::Interned, Builtin};
 
-pub(crate) type ExprIdx = Idx<Expr>;
-pub(crate) type TypeExprIdx = Idx<TypeExpr>;
-pub(crate) type DefinitionIdx = Idx<Definition>;
-pub(crate) type OpenIdx = Idx<Open>;
-pub(crate) type TypeDefinitionIdx = Idx<TypeDefinition>;
+pub type ExprIdx = Idx<Expr>;
+pub type TypeExprIdx = Idx<TypeExpr>;
+pub type DefinitionIdx = Idx<Definition>;
+pub type OpenIdx = Idx<Open>;
+pub type TypeDefinitionIdx = Idx<TypeDefinition>;
+pub type ConstructorIdx = Idx<Constructor>;
+pub type PatIdx = Idx<Pat>;
 
-pub(crate) type Name = Interned<String>;
+pub type Name = Interned<String>;
 
 #[derive(PartialEq, Debug)]
 pub struct Definition {
     pub name: Name,
     pub defn: ExprIdx,
+    /// Type annotation of a definition without parameters.
+    /// Annotations of functions are their return types and are kept on the innermost lambda.
+    pub typ: TypeExprIdx,
 }
 
 #[derive(PartialEq, Debug)]
@@ -29,17 +39,51 @@ pub struct Open {
 #[derive(PartialEq, Debug)]
 pub struct TypeDefinition {
     pub name: Name,
-    pub defn: TypeExprIdx,
+    /// Type variables the definition is parameterized by, like `a` in `type option a`.
+    pub params: Box<[Name]>,
+    pub defn: TypeDefinitionBody,
+}
+
+#[derive(PartialEq, Debug)]
+pub enum TypeDefinitionBody {
+    /// Another name for the type expression.
+    Alias(TypeExprIdx),
+    /// A sum of products, with its constructors in declaration order.
+    Sum(Box<[ConstructorIdx]>),
+}
+
+#[derive(PartialEq, Debug)]
+pub struct Constructor {
+    pub name: Name,
+    /// The sum type the constructor builds values of.
+    pub parent: TypeDefinitionIdx,
+    pub fields: Box<[TypeExprIdx]>,
 }
 
 #[derive(PartialEq, Debug)]
 pub enum Expr {
     Missing,
     LetExpr(Box<LetExpr>),
-    IdentExpr { name: Name },
+    IdentExpr {
+        name: Name,
+    },
+    /// Operator applied as a function, which no binding in scope can shadow.
+    BuiltinExpr(Builtin),
     LambdaExpr(Box<LambdaExpr>),
-    AppExpr { func: ExprIdx, arg: ExprIdx },
+    AppExpr {
+        func: ExprIdx,
+        arg: ExprIdx,
+    },
     LiteralExpr(Literal),
+    IfExpr {
+        condition: ExprIdx,
+        then_branch: ExprIdx,
+        else_branch: ExprIdx,
+    },
+    MatchExpr {
+        scrutinee: ExprIdx,
+        arms: Box<[MatchArm]>,
+    },
 }
 
 impl Expr {
@@ -75,7 +119,6 @@ impl Expr {
         Self::LiteralExpr(Literal::IntLiteral(val))
     }
 
-    #[allow(unused)]
     pub(crate) fn bool_expr(val: bool) -> Self {
         Self::LiteralExpr(Literal::BoolLiteral(val))
     }
@@ -99,11 +142,64 @@ pub struct LambdaExpr {
     pub body: ExprIdx,
 }
 
+#[derive(PartialEq, Debug)]
+pub struct MatchArm {
+    pub pat: PatIdx,
+    pub expr: ExprIdx,
+}
+
+#[derive(PartialEq, Debug)]
+pub enum Pat {
+    Missing,
+    Wildcard,
+    Literal(Literal),
+    /// A constructor applied to patterns of its fields, or a name binding the matched value.
+    /// Which one is decided by name resolution.
+    Ident {
+        name: Name,
+        args: Box<[PatIdx]>,
+    },
+}
+
 #[derive(PartialEq, Debug)]
 pub enum TypeExpr {
     Missing,
-    IdentTypeExpr { name: Name },
-    TypeArrow { from: TypeExprIdx, to: TypeExprIdx },
+    IdentTypeExpr {
+        name: Name,
+    },
+    /// A type variable like `'a`, named without the quote.
+    TypeVar {
+        name: Name,
+    },
+    /// A type definition applied to a type argument, like `option int`.
+    TypeApp {
+        func: TypeExprIdx,
+        arg: TypeExprIdx,
+    },
+    TypeArrow {
+        from: TypeExprIdx,
+        to: TypeExprIdx,
+    },
+}
+
+/// Error in source which parses, found while lowering it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LowerError {
+    /// The integer literal does not fit in 64 bits, and is lowered as missing.
+    IntOutOfRange { expr: ExprIdx },
+    /// The integer literal pattern does not fit in 64 bits, and is lowered as missing.
+    IntPatOutOfRange { pat: PatIdx },
+}
+
+impl LowerError {
+    #[must_use]
+    pub fn message(&self) -> String {
+        match self {
+            LowerError::IntOutOfRange { .. } | LowerError::IntPatOutOfRange { .. } => {
+                "integer literal out of range".into()
+            }
+        }
+    }
 }
 
 #[derive(PartialEq, Debug)]
@@ -112,9 +208,8 @@ pub enum Literal {
     BoolLiteral(bool),
 }
 
-#[allow(unused)]
 #[derive(PartialEq, Debug, Clone)]
 pub struct Param {
-    pub(crate) name: Name,
-    pub(crate) typ: TypeExprIdx,
+    pub name: Name,
+    pub typ: TypeExprIdx,
 }
@@ -1,5 +1,8 @@
-use crate::hir::{Definition, Expr, ExprIdx, Open, Param, TypeDefinition, TypeExpr, TypeExprIdx};
-use crate::{intern::Interner, Name};
+use crate::hir::{
+    Constructor, Definition, Expr, ExprIdx, LowerError, Open, Param, Pat, PatIdx, SourceMap,
+    TypeDefinition, TypeDefinitionBody, TypeExpr, TypeExprIdx,
+};
+use crate::{intern::Interner, Builtin, Name};
 use la_arena::Arena;
 
 #[derive(Debug)]
@@ -8,9 +11,15 @@ pub struct Module {
     pub(super) definitions: Arena<Definition>,
     pub(super) opens: Arena<Open>,
     pub(super) type_definitions: Arena<TypeDefinition>,
+    pub(super) constructors: Arena<Constructor>,
     pub(super) expressions: Arena<Expr>,
+    pub(super) patterns: Arena<Pat>,
     pub(super) type_expressions: Arena<TypeExpr>,
     pub(super) names: Interner<String>,
+    pub(super) source_map: SourceMap,
+    pub(super) errors: Vec<LowerError>,
+    /// Names of the builtins, empty if the prelude is disabled.
+    pub(super) prelude: Vec<(Name, Builtin)>,
 }
 
 fn name_deep_eq(a_module: &Module, b_module: &Module, a: Name, b: Name) -> bool {
@@ -32,9 +41,20 @@ pub(super) fn type_expr_deep_eq(
     let b = b_module.get_type_expr(b);
     match (a, b) {
         (TypeExpr::Missing, TypeExpr::Missing) => true,
-        (TypeExpr::IdentTypeExpr { name: a }, TypeExpr::IdentTypeExpr { name: b }) => {
+        (TypeExpr::IdentTypeExpr { name: a }, TypeExpr::IdentTypeExpr { name: b })
+        | (TypeExpr::TypeVar { name: a }, TypeExpr::TypeVar { name: b }) => {
             name_deep_eq(a_module, b_module, *a, *b)
         }
+        (
+            TypeExpr::TypeApp { func, arg },
+            TypeExpr::TypeApp {
+                func: b_func,
+                arg: b_arg,
+            },
+        ) => {
+            type_expr_deep_eq(a_module, b_module, *func, *b_func)
+                && type_expr_deep_eq(a_module, b_module, *arg, *b_arg)
+        }
         (
             TypeExpr::TypeArrow { from, to },
             TypeExpr::TypeArrow {
@@ -49,12 +69,37 @@ pub(super) fn type_expr_deep_eq(
     }
 }
 
+pub(super) fn pat_deep_eq(a_module: &Module, b_module: &Module, a: PatIdx, b: PatIdx) -> bool {
+    let a = a_module.get_pat(a);
+    let b = b_module.get_pat(b);
+    match (a, b) {
+        (Pat::Missing, Pat::Missing) | (Pat::Wildcard, Pat::Wildcard) => true,
+        (Pat::Literal(a), Pat::Literal(b)) => a == b,
+        (
+            Pat::Ident { name, args },
+            Pat::Ident {
+                name: b_name,
+                args: b_args,
+            },
+        ) => {
+            name_deep_eq(a_module, b_module, *name, *b_name)
+                && args.len() == b_args.len()
+                && args
+                    .iter()
+                    .zip(b_args.iter())
+                    .all(|(a, b)| pat_deep_eq(a_module, b_module, *a, *b))
+        }
+        _ => false,
+    }
+}
+
 pub(super) fn expr_deep_eq(a_module: &Module, b_module: &Module, a: ExprIdx, b: ExprIdx) -> bool {
     let a = a_module.get_expr(a);
     let b = b_module.get_expr(b);
     match (a, b) {
         (Expr::Missing, Expr::Missing) => true,
         (Expr::LiteralExpr(a), Expr::LiteralExpr(b)) => a == b,
+        (Expr::BuiltinExpr(a), Expr::BuiltinExpr(b)) => a == b,
         (Expr::IdentExpr { name: a }, Expr::IdentExpr { name: b }) => {
             name_deep_eq(a_module, b_module, *a, *b)
         }
@@ -68,6 +113,36 @@ pub(super) fn expr_deep_eq(a_module: &Module, b_module: &Module, a: ExprIdx, b:
             expr_deep_eq(a_module, b_module, *func, *b_func)
                 && expr_deep_eq(a_module, b_module, *arg, *b_arg)
         }
+        (
+            Expr::IfExpr {
+                condition,
+                then_branch,
+                else_branch,
+            },
+            Expr::IfExpr {
+                condition: b_condition,
+                then_branch: b_then_branch,
+                else_branch: b_else_branch,
+            },
+        ) => {
+            expr_deep_eq(a_module, b_module, *condition, *b_condition)
+                && expr_deep_eq(a_module, b_module, *then_branch, *b_then_branch)
+                && expr_deep_eq(a_module, b_module, *else_branch, *b_else_branch)
+        }
+        (
+            Expr::MatchExpr { scrutinee, arms },
+            Expr::MatchExpr {
+                scrutinee: b_scrutinee,
+                arms: b_arms,
+            },
+        ) => {
+            expr_deep_eq(a_module, b_module, *scrutinee, *b_scrutinee)
+                && arms.len() == b_arms.len()
+                && arms.iter().zip(b_arms.iter()).all(|(a, b)| {
+                    pat_deep_eq(a_module, b_module, a.pat, b.pat)
+                        && expr_deep_eq(a_module, b_module, a.expr, b.expr)
+                })
+        }
         (Expr::LambdaExpr(l_lambda), Expr::LambdaExpr(b_lambda)) => {
             param_deep_eq(a_module, b_module, &l_lambda.param, &b_lambda.param)
                 && expr_deep_eq(a_module, b_module, l_lambda.body, b_lambda.body)
@@ -101,6 +176,7 @@ impl PartialEq for Module {
             .all(|(a, b)| {
                 expr_deep_eq(self, other, a.defn, b.defn)
                     && name_deep_eq(self, other, a.name, b.name)
+                    && type_expr_deep_eq(self, other, a.typ, b.typ)
             })
             && self
                 .opens
@@ -111,13 +187,53 @@ impl PartialEq for Module {
                 .type_definitions
                 .values()
                 .zip(other.type_definitions.values())
-                .all(|(a, b)| {
-                    name_deep_eq(self, other, a.name, b.name)
-                        && type_expr_deep_eq(self, other, a.defn, b.defn)
-                })
+                .all(|(a, b)| type_definition_deep_eq(self, other, a, b))
     }
 }
 
+fn type_definition_deep_eq(
+    a_module: &Module,
+    b_module: &Module,
+    a: &TypeDefinition,
+    b: &TypeDefinition,
+) -> bool {
+    let bodies_eq = match (&a.defn, &b.defn) {
+        (TypeDefinitionBody::Alias(a), TypeDefinitionBody::Alias(b)) => {
+            type_expr_deep_eq(a_module, b_module, *a, *b)
+        }
+        (TypeDefinitionBody::Sum(a), TypeDefinitionBody::Sum(b)) => {
+            a.len() == b.len()
+                && a.iter().zip(b.iter()).all(|(a, b)| {
+                    let a = &a_module.constructors[*a];
+                    let b = &b_module.constructors[*b];
+                    constructor_deep_eq(a_module, b_module, a, b)
+                })
+        }
+        _ => false,
+    };
+    bodies_eq
+        && name_deep_eq(a_module, b_module, a.name, b.name)
+        && a.params.len() == b.params.len()
+        && a.params
+            .iter()
+            .zip(b.params.iter())
+            .all(|(a, b)| name_deep_eq(a_module, b_module, *a, *b))
+}
+
+fn constructor_deep_eq(
+    a_module: &Module,
+    b_module: &Module,
+    a: &Constructor,
+    b: &Constructor,
+) -> bool {
+    name_deep_eq(a_module, b_module, a.name, b.name)
+        && a.fields.len() == b.fields.len()
+        && a.fields
+            .iter()
+            .zip(b.fields.iter())
+            .all(|(a, b)| type_expr_deep_eq(a_module, b_module, *a, *b))
+}
+
 #[allow(unreachable_code, unused)]
 impl Default for Module {
     fn default() -> Self {
@@ -129,13 +245,29 @@ impl Default for Module {
 impl Module {
     #[must_use]
     pub fn new() -> Self {
+        let mut module = Self::without_prelude();
+        module.prelude = Builtin::ALL
+            .iter()
+            .map(|builtin| (module.names.intern(builtin.name().into()), *builtin))
+            .collect();
+        module
+    }
+
+    /// Module in which the builtin types and functions are not defined.
+    #[must_use]
+    pub fn without_prelude() -> Self {
         Self {
             expressions: Arena::new(),
+            patterns: Arena::new(),
             type_expressions: Arena::new(),
             definitions: Arena::new(),
             opens: Arena::new(),
             type_definitions: Arena::new(),
+            constructors: Arena::new(),
             names: Interner::new(),
+            source_map: SourceMap::default(),
+            errors: Vec::new(),
+            prelude: Vec::new(),
         }
     }
 }
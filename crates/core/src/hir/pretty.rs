@@ -0,0 +1,217 @@
+use std::fmt::Write;
+
+use crate::hir::{Expr, ExprIdx, Literal, Param, Pat, PatIdx, TypeExpr, TypeExprIdx};
+use crate::Module;
+
+impl Module {
+    /// Renders the expression as an indented tree, one node per line.
+    #[must_use]
+    pub fn debug_expr(&self, expr: ExprIdx) -> String {
+        let mut buf = String::new();
+        self.debug_expr_into(&mut buf, expr, 0);
+        buf
+    }
+
+    /// Renders the type expression in the surface syntax, `_` standing for missing types.
+    #[must_use]
+    pub fn debug_type_expr(&self, type_expr: TypeExprIdx) -> String {
+        match self.get_type_expr(type_expr) {
+            TypeExpr::Missing => "_".into(),
+            TypeExpr::IdentTypeExpr { name } => self.lookup_name(*name).into(),
+            TypeExpr::TypeVar { name } => format!("'{}", self.lookup_name(*name)),
+            TypeExpr::TypeApp { func, arg } => {
+                let func_text = self.debug_type_expr(*func);
+                let arg_text = self.debug_type_expr(*arg);
+                match self.get_type_expr(*arg) {
+                    TypeExpr::TypeApp { .. } | TypeExpr::TypeArrow { .. } => {
+                        format!("{func_text} ({arg_text})")
+                    }
+                    _ => format!("{func_text} {arg_text}"),
+                }
+            }
+            TypeExpr::TypeArrow { from, to } => {
+                let from_text = self.debug_type_expr(*from);
+                let to_text = self.debug_type_expr(*to);
+                match self.get_type_expr(*from) {
+                    TypeExpr::TypeArrow { .. } => format!("({from_text}) -> {to_text}"),
+                    _ => format!("{from_text} -> {to_text}"),
+                }
+            }
+        }
+    }
+
+    /// Renders the pattern in the surface syntax, `_` standing for missing patterns.
+    #[must_use]
+    pub fn debug_pat(&self, pat: PatIdx) -> String {
+        match self.get_pat(pat) {
+            Pat::Missing | Pat::Wildcard => "_".into(),
+            Pat::Literal(Literal::IntLiteral(value)) => value.to_string(),
+            Pat::Literal(Literal::BoolLiteral(value)) => value.to_string(),
+            Pat::Ident { name, args } => {
+                let mut text = self.lookup_name(*name).to_string();
+                for arg in args {
+                    match self.get_pat(*arg) {
+                        Pat::Ident { args, .. } if !args.is_empty() => {
+                            write!(text, " ({})", self.debug_pat(*arg)).unwrap();
+                        }
+                        _ => write!(text, " {}", self.debug_pat(*arg)).unwrap(),
+                    }
+                }
+                text
+            }
+        }
+    }
+
+    fn debug_expr_into(&self, buf: &mut String, expr: ExprIdx, indent: usize) {
+        buf.push_str(&"  ".repeat(indent));
+        match self.get_expr(expr) {
+            Expr::Missing => buf.push_str("Missing\n"),
+            Expr::IdentExpr { name } => writeln!(buf, "Ident {}", self.lookup_name(*name)).unwrap(),
+            Expr::BuiltinExpr(builtin) => writeln!(buf, "Builtin {}", builtin.name()).unwrap(),
+            Expr::LiteralExpr(Literal::IntLiteral(value)) => writeln!(buf, "Int {value}").unwrap(),
+            Expr::LiteralExpr(Literal::BoolLiteral(value)) => {
+                writeln!(buf, "Bool {value}").unwrap();
+            }
+            Expr::AppExpr { func, arg } => {
+                buf.push_str("App\n");
+                self.debug_expr_into(buf, *func, indent + 1);
+                self.debug_expr_into(buf, *arg, indent + 1);
+            }
+            Expr::IfExpr {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                buf.push_str("If\n");
+                self.debug_expr_into(buf, *condition, indent + 1);
+                self.debug_expr_into(buf, *then_branch, indent + 1);
+                self.debug_expr_into(buf, *else_branch, indent + 1);
+            }
+            Expr::MatchExpr { scrutinee, arms } => {
+                buf.push_str("Match\n");
+                self.debug_expr_into(buf, *scrutinee, indent + 1);
+                for arm in arms {
+                    let indent = indent + 1;
+                    writeln!(
+                        buf,
+                        "{}Arm {}",
+                        "  ".repeat(indent),
+                        self.debug_pat(arm.pat)
+                    )
+                    .unwrap();
+                    self.debug_expr_into(buf, arm.expr, indent + 1);
+                }
+            }
+            Expr::LambdaExpr(lambda) => {
+                buf.push_str("Lambda");
+                self.debug_params_into(buf, std::slice::from_ref(&lambda.param));
+                self.debug_annotation_into(buf, lambda.return_type);
+                buf.push('\n');
+                self.debug_expr_into(buf, lambda.body, indent + 1);
+            }
+            Expr::LetExpr(let_expr) => {
+                write!(buf, "Let {}", self.lookup_name(let_expr.name)).unwrap();
+                self.debug_params_into(buf, &let_expr.params);
+                self.debug_annotation_into(buf, let_expr.return_type);
+                buf.push('\n');
+                self.debug_expr_into(buf, let_expr.defn, indent + 1);
+                self.debug_expr_into(buf, let_expr.body, indent + 1);
+            }
+        }
+    }
+
+    fn debug_params_into(&self, buf: &mut String, params: &[Param]) {
+        for param in params {
+            let name = self.lookup_name(param.name);
+            match self.get_type_expr(param.typ) {
+                TypeExpr::Missing => write!(buf, " {name}").unwrap(),
+                _ => write!(buf, " ({name}: {})", self.debug_type_expr(param.typ)).unwrap(),
+            }
+        }
+    }
+
+    fn debug_annotation_into(&self, buf: &mut String, type_expr: TypeExprIdx) {
+        if !matches!(self.get_type_expr(type_expr), TypeExpr::Missing) {
+            write!(buf, " : {}", self.debug_type_expr(type_expr)).unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use crate::Module;
+
+    fn check(text: &str, expected: &Expect) {
+        let mut module = Module::new();
+        module.lower_module(&parser::parse(text).module());
+        let (_, definition) = module.definitions().next().unwrap();
+        expected.assert_eq(&module.debug_expr(definition.defn));
+    }
+
+    #[test]
+    fn debug_binary() {
+        check(
+            "def x = 1 + 2;",
+            &expect![[r"
+                App
+                  App
+                    Builtin add
+                    Int 1
+                  Int 2
+            "]],
+        );
+    }
+
+    #[test]
+    fn debug_if() {
+        check(
+            "def x = if false { 1 } else if true { 2 } else { 3 };",
+            &expect![[r"
+                If
+                  Bool false
+                  Int 1
+                  If
+                    Bool true
+                    Int 2
+                    Int 3
+            "]],
+        );
+    }
+
+    #[test]
+    fn debug_match() {
+        check(
+            "def x = match (Pair 1 None) { Pair 0 (Some (Some _)) => true, Pair _ b => b, };",
+            &expect![[r"
+                Match
+                  App
+                    App
+                      Ident Pair
+                      Int 1
+                    Ident None
+                  Arm Pair 0 (Some (Some _))
+                    Bool true
+                  Arm Pair _ b
+                    Ident b
+            "]],
+        );
+    }
+
+    #[test]
+    fn debug_function() {
+        check(
+            "def f (x: int) (g: int -> int) : int { let y = (g x); y }",
+            &expect![[r"
+                Lambda (x: int)
+                  Lambda (g: int -> int) : int
+                    Let y
+                      App
+                        Ident g
+                        Ident x
+                      Ident y
+            "]],
+        );
+    }
+}
@@ -0,0 +1,483 @@
+use la_arena::{Arena, ArenaMap, Idx};
+
+use crate::hir::{ConstructorIdx, DefinitionIdx, Expr, ExprIdx, Name, Param, Pat, PatIdx};
+use crate::{Builtin, Module};
+
+pub type ScopeIdx = Idx<Scope>;
+
+/// The binder an identifier refers to.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Binding {
+    Builtin(Builtin),
+    Definition(DefinitionIdx),
+    Constructor(ConstructorIdx),
+    /// Name bound by the let expression at the index.
+    Let(ExprIdx),
+    /// The `index`-th parameter of the lambda or let expression at `owner`.
+    Param {
+        owner: ExprIdx,
+        index: usize,
+    },
+    /// Name bound by the identifier pattern at the index.
+    Pat(PatIdx),
+}
+
+#[derive(Debug, Default)]
+pub struct Scope {
+    parent: Option<ScopeIdx>,
+    entries: Vec<(Name, Binding)>,
+}
+
+/// Lexical scopes of a module, with every identifier resolved to its binder.
+#[derive(Debug, Default)]
+pub struct Scopes {
+    arena: Arena<Scope>,
+    scope_of_expr: ArenaMap<ExprIdx, ScopeIdx>,
+    resolutions: ArenaMap<ExprIdx, Binding>,
+    unresolved: Vec<ExprIdx>,
+    pat_constructors: ArenaMap<PatIdx, ConstructorIdx>,
+    unresolved_constructors: Vec<PatIdx>,
+    duplicates: Vec<Binding>,
+}
+
+impl Scopes {
+    #[must_use]
+    pub fn new(module: &Module) -> Self {
+        let mut scopes = Self::default();
+        let prelude = scopes.arena.alloc(Scope {
+            parent: None,
+            entries: module
+                .prelude()
+                .map(|(name, builtin)| (name, Binding::Builtin(builtin)))
+                .collect(),
+        });
+        let root = scopes.child(prelude, Vec::new());
+
+        // All definitions are mutually recursive, so each one is visible in every body.
+        let mut top_level: Vec<_> = module
+            .definitions()
+            .map(|(idx, definition)| (definition.name, Binding::Definition(idx)))
+            .chain(
+                module
+                    .constructors()
+                    .map(|(idx, constructor)| (constructor.name, Binding::Constructor(idx))),
+            )
+            .collect();
+        // Of the items with the same name, the first one in the source is kept.
+        top_level.sort_by_key(|(_, binding)| {
+            module
+                .source_map()
+                .binding_syntax(*binding)
+                .map(|ptr| ptr.text_range().start())
+        });
+        for (name, binding) in top_level {
+            if scopes.lookup_in(root, name).is_none() {
+                scopes.arena[root].entries.push((name, binding));
+            } else {
+                scopes.duplicates.push(binding);
+            }
+        }
+        for (_, definition) in module.definitions() {
+            scopes.resolve_expr(module, definition.defn, root);
+        }
+        scopes
+    }
+
+    /// Binder of the identifier expression, `None` if it is unresolved or not an identifier.
+    #[must_use]
+    pub fn resolution(&self, expr: ExprIdx) -> Option<Binding> {
+        self.resolutions.get(expr).copied()
+    }
+
+    /// Identifier expressions which do not refer to any binder.
+    #[must_use]
+    pub fn unresolved(&self) -> &[ExprIdx] {
+        &self.unresolved
+    }
+
+    /// Constructor matched by the identifier pattern, `None` if the pattern binds a name.
+    #[must_use]
+    pub fn pat_constructor(&self, pat: PatIdx) -> Option<ConstructorIdx> {
+        self.pat_constructors.get(pat).copied()
+    }
+
+    /// Identifier patterns with arguments whose name is not a constructor.
+    #[must_use]
+    pub fn unresolved_constructors(&self) -> &[PatIdx] {
+        &self.unresolved_constructors
+    }
+
+    /// Definitions and constructors named like an earlier one, which are not in scope.
+    #[must_use]
+    pub fn duplicates(&self) -> &[Binding] {
+        &self.duplicates
+    }
+
+    /// Patterns matching the constructor.
+    pub fn constructor_patterns(
+        &self,
+        constructor: ConstructorIdx,
+    ) -> impl Iterator<Item = PatIdx> + '_ {
+        self.pat_constructors
+            .iter()
+            .filter(move |(_, matched)| **matched == constructor)
+            .map(|(pat, _)| pat)
+    }
+
+    /// Scope in which the expression is evaluated.
+    #[must_use]
+    pub fn scope_of_expr(&self, expr: ExprIdx) -> Option<ScopeIdx> {
+        self.scope_of_expr.get(expr).copied()
+    }
+
+    /// Looks the name up in the scope and its ancestors.
+    #[must_use]
+    pub fn lookup(&self, scope: ScopeIdx, name: Name) -> Option<Binding> {
+        self.ancestors(scope)
+            .find_map(|scope| self.lookup_in(scope, name))
+    }
+
+    /// Names visible in the scope, innermost first, without the shadowed ones.
+    pub fn visible_names(&self, scope: ScopeIdx) -> impl Iterator<Item = (Name, Binding)> + '_ {
+        let mut seen = Vec::new();
+        self.ancestors(scope)
+            .flat_map(|scope| self.arena[scope].entries.iter().rev())
+            .filter(move |(name, _)| {
+                if seen.contains(name) {
+                    false
+                } else {
+                    seen.push(*name);
+                    true
+                }
+            })
+            .copied()
+    }
+
+    /// Identifier expressions referring to the binding.
+    pub fn references(&self, binding: Binding) -> impl Iterator<Item = ExprIdx> + '_ {
+        self.resolutions
+            .iter()
+            .filter(move |(_, resolved)| **resolved == binding)
+            .map(|(expr, _)| expr)
+    }
+
+    fn ancestors(&self, scope: ScopeIdx) -> impl Iterator<Item = ScopeIdx> + '_ {
+        std::iter::successors(Some(scope), |scope| self.arena[*scope].parent)
+    }
+
+    fn lookup_in(&self, scope: ScopeIdx, name: Name) -> Option<Binding> {
+        self.arena[scope]
+            .entries
+            .iter()
+            .rev()
+            .find(|(entry, _)| *entry == name)
+            .map(|(_, binding)| *binding)
+    }
+
+    fn child(&mut self, parent: ScopeIdx, entries: Vec<(Name, Binding)>) -> ScopeIdx {
+        self.arena.alloc(Scope {
+            parent: Some(parent),
+            entries,
+        })
+    }
+
+    fn param_entries(module: &Module, owner: ExprIdx, params: &[Param]) -> Vec<(Name, Binding)> {
+        params
+            .iter()
+            .enumerate()
+            .filter(|(_, param)| !module.lookup_name(param.name).is_empty())
+            .map(|(index, param)| (param.name, Binding::Param { owner, index }))
+            .collect()
+    }
+
+    fn resolve_expr(&mut self, module: &Module, expr: ExprIdx, scope: ScopeIdx) {
+        self.scope_of_expr.insert(expr, scope);
+        match module.get_expr(expr) {
+            Expr::Missing | Expr::LiteralExpr(_) | Expr::BuiltinExpr(_) => {}
+            Expr::IdentExpr { name } => {
+                if let Some(binding) = self.lookup(scope, *name) {
+                    self.resolutions.insert(expr, binding);
+                } else {
+                    self.unresolved.push(expr);
+                }
+            }
+            Expr::AppExpr { func, arg } => {
+                self.resolve_expr(module, *func, scope);
+                self.resolve_expr(module, *arg, scope);
+            }
+            Expr::IfExpr {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.resolve_expr(module, *condition, scope);
+                self.resolve_expr(module, *then_branch, scope);
+                self.resolve_expr(module, *else_branch, scope);
+            }
+            Expr::MatchExpr { scrutinee, arms } => {
+                self.resolve_expr(module, *scrutinee, scope);
+                for arm in arms {
+                    let mut entries = Vec::new();
+                    self.resolve_pat(module, arm.pat, scope, &mut entries);
+                    let arm_scope = self.child(scope, entries);
+                    self.resolve_expr(module, arm.expr, arm_scope);
+                }
+            }
+            Expr::LambdaExpr(lambda) => {
+                let entries =
+                    Self::param_entries(module, expr, std::slice::from_ref(&lambda.param));
+                let body_scope = self.child(scope, entries);
+                self.resolve_expr(module, lambda.body, body_scope);
+            }
+            Expr::LetExpr(let_expr) => {
+                let defn_scope = if let_expr.params.is_empty() {
+                    scope
+                } else {
+                    let entries = Self::param_entries(module, expr, &let_expr.params);
+                    self.child(scope, entries)
+                };
+                self.resolve_expr(module, let_expr.defn, defn_scope);
+
+                let body_scope = if module.lookup_name(let_expr.name).is_empty() {
+                    scope
+                } else {
+                    self.child(scope, vec![(let_expr.name, Binding::Let(expr))])
+                };
+                self.resolve_expr(module, let_expr.body, body_scope);
+            }
+        }
+    }
+
+    /// A name without arguments refers to a constructor if one is visible,
+    /// otherwise it binds the matched value, collected in `entries`.
+    fn resolve_pat(
+        &mut self,
+        module: &Module,
+        pat: PatIdx,
+        scope: ScopeIdx,
+        entries: &mut Vec<(Name, Binding)>,
+    ) {
+        let Pat::Ident { name, args } = module.get_pat(pat) else {
+            return;
+        };
+        match self.lookup(scope, *name) {
+            Some(Binding::Constructor(constructor)) => {
+                self.pat_constructors.insert(pat, constructor);
+            }
+            _ if args.is_empty() => entries.push((*name, Binding::Pat(pat))),
+            _ => self.unresolved_constructors.push(pat),
+        }
+        for arg in args {
+            self.resolve_pat(module, *arg, scope, entries);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write;
+
+    use expect_test::{expect, Expect};
+
+    use super::{Binding, Scopes};
+    use crate::{Expr, Module};
+
+    fn check(text: &str, expected: &Expect) {
+        let mut module = Module::default();
+        module.lower_module(&parser::parse(text).module());
+        let scopes = Scopes::new(&module);
+
+        let mut actual = String::new();
+        for (idx, expr) in module.exprs() {
+            if let Expr::BuiltinExpr(builtin) = expr {
+                writeln!(actual, "operator: builtin {}", builtin.name()).unwrap();
+                continue;
+            }
+            let Expr::IdentExpr { name } = expr else {
+                continue;
+            };
+            let resolution = match scopes.resolution(idx) {
+                None => "unresolved".to_string(),
+                Some(Binding::Builtin(builtin)) => format!("builtin {}", builtin.name()),
+                Some(Binding::Definition(definition)) => {
+                    let name = module.get_definition(definition).name;
+                    format!("definition {}", module.lookup_name(name))
+                }
+                Some(Binding::Constructor(constructor)) => {
+                    let name = module.get_constructor(constructor).name;
+                    format!("constructor {}", module.lookup_name(name))
+                }
+                Some(Binding::Let(let_expr)) => {
+                    let Expr::LetExpr(let_expr) = module.get_expr(let_expr) else {
+                        unreachable!()
+                    };
+                    format!("let {}", module.lookup_name(let_expr.name))
+                }
+                Some(Binding::Param { owner, index }) => match module.get_expr(owner) {
+                    Expr::LambdaExpr(lambda) => {
+                        format!("param {} of lambda", module.lookup_name(lambda.param.name))
+                    }
+                    Expr::LetExpr(let_expr) => format!(
+                        "param {} of let {}",
+                        module.lookup_name(let_expr.params[index].name),
+                        module.lookup_name(let_expr.name)
+                    ),
+                    _ => unreachable!(),
+                },
+                Some(Binding::Pat(pat)) => format!("pattern {}", module.debug_pat(pat)),
+            };
+            writeln!(actual, "{}: {resolution}", module.lookup_name(*name)).unwrap();
+        }
+        assert_eq!(
+            scopes.unresolved().len(),
+            actual.matches("unresolved").count()
+        );
+        expected.assert_eq(&actual);
+    }
+
+    #[test]
+    fn resolve_params() {
+        check(
+            "def f x y = (x y);",
+            &expect![[r"
+                x: param x of lambda
+                y: param y of lambda
+            "]],
+        );
+    }
+
+    #[test]
+    fn definitions_are_mutually_recursive() {
+        check(
+            "def a = b; def b = a;",
+            &expect![[r"
+                b: definition b
+                a: definition a
+            "]],
+        );
+    }
+
+    #[test]
+    fn let_is_visible_after_binding() {
+        check(
+            "def f { let x = x; x }",
+            &expect![[r"
+                x: let x
+                x: unresolved
+            "]],
+        );
+    }
+
+    #[test]
+    fn let_params_are_visible_in_definition_only() {
+        check(
+            "def f { let g x = x; (g x) }",
+            &expect![[r"
+                g: let g
+                x: unresolved
+                x: param x of let g
+            "]],
+        );
+    }
+
+    #[test]
+    fn inner_bindings_shadow_outer() {
+        check(
+            "def x = 1; def f x { let x = x; x }",
+            &expect![[r"
+                x: let x
+                x: param x of lambda
+            "]],
+        );
+    }
+
+    #[test]
+    fn resolve_constructors() {
+        check(
+            "type t = A | B; def A = 1; def f B = (A B); def g = B;",
+            &expect![[r"
+                A: constructor A
+                B: param B of lambda
+                B: constructor B
+            "]],
+        );
+    }
+
+    #[test]
+    fn match_arms_bind_pattern_names() {
+        check(
+            "type t = A | B int; def f x = match x { B y => y, A => x, y => (y B) };",
+            &expect![[r"
+                x: param x of lambda
+                y: pattern y
+                x: param x of lambda
+                y: pattern y
+                B: constructor B
+            "]],
+        );
+    }
+
+    #[test]
+    fn resolve_builtins() {
+        check(
+            "def f x = x + 1; def g = (eq f);",
+            &expect![[r"
+                x: param x of lambda
+                operator: builtin add
+                eq: builtin eq
+                f: definition f
+            "]],
+        );
+    }
+
+    #[test]
+    fn definitions_shadow_builtins() {
+        check(
+            "def add = 1; def f = add; def g { let sub x y = x; (sub 1 2) + 3 - 4 }",
+            &expect![[r"
+                add: definition add
+                sub: let sub
+                operator: builtin add
+                operator: builtin sub
+                x: param x of let sub
+            "]],
+        );
+    }
+
+    #[test]
+    fn duplicates_are_not_in_scope() {
+        let text = "type t = A | B; def a = 1; def a = true; def B = 2; def f = (a B);";
+        let mut module = Module::default();
+        module.lower_module(&parser::parse(text).module());
+        let scopes = Scopes::new(&module);
+        let duplicates: Vec<_> = scopes
+            .duplicates()
+            .iter()
+            .map(|binding| match binding {
+                Binding::Definition(definition) => module.get_definition(*definition).name,
+                Binding::Constructor(constructor) => module.get_constructor(*constructor).name,
+                _ => unreachable!(),
+            })
+            .map(|name| module.lookup_name(name))
+            .collect();
+        assert_eq!(duplicates, ["a", "B"]);
+
+        check(
+            text,
+            &expect![[r"
+                a: definition a
+                B: constructor B
+            "]],
+        );
+    }
+
+    #[test]
+    fn report_unresolved() {
+        check(
+            "def f = (g 1);",
+            &expect![[r"
+                g: unresolved
+            "]],
+        );
+    }
+}
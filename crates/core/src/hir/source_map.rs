@@ -0,0 +1,350 @@
+use std::collections::HashMap;
+
+use la_arena::ArenaMap;
+use parser::{SyntaxNode, SyntaxNodePtr};
+
+use crate::hir::{
+    Binding, ConstructorIdx, DefinitionIdx, ExprIdx, PatIdx, TypeDefinitionIdx, TypeExprIdx,
+};
+
+/// Origin of a lowered expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExprSource {
+    pub ptr: SyntaxNodePtr,
+    /// Set for expressions introduced by desugaring, like the inner lambdas of curried functions
+    /// or lets wrapping expression statements, which share their syntax with other expressions.
+    pub synthesized: bool,
+}
+
+/// Links HIR nodes to the syntax they were lowered from and back.
+#[derive(Debug, Default)]
+pub struct SourceMap {
+    expr_map: ArenaMap<ExprIdx, ExprSource>,
+    expr_map_back: HashMap<SyntaxNodePtr, ExprIdx>,
+    pat_map: ArenaMap<PatIdx, SyntaxNodePtr>,
+    pat_map_back: HashMap<SyntaxNodePtr, PatIdx>,
+    type_expr_map: ArenaMap<TypeExprIdx, SyntaxNodePtr>,
+    type_expr_map_back: HashMap<SyntaxNodePtr, TypeExprIdx>,
+    definition_map: ArenaMap<DefinitionIdx, SyntaxNodePtr>,
+    definition_map_back: HashMap<SyntaxNodePtr, DefinitionIdx>,
+    type_definition_map: ArenaMap<TypeDefinitionIdx, SyntaxNodePtr>,
+    type_definition_map_back: HashMap<SyntaxNodePtr, TypeDefinitionIdx>,
+    constructor_map: ArenaMap<ConstructorIdx, SyntaxNodePtr>,
+    constructor_map_back: HashMap<SyntaxNodePtr, ConstructorIdx>,
+    /// Parameters keyed by their owner and position, as in [`Binding::Param`].
+    param_map: HashMap<(ExprIdx, usize), SyntaxNodePtr>,
+    param_map_back: HashMap<SyntaxNodePtr, (ExprIdx, usize)>,
+}
+
+impl SourceMap {
+    pub(super) fn insert_expr(&mut self, expr: ExprIdx, ptr: SyntaxNodePtr) {
+        self.expr_map.insert(
+            expr,
+            ExprSource {
+                ptr,
+                synthesized: false,
+            },
+        );
+        self.expr_map_back.insert(ptr, expr);
+    }
+
+    pub(super) fn insert_synthesized_expr(&mut self, expr: ExprIdx, ptr: SyntaxNodePtr) {
+        self.expr_map.insert(
+            expr,
+            ExprSource {
+                ptr,
+                synthesized: true,
+            },
+        );
+    }
+
+    pub(super) fn insert_pat(&mut self, pat: PatIdx, ptr: SyntaxNodePtr) {
+        self.pat_map.insert(pat, ptr);
+        self.pat_map_back.insert(ptr, pat);
+    }
+
+    pub(super) fn insert_type_expr(&mut self, type_expr: TypeExprIdx, ptr: SyntaxNodePtr) {
+        self.type_expr_map.insert(type_expr, ptr);
+        self.type_expr_map_back.insert(ptr, type_expr);
+    }
+
+    pub(super) fn insert_definition(&mut self, definition: DefinitionIdx, ptr: SyntaxNodePtr) {
+        self.definition_map.insert(definition, ptr);
+        self.definition_map_back.insert(ptr, definition);
+    }
+
+    pub(super) fn insert_type_definition(
+        &mut self,
+        type_definition: TypeDefinitionIdx,
+        ptr: SyntaxNodePtr,
+    ) {
+        self.type_definition_map.insert(type_definition, ptr);
+        self.type_definition_map_back.insert(ptr, type_definition);
+    }
+
+    pub(super) fn insert_constructor(&mut self, constructor: ConstructorIdx, ptr: SyntaxNodePtr) {
+        self.constructor_map.insert(constructor, ptr);
+        self.constructor_map_back.insert(ptr, constructor);
+    }
+
+    pub(super) fn insert_param(&mut self, owner: ExprIdx, index: usize, ptr: SyntaxNodePtr) {
+        self.param_map.insert((owner, index), ptr);
+        self.param_map_back.insert(ptr, (owner, index));
+    }
+
+    /// Syntax of the expression, `None` for expressions missing from the source.
+    #[must_use]
+    pub fn expr_syntax(&self, expr: ExprIdx) -> Option<SyntaxNodePtr> {
+        self.expr_source(expr).map(|source| source.ptr)
+    }
+
+    #[must_use]
+    pub fn expr_source(&self, expr: ExprIdx) -> Option<ExprSource> {
+        self.expr_map.get(expr).copied()
+    }
+
+    #[must_use]
+    pub fn is_synthesized(&self, expr: ExprIdx) -> bool {
+        self.expr_source(expr)
+            .is_some_and(|source| source.synthesized)
+    }
+
+    /// Syntax of the pattern, `None` for patterns missing from the source.
+    #[must_use]
+    pub fn pat_syntax(&self, pat: PatIdx) -> Option<SyntaxNodePtr> {
+        self.pat_map.get(pat).copied()
+    }
+
+    #[must_use]
+    pub fn syntax_pat(&self, ptr: SyntaxNodePtr) -> Option<PatIdx> {
+        self.pat_map_back.get(&ptr).copied()
+    }
+
+    /// Syntax of the type expression, `None` for type expressions missing from the source.
+    #[must_use]
+    pub fn type_expr_syntax(&self, type_expr: TypeExprIdx) -> Option<SyntaxNodePtr> {
+        self.type_expr_map.get(type_expr).copied()
+    }
+
+    /// Expression lowered from exactly this node; synthesized expressions are never returned.
+    #[must_use]
+    pub fn syntax_expr(&self, ptr: SyntaxNodePtr) -> Option<ExprIdx> {
+        self.expr_map_back.get(&ptr).copied()
+    }
+
+    #[must_use]
+    pub fn syntax_type_expr(&self, ptr: SyntaxNodePtr) -> Option<TypeExprIdx> {
+        self.type_expr_map_back.get(&ptr).copied()
+    }
+
+    #[must_use]
+    pub fn definition_syntax(&self, definition: DefinitionIdx) -> Option<SyntaxNodePtr> {
+        self.definition_map.get(definition).copied()
+    }
+
+    #[must_use]
+    pub fn syntax_definition(&self, ptr: SyntaxNodePtr) -> Option<DefinitionIdx> {
+        self.definition_map_back.get(&ptr).copied()
+    }
+
+    #[must_use]
+    pub fn type_definition_syntax(
+        &self,
+        type_definition: TypeDefinitionIdx,
+    ) -> Option<SyntaxNodePtr> {
+        self.type_definition_map.get(type_definition).copied()
+    }
+
+    #[must_use]
+    pub fn syntax_type_definition(&self, ptr: SyntaxNodePtr) -> Option<TypeDefinitionIdx> {
+        self.type_definition_map_back.get(&ptr).copied()
+    }
+
+    #[must_use]
+    pub fn constructor_syntax(&self, constructor: ConstructorIdx) -> Option<SyntaxNodePtr> {
+        self.constructor_map.get(constructor).copied()
+    }
+
+    #[must_use]
+    pub fn syntax_constructor(&self, ptr: SyntaxNodePtr) -> Option<ConstructorIdx> {
+        self.constructor_map_back.get(&ptr).copied()
+    }
+
+    /// Syntax of the `index`-th parameter of the lambda or let expression at `owner`.
+    #[must_use]
+    pub fn param_syntax(&self, owner: ExprIdx, index: usize) -> Option<SyntaxNodePtr> {
+        self.param_map.get(&(owner, index)).copied()
+    }
+
+    #[must_use]
+    pub fn syntax_param(&self, ptr: SyntaxNodePtr) -> Option<(ExprIdx, usize)> {
+        self.param_map_back.get(&ptr).copied()
+    }
+
+    /// Node introducing the binding: a `DEFINITION`, `VARIANT`, `LET_STMT`, `PARAM`
+    /// or `IDENT_PAT`, `None` for builtins.
+    #[must_use]
+    pub fn binding_syntax(&self, binding: Binding) -> Option<SyntaxNodePtr> {
+        match binding {
+            Binding::Builtin(_) => None,
+            Binding::Definition(definition) => self.definition_syntax(definition),
+            Binding::Constructor(constructor) => self.constructor_syntax(constructor),
+            Binding::Let(expr) => self.expr_syntax(expr),
+            Binding::Param { owner, index } => self.param_syntax(owner, index),
+            Binding::Pat(pat) => self.pat_syntax(pat),
+        }
+    }
+
+    /// Binding introduced by exactly this node, the inverse of [`SourceMap::binding_syntax`].
+    /// Patterns are left out, as only name resolution tells bindings from constructors.
+    #[must_use]
+    pub fn syntax_binding(&self, ptr: SyntaxNodePtr) -> Option<Binding> {
+        if let Some(definition) = self.syntax_definition(ptr) {
+            return Some(Binding::Definition(definition));
+        }
+        if let Some(constructor) = self.syntax_constructor(ptr) {
+            return Some(Binding::Constructor(constructor));
+        }
+        if let Some((owner, index)) = self.syntax_param(ptr) {
+            return Some(Binding::Param { owner, index });
+        }
+        self.syntax_expr(ptr)
+            .filter(|_| ptr.kind() == parser::SyntaxKind::LET_STMT)
+            .map(Binding::Let)
+    }
+
+    /// Innermost expression containing the node, e.g. the one under a cursor.
+    #[must_use]
+    pub fn covering_expr(&self, node: &SyntaxNode) -> Option<ExprIdx> {
+        node.ancestors()
+            .find_map(|node| self.syntax_expr(SyntaxNodePtr::new(&node)))
+    }
+
+    /// Innermost type expression containing the node.
+    #[must_use]
+    pub fn covering_type_expr(&self, node: &SyntaxNode) -> Option<TypeExprIdx> {
+        node.ancestors()
+            .find_map(|node| self.syntax_type_expr(SyntaxNodePtr::new(&node)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write;
+
+    use expect_test::{expect, Expect};
+    use parser::SyntaxNodePtr;
+
+    use crate::Module;
+
+    fn check(text: &str, expected: &Expect) {
+        let parsed = parser::parse(text);
+        let root = parsed.syntax();
+        let mut module = Module::new();
+        module.lower_module(&parsed.module());
+
+        let mut actual = String::new();
+        for (expr, _) in module.exprs() {
+            let Some(source) = module.source_map().expr_source(expr) else {
+                writeln!(actual, "{expr:?}: missing").unwrap();
+                continue;
+            };
+            let node = source.ptr.to_node(&root);
+            let synthesized = if source.synthesized {
+                " (synthesized)"
+            } else {
+                ""
+            };
+            writeln!(
+                actual,
+                "{expr:?}: {:?}{synthesized} {:?}",
+                node.kind(),
+                node.text()
+            )
+            .unwrap();
+
+            if !source.synthesized {
+                assert_eq!(module.source_map().syntax_expr(source.ptr), Some(expr));
+            }
+        }
+        expected.assert_eq(&actual);
+    }
+
+    #[test]
+    fn curried_definition() {
+        check(
+            "def f x y = x;",
+            &expect![[r#"
+                Idx::<Expr>(0): IDENT_EXPR "x"
+                Idx::<Expr>(1): DEFINITION (synthesized) "def f x y = x;"
+                Idx::<Expr>(2): DEFINITION "def f x y = x;"
+            "#]],
+        );
+    }
+
+    #[test]
+    fn block_statements() {
+        check(
+            "def f { let x = 1; x; x }",
+            &expect![[r#"
+                Idx::<Expr>(0): IDENT_EXPR "x "
+                Idx::<Expr>(1): IDENT_EXPR "x"
+                Idx::<Expr>(2): EXPR_STMT (synthesized) "x; "
+                Idx::<Expr>(3): LITERAL_EXPR "1"
+                Idx::<Expr>(4): LET_STMT "let x = 1; "
+            "#]],
+        );
+    }
+
+    #[test]
+    fn covering_expr_of_token() {
+        let parsed = parser::parse("def f = (g (h 1));");
+        let root = parsed.syntax();
+        let mut module = Module::new();
+        module.lower_module(&parsed.module());
+
+        let offset = "def f = (g (h ".len().try_into().unwrap();
+        let token = root.token_at_offset(offset).right_biased().unwrap();
+        let expr = module
+            .source_map()
+            .covering_expr(&token.parent().unwrap())
+            .unwrap();
+        let ptr = module.source_map().expr_syntax(expr).unwrap();
+        assert_eq!(ptr, SyntaxNodePtr::new(&token.parent().unwrap()));
+        assert_eq!(ptr.to_node(&root).text(), "1");
+    }
+
+    #[test]
+    fn binders_round_trip() {
+        let parsed = parser::parse("type t = int; def f x y { let g z = z; (g x) }");
+        let root = parsed.syntax();
+        let mut module = Module::new();
+        module.lower_module(&parsed.module());
+        let source_map = module.source_map();
+
+        let mut actual = String::new();
+        for node in root.descendants() {
+            let ptr = SyntaxNodePtr::new(&node);
+            if let Some(binding) = source_map.syntax_binding(ptr) {
+                assert_eq!(source_map.binding_syntax(binding), Some(ptr));
+                writeln!(actual, "{binding:?}: {:?}", node.text()).unwrap();
+            }
+            if let Some(type_definition) = source_map.syntax_type_definition(ptr) {
+                assert_eq!(
+                    source_map.type_definition_syntax(type_definition),
+                    Some(ptr)
+                );
+                writeln!(actual, "{type_definition:?}: {:?}", node.text()).unwrap();
+            }
+        }
+        expect![[r#"
+            Idx::<TypeDefinition>(0): "type t = int; "
+            Definition(Idx::<Definition>(0)): "def f x y { let g z = z; (g x) }"
+            Param { owner: Idx::<Expr>(6), index: 0 }: "x "
+            Param { owner: Idx::<Expr>(5), index: 0 }: "y "
+            Let(Idx::<Expr>(4)): "let g z = z; "
+            Param { owner: Idx::<Expr>(4), index: 0 }: "z "
+        "#]]
+        .assert_eq(&actual);
+    }
+}
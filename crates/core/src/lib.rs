@@ -1,4 +1,10 @@
+mod eval;
 mod hir;
 mod intern;
+mod prelude;
+mod types;
 
+pub use crate::eval::*;
 pub use crate::hir::*;
+pub use crate::prelude::*;
+pub use crate::types::*;
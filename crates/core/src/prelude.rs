@@ -0,0 +1,58 @@
+use crate::Type;
+
+/// Functions available in every module with the prelude enabled.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Builtin {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Eq,
+    Lt,
+}
+
+impl Builtin {
+    pub const ALL: [Builtin; 6] = [
+        Builtin::Add,
+        Builtin::Sub,
+        Builtin::Mul,
+        Builtin::Div,
+        Builtin::Eq,
+        Builtin::Lt,
+    ];
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Builtin::Add => "add",
+            Builtin::Sub => "sub",
+            Builtin::Mul => "mul",
+            Builtin::Div => "div",
+            Builtin::Eq => "eq",
+            Builtin::Lt => "lt",
+        }
+    }
+
+    #[must_use]
+    pub fn typ(self) -> Type {
+        let result = match self {
+            Builtin::Add | Builtin::Sub | Builtin::Mul | Builtin::Div => Type::Int,
+            Builtin::Eq | Builtin::Lt => Type::Bool,
+        };
+        Type::arrow(Type::Int, Type::arrow(Type::Int, result))
+    }
+}
+
+/// Names of the primitive types defined by the prelude.
+pub const BUILTIN_TYPES: [&str; 3] = ["int", "bool", "unit"];
+
+/// Primitive type with the given name, if the prelude defines one.
+#[must_use]
+pub fn builtin_type(name: &str) -> Option<Type> {
+    match name {
+        "int" => Some(Type::Int),
+        "bool" => Some(Type::Bool),
+        "unit" => Some(Type::Unit),
+        _ => None,
+    }
+}
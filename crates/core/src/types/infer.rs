@@ -0,0 +1,1514 @@
+use std::collections::{hash_map::Entry, HashMap};
+
+use la_arena::ArenaMap;
+
+use super::unify::{UnificationTable, UnifyError};
+use super::{Scheme, Type, TypePrinter, TypeVar};
+use crate::hir::{
+    Binding, ConstructorIdx, DefinitionIdx, Expr, ExprIdx, Literal, MatchArm, Param, Pat, PatIdx,
+    Scopes, TypeDefinitionBody, TypeDefinitionIdx, TypeExpr, TypeExprIdx,
+};
+use crate::{builtin_type, Module, Name};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum TypeError {
+    /// The type of `expr` differs from the one required by its context.
+    /// If the requirement comes from a type annotation, `annotation` points to it.
+    Mismatch {
+        expr: ExprIdx,
+        expected: Type,
+        actual: Type,
+        annotation: Option<TypeExprIdx>,
+    },
+    UnboundVariable {
+        expr: ExprIdx,
+        name: Name,
+    },
+    /// Unifying `var` with `typ` would produce an infinite type.
+    InfiniteType {
+        expr: ExprIdx,
+        var: TypeVar,
+        typ: Type,
+    },
+    /// The pattern matches values of another type than the matched expression has.
+    PatMismatch {
+        pat: PatIdx,
+        expected: Type,
+        actual: Type,
+    },
+    /// A pattern with arguments whose name is not a constructor.
+    UnboundConstructor {
+        pat: PatIdx,
+        name: Name,
+    },
+    /// The constructor pattern has a different number of arguments than the constructor has fields.
+    ConstructorArity {
+        pat: PatIdx,
+        constructor: ConstructorIdx,
+        found: usize,
+    },
+    /// The type is applied to a different number of arguments than it has parameters.
+    TypeArity {
+        type_expr: TypeExprIdx,
+        expected: usize,
+        found: usize,
+    },
+    /// A type name which is neither defined in the module nor a builtin type.
+    UnboundType {
+        type_expr: TypeExprIdx,
+        name: Name,
+    },
+    /// A type variable in a type definition which is not one of its parameters.
+    UnboundTypeVar {
+        type_expr: TypeExprIdx,
+        name: Name,
+    },
+    /// A definition or constructor named like an earlier one.
+    DuplicateDefinition {
+        binding: Binding,
+        name: Name,
+    },
+    /// A type definition named like an earlier one.
+    DuplicateTypeDefinition {
+        type_definition: TypeDefinitionIdx,
+        name: Name,
+    },
+}
+
+impl TypeError {
+    /// Expression the error is reported at, `None` for errors in patterns and types.
+    #[must_use]
+    pub fn expr(&self) -> Option<ExprIdx> {
+        match self {
+            TypeError::Mismatch { expr, .. }
+            | TypeError::UnboundVariable { expr, .. }
+            | TypeError::InfiniteType { expr, .. } => Some(*expr),
+            TypeError::PatMismatch { .. }
+            | TypeError::UnboundConstructor { .. }
+            | TypeError::ConstructorArity { .. }
+            | TypeError::TypeArity { .. }
+            | TypeError::UnboundType { .. }
+            | TypeError::UnboundTypeVar { .. }
+            | TypeError::DuplicateDefinition { .. }
+            | TypeError::DuplicateTypeDefinition { .. } => None,
+        }
+    }
+
+    /// Pattern the error is reported at, `None` for errors in expressions and types.
+    #[must_use]
+    pub fn pat(&self) -> Option<PatIdx> {
+        match self {
+            TypeError::PatMismatch { pat, .. }
+            | TypeError::UnboundConstructor { pat, .. }
+            | TypeError::ConstructorArity { pat, .. } => Some(*pat),
+            TypeError::Mismatch { .. }
+            | TypeError::UnboundVariable { .. }
+            | TypeError::InfiniteType { .. }
+            | TypeError::TypeArity { .. }
+            | TypeError::UnboundType { .. }
+            | TypeError::UnboundTypeVar { .. }
+            | TypeError::DuplicateDefinition { .. }
+            | TypeError::DuplicateTypeDefinition { .. } => None,
+        }
+    }
+
+    /// Type expression the error is reported at, `None` for errors in expressions and patterns.
+    #[must_use]
+    pub fn type_expr(&self) -> Option<TypeExprIdx> {
+        match self {
+            TypeError::TypeArity { type_expr, .. }
+            | TypeError::UnboundType { type_expr, .. }
+            | TypeError::UnboundTypeVar { type_expr, .. } => Some(*type_expr),
+            TypeError::Mismatch { .. }
+            | TypeError::UnboundVariable { .. }
+            | TypeError::InfiniteType { .. }
+            | TypeError::PatMismatch { .. }
+            | TypeError::UnboundConstructor { .. }
+            | TypeError::ConstructorArity { .. }
+            | TypeError::DuplicateDefinition { .. }
+            | TypeError::DuplicateTypeDefinition { .. } => None,
+        }
+    }
+
+    #[must_use]
+    pub fn message(&self, module: &Module) -> String {
+        let mut printer = TypePrinter::new(module);
+        match self {
+            TypeError::Mismatch {
+                expected, actual, ..
+            }
+            | TypeError::PatMismatch {
+                expected, actual, ..
+            } => format!(
+                "expected `{}`, found `{}`",
+                printer.print(expected),
+                printer.print(actual)
+            ),
+            TypeError::UnboundVariable { name, .. } => {
+                format!("unbound variable `{}`", module.lookup_name(*name))
+            }
+            TypeError::InfiniteType { var, typ, .. } => format!(
+                "infinite type: `{}` occurs in `{}`",
+                printer.print(&Type::Var(*var)),
+                printer.print(typ)
+            ),
+            TypeError::UnboundConstructor { name, .. } => {
+                format!("unbound constructor `{}`", module.lookup_name(*name))
+            }
+            TypeError::ConstructorArity {
+                constructor, found, ..
+            } => {
+                let constructor = module.get_constructor(*constructor);
+                let expected = constructor.fields.len();
+                format!(
+                    "constructor `{}` has {expected} field{}, found {found}",
+                    module.lookup_name(constructor.name),
+                    if expected == 1 { "" } else { "s" }
+                )
+            }
+            TypeError::TypeArity {
+                expected, found, ..
+            } => format!(
+                "expected {expected} type argument{}, found {found}",
+                if *expected == 1 { "" } else { "s" }
+            ),
+            TypeError::UnboundType { name, .. } => {
+                format!("unbound type `{}`", module.lookup_name(*name))
+            }
+            TypeError::UnboundTypeVar { name, .. } => {
+                format!("unbound type variable `'{}`", module.lookup_name(*name))
+            }
+            TypeError::DuplicateDefinition { name, .. } => {
+                format!("`{}` is already defined", module.lookup_name(*name))
+            }
+            TypeError::DuplicateTypeDefinition { name, .. } => {
+                format!("type `{}` is already defined", module.lookup_name(*name))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct InferenceResult {
+    expr_types: ArenaMap<ExprIdx, Type>,
+    definition_types: ArenaMap<DefinitionIdx, Type>,
+    type_definition_types: ArenaMap<TypeDefinitionIdx, Type>,
+    constructor_types: ArenaMap<ConstructorIdx, Type>,
+    let_types: ArenaMap<ExprIdx, Type>,
+    param_types: HashMap<(ExprIdx, usize), Type>,
+    pat_types: ArenaMap<PatIdx, Type>,
+    definition_schemes: ArenaMap<DefinitionIdx, Scheme>,
+    constructor_schemes: ArenaMap<ConstructorIdx, Scheme>,
+    let_schemes: ArenaMap<ExprIdx, Scheme>,
+    errors: Vec<TypeError>,
+}
+
+impl InferenceResult {
+    #[must_use]
+    pub fn type_of_expr(&self, expr: ExprIdx) -> Option<&Type> {
+        self.expr_types.get(expr)
+    }
+
+    #[must_use]
+    pub fn type_of_definition(&self, definition: DefinitionIdx) -> Option<&Type> {
+        self.definition_types.get(definition)
+    }
+
+    /// Type the alias expands to, or the sum type applied to its parameters.
+    #[must_use]
+    pub fn type_of_type_definition(&self, type_definition: TypeDefinitionIdx) -> Option<&Type> {
+        self.type_definition_types.get(type_definition)
+    }
+
+    /// Type of the constructor as a function of its fields.
+    #[must_use]
+    pub fn type_of_constructor(&self, constructor: ConstructorIdx) -> Option<&Type> {
+        self.constructor_types.get(constructor)
+    }
+
+    /// Type of the name bound by the let expression at `let_expr`.
+    #[must_use]
+    pub fn type_of_let(&self, let_expr: ExprIdx) -> Option<&Type> {
+        self.let_types.get(let_expr)
+    }
+
+    /// Type of the `index`-th parameter of the lambda or let expression at `owner`.
+    #[must_use]
+    pub fn type_of_param(&self, owner: ExprIdx, index: usize) -> Option<&Type> {
+        self.param_types.get(&(owner, index))
+    }
+
+    /// Type of the values matched by the pattern.
+    #[must_use]
+    pub fn type_of_pat(&self, pat: PatIdx) -> Option<&Type> {
+        self.pat_types.get(pat)
+    }
+
+    /// Type of the definition, generalized over the variables not fixed by its uses
+    /// in mutually recursive definitions.
+    #[must_use]
+    pub fn scheme_of_definition(&self, definition: DefinitionIdx) -> Option<&Scheme> {
+        self.definition_schemes.get(definition)
+    }
+
+    #[must_use]
+    pub fn scheme_of_constructor(&self, constructor: ConstructorIdx) -> Option<&Scheme> {
+        self.constructor_schemes.get(constructor)
+    }
+
+    /// Type of the name bound by the let expression,
+    /// generalized over the variables which do not occur in its context.
+    #[must_use]
+    pub fn scheme_of_let(&self, let_expr: ExprIdx) -> Option<&Scheme> {
+        self.let_schemes.get(let_expr)
+    }
+
+    #[must_use]
+    pub fn errors(&self) -> &[TypeError] {
+        &self.errors
+    }
+}
+
+#[must_use]
+pub fn infer(module: &Module) -> InferenceResult {
+    let mut ctx = InferenceCtx::new(module);
+    ctx.infer_module();
+    ctx.finish()
+}
+
+struct InferenceCtx<'a> {
+    module: &'a Module,
+    table: UnificationTable,
+    result: InferenceResult,
+    scopes: Scopes,
+    type_definitions: HashMap<Name, TypeDefinitionIdx>,
+    /// Types of the binders in scope which cannot be generalized:
+    /// parameters, matched values and the definitions being inferred.
+    env: Vec<Type>,
+    /// Type variables named in the annotations of the definition being inferred.
+    type_vars: HashMap<Name, Type>,
+}
+
+impl<'a> InferenceCtx<'a> {
+    fn new(module: &'a Module) -> Self {
+        let mut result = InferenceResult::default();
+        let scopes = Scopes::new(module);
+        for &binding in scopes.duplicates() {
+            let name = match binding {
+                Binding::Definition(definition) => module.get_definition(definition).name,
+                Binding::Constructor(constructor) => module.get_constructor(constructor).name,
+                _ => continue,
+            };
+            result
+                .errors
+                .push(TypeError::DuplicateDefinition { binding, name });
+        }
+
+        // Like definitions, types named like an earlier one are not used.
+        let mut type_definitions = HashMap::new();
+        for (idx, type_definition) in module.type_definitions() {
+            let name = type_definition.name;
+            if let Entry::Vacant(entry) = type_definitions.entry(name) {
+                entry.insert(idx);
+            } else {
+                result.errors.push(TypeError::DuplicateTypeDefinition {
+                    type_definition: idx,
+                    name,
+                });
+            }
+        }
+
+        Self {
+            module,
+            table: UnificationTable::default(),
+            result,
+            scopes,
+            type_definitions,
+            env: Vec::new(),
+            type_vars: HashMap::new(),
+        }
+    }
+
+    fn infer_module(&mut self) {
+        for (idx, _) in self.module.type_definitions() {
+            let typ = self.instantiate_type_definition(idx, &mut Vec::new());
+            self.result.type_definition_types.insert(idx, typ);
+        }
+        for (idx, _) in self.module.constructors() {
+            let typ = self.instantiate_constructor(idx);
+            let scheme = self.generalize(&typ);
+            self.result.constructor_types.insert(idx, typ);
+            self.result.constructor_schemes.insert(idx, scheme);
+        }
+
+        // Mutually recursive definitions are inferred together, after the ones they use,
+        // so that those are already generalized.
+        for group in definition_groups(self.module, &self.scopes) {
+            let mut type_vars = Vec::with_capacity(group.len());
+            for &idx in &group {
+                let typ = self.lower_type_expr(self.module.get_definition(idx).typ);
+                self.result.definition_types.insert(idx, typ.clone());
+                self.env.push(typ);
+                type_vars.push(std::mem::take(&mut self.type_vars));
+            }
+
+            for (&idx, type_vars) in group.iter().zip(type_vars) {
+                self.type_vars = type_vars;
+                let definition = self.module.get_definition(idx);
+                let expected = self.result.definition_types[idx].clone();
+                let actual = self.infer_expr(definition.defn);
+                let annotation = self.annotation(definition.typ);
+                self.unify_at(definition.defn, &expected, &actual, annotation);
+            }
+            self.type_vars.clear();
+            self.env.clear();
+
+            for idx in group {
+                let scheme = self.generalize(&self.result.definition_types[idx]);
+                self.result.definition_schemes.insert(idx, scheme);
+            }
+        }
+    }
+
+    /// Quantifies the type over its variables which do not occur in the environment.
+    fn generalize(&self, typ: &Type) -> Scheme {
+        let mut env_vars = Vec::new();
+        for binder in &self.env {
+            self.table.resolve(binder).free_vars(&mut env_vars);
+        }
+        let typ = self.table.resolve(typ);
+        let mut vars = Vec::new();
+        typ.free_vars(&mut vars);
+        vars.retain(|var| !env_vars.contains(var));
+        Scheme { vars, typ }
+    }
+
+    /// Type of a use of a binding, with fresh variables for the quantified ones.
+    fn instantiate(&mut self, scheme: &Scheme) -> Type {
+        let substitution: HashMap<_, _> = scheme
+            .vars
+            .iter()
+            .map(|var| (*var, self.table.fresh()))
+            .collect();
+        scheme.typ.substitute(&substitution)
+    }
+
+    fn finish(mut self) -> InferenceResult {
+        let table = &self.table;
+        let resolve_all = |types: &mut dyn Iterator<Item = &mut Type>| {
+            types.for_each(|typ| *typ = table.resolve(typ));
+        };
+        resolve_all(&mut self.result.expr_types.values_mut());
+        resolve_all(&mut self.result.definition_types.values_mut());
+        resolve_all(&mut self.result.type_definition_types.values_mut());
+        resolve_all(&mut self.result.constructor_types.values_mut());
+        resolve_all(&mut self.result.let_types.values_mut());
+        resolve_all(&mut self.result.param_types.values_mut());
+        resolve_all(&mut self.result.pat_types.values_mut());
+        let schemes = self
+            .result
+            .definition_schemes
+            .values_mut()
+            .chain(self.result.constructor_schemes.values_mut())
+            .chain(self.result.let_schemes.values_mut());
+        resolve_all(&mut schemes.map(|scheme| &mut scheme.typ));
+
+        for error in &mut self.result.errors {
+            match error {
+                TypeError::Mismatch {
+                    expected, actual, ..
+                }
+                | TypeError::PatMismatch {
+                    expected, actual, ..
+                } => {
+                    *expected = table.resolve(expected);
+                    *actual = table.resolve(actual);
+                }
+                TypeError::InfiniteType { typ, .. } => *typ = table.resolve(typ),
+                TypeError::UnboundVariable { .. }
+                | TypeError::UnboundConstructor { .. }
+                | TypeError::ConstructorArity { .. }
+                | TypeError::TypeArity { .. }
+                | TypeError::UnboundType { .. }
+                | TypeError::UnboundTypeVar { .. }
+                | TypeError::DuplicateDefinition { .. }
+                | TypeError::DuplicateTypeDefinition { .. } => {}
+            }
+        }
+
+        self.result
+    }
+
+    fn infer_expr(&mut self, idx: ExprIdx) -> Type {
+        let typ = match self.module.get_expr(idx) {
+            Expr::Missing => self.table.fresh(),
+            Expr::LiteralExpr(Literal::IntLiteral(_)) => Type::Int,
+            Expr::LiteralExpr(Literal::BoolLiteral(_)) => Type::Bool,
+            Expr::IdentExpr { name } => self.lookup(idx, *name),
+            Expr::BuiltinExpr(builtin) => builtin.typ(),
+            Expr::AppExpr { func, arg } => self.infer_app(*func, *arg),
+            Expr::IfExpr {
+                condition,
+                then_branch,
+                else_branch,
+            } => self.infer_if(*condition, *then_branch, *else_branch),
+            Expr::MatchExpr { scrutinee, arms } => self.infer_match(*scrutinee, arms),
+            Expr::LambdaExpr(lambda) => {
+                let param_type = self.bind_params(idx, std::slice::from_ref(&lambda.param));
+                self.env.extend(param_type.iter().cloned());
+                let body_type = self.infer_annotated(lambda.body, lambda.return_type);
+                self.env.pop();
+
+                Type::arrow(param_type[0].clone(), body_type)
+            }
+            Expr::LetExpr(let_expr) => {
+                let param_types = self.bind_params(idx, &let_expr.params);
+                let env_len = self.env.len();
+                self.env.extend(param_types.iter().cloned());
+                let defn_type = self.infer_annotated(let_expr.defn, let_expr.return_type);
+                self.env.truncate(env_len);
+
+                let let_type = param_types
+                    .into_iter()
+                    .rev()
+                    .fold(defn_type, |typ, param| Type::arrow(param, typ));
+                let scheme = self.generalize(&let_type);
+                self.result.let_types.insert(idx, let_type);
+                self.result.let_schemes.insert(idx, scheme);
+
+                self.infer_expr(let_expr.body)
+            }
+        };
+
+        self.result.expr_types.insert(idx, typ.clone());
+        typ
+    }
+
+    fn infer_app(&mut self, func: ExprIdx, arg: ExprIdx) -> Type {
+        let func_type = self.infer_expr(func);
+        let arg_type = self.infer_expr(arg);
+
+        if let Type::Arrow(param_type, return_type) = self.table.shallow_resolve(&func_type) {
+            self.unify_at(arg, &param_type, &arg_type, None);
+            *return_type
+        } else {
+            let return_type = self.table.fresh();
+            let expected = Type::arrow(arg_type, return_type.clone());
+            self.unify_at(func, &expected, &func_type, None);
+            return_type
+        }
+    }
+
+    /// Both branches must have the type of the `then` branch.
+    fn infer_if(&mut self, condition: ExprIdx, then_branch: ExprIdx, else_branch: ExprIdx) -> Type {
+        let condition_type = self.infer_expr(condition);
+        self.unify_at(condition, &Type::Bool, &condition_type, None);
+
+        let then_type = self.infer_expr(then_branch);
+        let else_type = self.infer_expr(else_branch);
+        self.unify_at(else_branch, &then_type, &else_type, None);
+        then_type
+    }
+
+    /// Patterns must match values of the scrutinee's type,
+    /// and all arms must have the type of the first one.
+    fn infer_match(&mut self, scrutinee: ExprIdx, arms: &[MatchArm]) -> Type {
+        let scrutinee_type = self.infer_expr(scrutinee);
+        // Names bound by the patterns have parts of the matched type.
+        self.env.push(scrutinee_type.clone());
+        let mut result_type: Option<Type> = None;
+        for arm in arms {
+            self.infer_pat(arm.pat, &scrutinee_type);
+            let arm_type = self.infer_expr(arm.expr);
+            match &result_type {
+                Some(expected) => self.unify_at(arm.expr, expected, &arm_type, None),
+                None => result_type = Some(arm_type),
+            }
+        }
+        self.env.pop();
+        result_type.unwrap_or_else(|| self.table.fresh())
+    }
+
+    /// Checks that the pattern matches values of the `expected` type,
+    /// assigning it to the names the pattern binds.
+    fn infer_pat(&mut self, pat: PatIdx, expected: &Type) {
+        self.result.pat_types.insert(pat, expected.clone());
+        let actual = match self.module.get_pat(pat) {
+            Pat::Missing | Pat::Wildcard => return,
+            Pat::Literal(Literal::IntLiteral(_)) => Type::Int,
+            Pat::Literal(Literal::BoolLiteral(_)) => Type::Bool,
+            Pat::Ident { name, args } => {
+                let Some(constructor) = self.scopes.pat_constructor(pat) else {
+                    if !args.is_empty() {
+                        let name = *name;
+                        self.result
+                            .errors
+                            .push(TypeError::UnboundConstructor { pat, name });
+                        self.infer_unknown_pats(args);
+                    }
+                    return;
+                };
+                let field_count = self.module.get_constructor(constructor).fields.len();
+                if args.len() != field_count {
+                    self.result.errors.push(TypeError::ConstructorArity {
+                        pat,
+                        constructor,
+                        found: args.len(),
+                    });
+                    self.infer_unknown_pats(args);
+                    return;
+                }
+
+                let mut typ = self.constructor_type(constructor);
+                let mut fields = Vec::with_capacity(field_count);
+                while let Type::Arrow(field, rest) = typ {
+                    fields.push(*field);
+                    typ = *rest;
+                }
+                if !self.unify_pat(pat, expected, &typ) {
+                    self.infer_unknown_pats(args);
+                    return;
+                }
+                for (arg, field) in args.iter().zip(&fields) {
+                    self.infer_pat(*arg, field);
+                }
+                return;
+            }
+        };
+        self.unify_pat(pat, expected, &actual);
+    }
+
+    /// Infers patterns matching values of a type which cannot be known.
+    fn infer_unknown_pats(&mut self, pats: &[PatIdx]) {
+        for pat in pats {
+            let typ = self.table.fresh();
+            self.infer_pat(*pat, &typ);
+        }
+    }
+
+    fn unify_pat(&mut self, pat: PatIdx, expected: &Type, actual: &Type) -> bool {
+        if self.table.unify(expected, actual).is_ok() {
+            return true;
+        }
+        self.result.errors.push(TypeError::PatMismatch {
+            pat,
+            expected: expected.clone(),
+            actual: actual.clone(),
+        });
+        false
+    }
+
+    /// Infers the type of `expr`, checking it against `annotation` if present.
+    fn infer_annotated(&mut self, expr: ExprIdx, annotation: TypeExprIdx) -> Type {
+        let actual = self.infer_expr(expr);
+        match self.annotation(annotation) {
+            Some(annotation) => {
+                let expected = self.lower_type_expr(annotation);
+                self.unify_at(expr, &expected, &actual, Some(annotation));
+                expected
+            }
+            None => actual,
+        }
+    }
+
+    /// Assigns types to the parameters, returning them.
+    fn bind_params(&mut self, owner: ExprIdx, params: &[Param]) -> Vec<Type> {
+        params
+            .iter()
+            .enumerate()
+            .map(|(index, param)| {
+                let typ = self.lower_type_expr(param.typ);
+                self.result.param_types.insert((owner, index), typ.clone());
+                typ
+            })
+            .collect()
+    }
+
+    fn lookup(&mut self, expr: ExprIdx, name: Name) -> Type {
+        let typ = match self.scopes.resolution(expr) {
+            Some(Binding::Builtin(builtin)) => Some(builtin.typ()),
+            // Definitions in the group being inferred are not generalized yet.
+            Some(Binding::Definition(definition)) => {
+                match self.result.definition_schemes.get(definition).cloned() {
+                    Some(scheme) => Some(self.instantiate(&scheme)),
+                    None => self.result.definition_types.get(definition).cloned(),
+                }
+            }
+            Some(Binding::Constructor(constructor)) => Some(self.constructor_type(constructor)),
+            Some(Binding::Let(let_expr)) => self
+                .result
+                .let_schemes
+                .get(let_expr)
+                .cloned()
+                .map(|scheme| self.instantiate(&scheme)),
+            Some(Binding::Param { owner, index }) => {
+                self.result.param_types.get(&(owner, index)).cloned()
+            }
+            Some(Binding::Pat(pat)) => self.result.pat_types.get(pat).cloned(),
+            None => None,
+        };
+
+        if let Some(typ) = typ {
+            typ
+        } else {
+            self.result
+                .errors
+                .push(TypeError::UnboundVariable { expr, name });
+            Type::Error
+        }
+    }
+
+    fn unify_at(
+        &mut self,
+        expr: ExprIdx,
+        expected: &Type,
+        actual: &Type,
+        annotation: Option<TypeExprIdx>,
+    ) {
+        let error = match self.table.unify(expected, actual) {
+            Ok(()) => return,
+            Err(UnifyError::Mismatch) => TypeError::Mismatch {
+                expr,
+                expected: expected.clone(),
+                actual: actual.clone(),
+                annotation,
+            },
+            Err(UnifyError::Occurs(var, typ)) => TypeError::InfiniteType { expr, var, typ },
+        };
+        self.result.errors.push(error);
+    }
+
+    fn annotation(&self, type_expr: TypeExprIdx) -> Option<TypeExprIdx> {
+        match self.module.get_type_expr(type_expr) {
+            TypeExpr::Missing => None,
+            _ => Some(type_expr),
+        }
+    }
+
+    fn lower_type_expr(&mut self, type_expr: TypeExprIdx) -> Type {
+        self.lower_type_expr_expanding(type_expr, None, &mut Vec::new())
+    }
+
+    /// `params` holds the types substituted for the parameters of the enclosing type definition,
+    /// and is `None` outside of them, where type variables are shared by the whole definition.
+    /// `expanding` holds the aliases currently being expanded, to cut off cyclic definitions.
+    fn lower_type_expr_expanding(
+        &mut self,
+        type_expr: TypeExprIdx,
+        params: Option<&[(Name, Type)]>,
+        expanding: &mut Vec<Name>,
+    ) -> Type {
+        match self.module.get_type_expr(type_expr) {
+            TypeExpr::Missing => self.table.fresh(),
+            TypeExpr::IdentTypeExpr { name } => {
+                self.lower_type_name(type_expr, *name, Vec::new(), params, expanding)
+            }
+            TypeExpr::TypeVar { name } => {
+                let name = *name;
+                let Some(params) = params else {
+                    let table = &mut self.table;
+                    return self
+                        .type_vars
+                        .entry(name)
+                        .or_insert_with(|| table.fresh())
+                        .clone();
+                };
+                if let Some((_, typ)) = params.iter().find(|(param, _)| *param == name) {
+                    return typ.clone();
+                }
+                self.report_once(TypeError::UnboundTypeVar { type_expr, name });
+                Type::Error
+            }
+            TypeExpr::TypeApp { .. } => {
+                let mut func = type_expr;
+                let mut arg_exprs = Vec::new();
+                while let TypeExpr::TypeApp { func: inner, arg } = self.module.get_type_expr(func) {
+                    arg_exprs.push(*arg);
+                    func = *inner;
+                }
+                let args: Vec<_> = arg_exprs
+                    .into_iter()
+                    .rev()
+                    .map(|arg| self.lower_type_expr_expanding(arg, params, expanding))
+                    .collect();
+                if let TypeExpr::IdentTypeExpr { name } = self.module.get_type_expr(func) {
+                    return self.lower_type_name(type_expr, *name, args, params, expanding);
+                }
+                self.report_once(TypeError::TypeArity {
+                    type_expr,
+                    expected: 0,
+                    found: args.len(),
+                });
+                Type::Error
+            }
+            TypeExpr::TypeArrow { from, to } => {
+                let (from, to) = (*from, *to);
+                let from = self.lower_type_expr_expanding(from, params, expanding);
+                let to = self.lower_type_expr_expanding(to, params, expanding);
+                Type::arrow(from, to)
+            }
+        }
+    }
+
+    /// Type named `name` applied to `args`. Type definitions used without arguments get
+    /// fresh type variables for their parameters.
+    fn lower_type_name(
+        &mut self,
+        type_expr: TypeExprIdx,
+        name: Name,
+        args: Vec<Type>,
+        params: Option<&[(Name, Type)]>,
+        expanding: &mut Vec<Name>,
+    ) -> Type {
+        let param = params
+            .into_iter()
+            .flatten()
+            .find(|(param, _)| *param == name);
+        if let Some((_, typ)) = param {
+            return self.check_type_arity(type_expr, 0, args.len(), typ.clone());
+        }
+        if expanding.contains(&name) {
+            return Type::Error;
+        }
+        if let Some(&type_definition) = self.type_definitions.get(&name) {
+            if args.is_empty() {
+                return self.instantiate_type_definition(type_definition, expanding);
+            }
+            let expected = self
+                .module
+                .get_type_definition(type_definition)
+                .params
+                .len();
+            let found = args.len();
+            let typ = if found == expected {
+                self.apply_type_definition(type_definition, args, expanding)
+            } else {
+                Type::Error
+            };
+            return self.check_type_arity(type_expr, expected, found, typ);
+        }
+        let builtin = if self.module.has_prelude() {
+            builtin_type(self.module.lookup_name(name))
+        } else {
+            None
+        };
+        let Some(typ) = builtin else {
+            self.report_once(TypeError::UnboundType { type_expr, name });
+            return Type::Error;
+        };
+        self.check_type_arity(type_expr, 0, args.len(), typ)
+    }
+
+    /// `typ` if the number of type arguments is as expected, otherwise an error.
+    fn check_type_arity(
+        &mut self,
+        type_expr: TypeExprIdx,
+        expected: usize,
+        found: usize,
+        typ: Type,
+    ) -> Type {
+        if found == expected {
+            return typ;
+        }
+        self.report_once(TypeError::TypeArity {
+            type_expr,
+            expected,
+            found,
+        });
+        Type::Error
+    }
+
+    /// Errors in type definitions are found again every time they are expanded.
+    fn report_once(&mut self, error: TypeError) {
+        if !self.result.errors.contains(&error) {
+            self.result.errors.push(error);
+        }
+    }
+
+    /// Type named by the definition, with fresh type variables for its parameters.
+    fn instantiate_type_definition(
+        &mut self,
+        type_definition: TypeDefinitionIdx,
+        expanding: &mut Vec<Name>,
+    ) -> Type {
+        let args = self
+            .module
+            .get_type_definition(type_definition)
+            .params
+            .iter()
+            .map(|_| self.table.fresh())
+            .collect();
+        self.apply_type_definition(type_definition, args, expanding)
+    }
+
+    /// Type named by the definition applied to as many arguments as it has parameters.
+    fn apply_type_definition(
+        &mut self,
+        type_definition: TypeDefinitionIdx,
+        args: Vec<Type>,
+        expanding: &mut Vec<Name>,
+    ) -> Type {
+        let module = self.module;
+        let definition = module.get_type_definition(type_definition);
+        match &definition.defn {
+            TypeDefinitionBody::Alias(defn) => {
+                let params: Vec<_> = definition.params.iter().copied().zip(args).collect();
+                expanding.push(definition.name);
+                let typ = self.lower_type_expr_expanding(*defn, Some(&params), expanding);
+                expanding.pop();
+                typ
+            }
+            TypeDefinitionBody::Sum(_) => Type::Sum(type_definition, args),
+        }
+    }
+
+    fn constructor_type(&mut self, constructor: ConstructorIdx) -> Type {
+        let scheme = self.result.constructor_schemes[constructor].clone();
+        self.instantiate(&scheme)
+    }
+
+    /// Type of the constructor as a function of its fields,
+    /// with fresh type variables for the parameters of its type.
+    fn instantiate_constructor(&mut self, constructor: ConstructorIdx) -> Type {
+        let module = self.module;
+        let constructor = module.get_constructor(constructor);
+        let definition = module.get_type_definition(constructor.parent);
+        let args: Vec<_> = definition
+            .params
+            .iter()
+            .map(|_| self.table.fresh())
+            .collect();
+        let params: Vec<_> = definition
+            .params
+            .iter()
+            .copied()
+            .zip(args.clone())
+            .collect();
+
+        let fields: Vec<_> = constructor
+            .fields
+            .iter()
+            .map(|field| self.lower_type_expr_expanding(*field, Some(&params), &mut Vec::new()))
+            .collect();
+        fields
+            .into_iter()
+            .rev()
+            .fold(Type::Sum(constructor.parent, args), |typ, field| {
+                Type::arrow(field, typ)
+            })
+    }
+}
+
+/// Definitions grouped by mutual recursion, each group after the groups it refers to.
+/// These are the strongly connected components of the graph of references, found with
+/// Tarjan's algorithm.
+fn definition_groups(module: &Module, scopes: &Scopes) -> Vec<Vec<DefinitionIdx>> {
+    let definitions: Vec<_> = module.definitions().map(|(idx, _)| idx).collect();
+    let positions: HashMap<_, _> = definitions
+        .iter()
+        .enumerate()
+        .map(|(position, idx)| (*idx, position))
+        .collect();
+    let references: Vec<Vec<usize>> = definitions
+        .iter()
+        .map(|idx| {
+            let mut referenced = Vec::new();
+            let defn = module.get_definition(*idx).defn;
+            referenced_definitions(module, scopes, defn, &mut referenced);
+            referenced.iter().map(|idx| positions[idx]).collect()
+        })
+        .collect();
+
+    let mut tarjan = Tarjan {
+        references: &references,
+        next_index: 0,
+        indices: vec![None; definitions.len()],
+        low_links: vec![0; definitions.len()],
+        on_stack: vec![false; definitions.len()],
+        stack: Vec::new(),
+        groups: Vec::new(),
+    };
+    for position in 0..definitions.len() {
+        if tarjan.indices[position].is_none() {
+            tarjan.visit(position);
+        }
+    }
+    tarjan
+        .groups
+        .into_iter()
+        .map(|group| {
+            group
+                .into_iter()
+                .map(|position| definitions[position])
+                .collect()
+        })
+        .collect()
+}
+
+fn referenced_definitions(
+    module: &Module,
+    scopes: &Scopes,
+    expr: ExprIdx,
+    referenced: &mut Vec<DefinitionIdx>,
+) {
+    let mut visit = |expr| referenced_definitions(module, scopes, expr, referenced);
+    match module.get_expr(expr) {
+        Expr::Missing | Expr::LiteralExpr(_) | Expr::BuiltinExpr(_) => {}
+        Expr::IdentExpr { .. } => {
+            if let Some(Binding::Definition(definition)) = scopes.resolution(expr) {
+                referenced.push(definition);
+            }
+        }
+        Expr::AppExpr { func, arg } => {
+            visit(*func);
+            visit(*arg);
+        }
+        Expr::IfExpr {
+            condition,
+            then_branch,
+            else_branch,
+        } => {
+            visit(*condition);
+            visit(*then_branch);
+            visit(*else_branch);
+        }
+        Expr::MatchExpr { scrutinee, arms } => {
+            visit(*scrutinee);
+            for arm in arms {
+                visit(arm.expr);
+            }
+        }
+        Expr::LambdaExpr(lambda) => visit(lambda.body),
+        Expr::LetExpr(let_expr) => {
+            visit(let_expr.defn);
+            visit(let_expr.body);
+        }
+    }
+}
+
+struct Tarjan<'a> {
+    references: &'a [Vec<usize>],
+    next_index: usize,
+    indices: Vec<Option<usize>>,
+    low_links: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<usize>,
+    groups: Vec<Vec<usize>>,
+}
+
+impl Tarjan<'_> {
+    fn visit(&mut self, node: usize) {
+        let index = self.next_index;
+        self.next_index += 1;
+        self.indices[node] = Some(index);
+        self.low_links[node] = index;
+        self.stack.push(node);
+        self.on_stack[node] = true;
+
+        let references = self.references;
+        for &referenced in &references[node] {
+            match self.indices[referenced] {
+                None => {
+                    self.visit(referenced);
+                    self.low_links[node] = self.low_links[node].min(self.low_links[referenced]);
+                }
+                Some(referenced_index) if self.on_stack[referenced] => {
+                    self.low_links[node] = self.low_links[node].min(referenced_index);
+                }
+                Some(_) => {}
+            }
+        }
+
+        if self.low_links[node] == index {
+            let mut group = Vec::new();
+            while let Some(member) = self.stack.pop() {
+                self.on_stack[member] = false;
+                group.push(member);
+                if member == node {
+                    break;
+                }
+            }
+            group.sort_unstable();
+            self.groups.push(group);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write;
+
+    use expect_test::{expect, Expect};
+
+    use super::infer;
+    use crate::{Module, TypePrinter};
+
+    fn check(text: &str, expected: &Expect) {
+        check_module(Module::new(), text, expected);
+    }
+
+    fn check_without_prelude(text: &str, expected: &Expect) {
+        check_module(Module::without_prelude(), text, expected);
+    }
+
+    fn check_module(mut module: Module, text: &str, expected: &Expect) {
+        module.lower_module(&parser::parse(text).module());
+        let result = infer(&module);
+
+        let mut actual = String::new();
+        for (idx, definition) in module.definitions() {
+            let typ = result.type_of_definition(idx).unwrap();
+            let mut printer = TypePrinter::new(&module);
+            writeln!(
+                actual,
+                "{}: {}",
+                module.lookup_name(definition.name),
+                printer.print(typ)
+            )
+            .unwrap();
+        }
+        for error in result.errors() {
+            writeln!(actual, "error: {}", error.message(&module)).unwrap();
+        }
+        expected.assert_eq(&actual);
+    }
+
+    #[test]
+    fn infer_literal() {
+        check("def x = 42;", &expect![["x: int\n"]]);
+    }
+
+    #[test]
+    fn infer_identity() {
+        check(
+            "def id x = x;",
+            &expect![[r"
+                id: 'a -> 'a
+            "]],
+        );
+    }
+
+    #[test]
+    fn infer_const() {
+        check(
+            "def const x y = x;",
+            &expect![[r"
+                const: 'a -> 'b -> 'a
+            "]],
+        );
+    }
+
+    #[test]
+    fn infer_application() {
+        check(
+            "def apply f x = (f x);",
+            &expect![[r"
+                apply: ('a -> 'b) -> 'a -> 'b
+            "]],
+        );
+    }
+
+    #[test]
+    fn infer_param_annotation() {
+        check(
+            "def f (x: int) = x;",
+            &expect![[r"
+                f: int -> int
+            "]],
+        );
+    }
+
+    #[test]
+    fn infer_return_type_annotation() {
+        check(
+            "def f x : int = x;",
+            &expect![[r"
+                f: int -> int
+            "]],
+        );
+    }
+
+    #[test]
+    fn infer_definition_annotation() {
+        check(
+            "def f : int -> int = \\x -> x;",
+            &expect![[r"
+                f: int -> int
+            "]],
+        );
+    }
+
+    #[test]
+    fn infer_type_alias() {
+        check(
+            "type int_to_int = int -> int; def f: int_to_int = \\x -> x;",
+            &expect![[r"
+                f: int -> int
+            "]],
+        );
+    }
+
+    #[test]
+    fn infer_let() {
+        check(
+            "def f { let g x = x; let y = (g 1); y }",
+            &expect![[r"
+                f: int
+            "]],
+        );
+    }
+
+    #[test]
+    fn infer_mutually_recursive_definitions() {
+        check(
+            "def f x = (g x); def g (y: int) = (f y);",
+            &expect![[r"
+                f: int -> 'a
+                g: int -> 'a
+            "]],
+        );
+    }
+
+    #[test]
+    fn definitions_are_visible_before_declaration() {
+        check(
+            "def a = (b 1); def b x = x;",
+            &expect![[r"
+                a: int
+                b: 'a -> 'a
+            "]],
+        );
+    }
+
+    #[test]
+    fn report_mismatch_with_annotation() {
+        check(
+            "def f : int -> int = 1;",
+            &expect![[r"
+                f: int -> int
+                error: expected `int -> int`, found `int`
+            "]],
+        );
+    }
+
+    #[test]
+    fn report_mismatch_in_application() {
+        check(
+            "def f (x: int) = x; def g = (f f);",
+            &expect![[r"
+                f: int -> int
+                g: int
+                error: expected `int`, found `int -> int`
+            "]],
+        );
+    }
+
+    #[test]
+    fn report_applying_non_function() {
+        check(
+            "def f = (1 2);",
+            &expect![[r"
+                f: 'a
+                error: expected `int -> 'a`, found `int`
+            "]],
+        );
+    }
+
+    #[test]
+    fn report_unbound_variable() {
+        check(
+            "def f = x;",
+            &expect![[r"
+                f: 'a
+                error: unbound variable `x`
+            "]],
+        );
+    }
+
+    #[test]
+    fn report_infinite_type() {
+        check(
+            "def f x = (x x);",
+            &expect![[r"
+                f: 'a -> 'b
+                error: infinite type: `'a` occurs in `'a -> 'b`
+            "]],
+        );
+    }
+
+    #[test]
+    fn let_shadows_definition() {
+        check(
+            "def x = 1; def f (x: int -> int) { let y = (x 1); x }",
+            &expect![[r"
+                x: int
+                f: (int -> int) -> int -> int
+            "]],
+        );
+    }
+
+    #[test]
+    fn infer_arithmetic() {
+        check(
+            "def f x y = x * y + 1;",
+            &expect![[r"
+                f: int -> int -> int
+            "]],
+        );
+    }
+
+    #[test]
+    fn infer_comparison() {
+        check(
+            "def f x = (lt x 2); def g y = (eq y 3);",
+            &expect![[r"
+                f: int -> bool
+                g: int -> bool
+            "]],
+        );
+    }
+
+    #[test]
+    fn infer_if() {
+        check(
+            "def f x y = if (lt x 0) { y } else { false };",
+            &expect![[r"
+                f: int -> bool -> bool
+            "]],
+        );
+    }
+
+    #[test]
+    fn infer_constructors() {
+        check(
+            "type option a = None | Some a;\ntype shape = | Circle int | Scaled (int -> int) shape;\ndef a = (Some 1);\ndef b = None;\ndef c = (Scaled (mul 2));\ndef d = if true { (Some 1) } else { None };",
+            &expect![[r"
+                a: option int
+                b: option 'a
+                c: shape -> shape
+                d: option int
+            "]],
+        );
+    }
+
+    #[test]
+    fn report_constructor_mismatches() {
+        check(
+            "type shape = | Circle int | Square int;\ntype t = int;\ndef a : t = (Circle 1);\ndef b = (Square true);",
+            &expect![[r"
+                a: int
+                b: shape
+                error: expected `int`, found `shape`
+                error: expected `int`, found `bool`
+            "]],
+        );
+    }
+
+    #[test]
+    fn infer_match() {
+        check(
+            "type option a = None | Some a;\ndef get o d = match o { Some x => x, None => d };\ndef is_zero n = match n { 0 => true, _ => false };",
+            &expect![[r"
+                get: option 'a -> 'a -> 'a
+                is_zero: int -> bool
+            "]],
+        );
+    }
+
+    #[test]
+    fn report_pattern_errors() {
+        check(
+            "type option a = None | Some a;\ndef f o = match o { Some true => 1, Some 1 => 2, Some => 3, Other x => 4, None => false };",
+            &expect![[r"
+                f: option bool -> int
+                error: expected `bool`, found `int`
+                error: constructor `Some` has 1 field, found 0
+                error: unbound constructor `Other`
+                error: expected `int`, found `bool`
+            "]],
+        );
+    }
+
+    #[test]
+    fn report_if_mismatches() {
+        check(
+            "def f = if 1 { true } else { 2 };",
+            &expect![[r"
+                f: bool
+                error: expected `bool`, found `int`
+                error: expected `bool`, found `int`
+            "]],
+        );
+    }
+
+    #[test]
+    fn unbound_type_name() {
+        check(
+            "def f (x: itn) = x;",
+            &expect![[r"
+                f: {unknown} -> {unknown}
+                error: unbound type `itn`
+            "]],
+        );
+    }
+
+    #[test]
+    fn builtin_types_need_prelude() {
+        check_without_prelude(
+            "def f (x: int) = (add x 1);",
+            &expect![[r"
+                f: {unknown} -> 'a
+                error: unbound type `int`
+                error: unbound variable `add`
+            "]],
+        );
+    }
+
+    #[test]
+    fn cyclic_alias_does_not_loop() {
+        check(
+            "type a = b; type b = a; def f (x: a) = x;",
+            &expect![[r"
+                f: {unknown} -> {unknown}
+            "]],
+        );
+    }
+
+    #[test]
+    fn generalize_definitions() {
+        check(
+            "def id x = x; def a = (id 1); def b = (id true);",
+            &expect![[r"
+                id: 'a -> 'a
+                a: int
+                b: bool
+            "]],
+        );
+    }
+
+    #[test]
+    fn mutually_recursive_definitions_are_not_generalized_in_each_other() {
+        check(
+            "def f x = (g x); def g x = (f x); def a = (f 1); def b = (g true);",
+            &expect![[r"
+                f: 'a -> 'b
+                g: 'a -> 'b
+                a: 'a
+                b: 'a
+            "]],
+        );
+    }
+
+    #[test]
+    fn generalize_let() {
+        check(
+            "def f { let id x = x; let a = (id 1); (id true) }",
+            &expect![[r"
+                f: bool
+            "]],
+        );
+    }
+
+    #[test]
+    fn let_is_not_generalized_over_params() {
+        check(
+            "def f x { let y = x; let z = (add y 1); x }",
+            &expect![[r"
+                f: int -> int
+            "]],
+        );
+    }
+
+    #[test]
+    fn print_schemes() {
+        let mut module = Module::new();
+        module.lower_module(
+            &parser::parse("type option a = None | Some a; def const x y = x; def n = 1;").module(),
+        );
+        let result = infer(&module);
+        let mut actual = String::new();
+        for (idx, definition) in module.definitions() {
+            let scheme = result.scheme_of_definition(idx).unwrap();
+            let mut printer = TypePrinter::new(&module);
+            let name = module.lookup_name(definition.name);
+            writeln!(actual, "{name}: {}", printer.print_scheme(scheme)).unwrap();
+        }
+        for (idx, constructor) in module.constructors() {
+            let scheme = result.scheme_of_constructor(idx).unwrap();
+            let mut printer = TypePrinter::new(&module);
+            let name = module.lookup_name(constructor.name);
+            writeln!(actual, "{name}: {}", printer.print_scheme(scheme)).unwrap();
+        }
+        expect![[r"
+            const: forall a b. a -> b -> a
+            n: int
+            None: forall a. option a
+            Some: forall a. a -> option a
+        "]]
+        .assert_eq(&actual);
+    }
+
+    #[test]
+    fn infer_type_application() {
+        check(
+            "type option a = None | Some a;\ntype pair a = a -> a -> a;\ndef f (o: option int) (p: pair bool) = o;\ndef g (o: option (option bool)) = (Some o);",
+            &expect![[r"
+                f: option int -> (bool -> bool -> bool) -> option int
+                g: option (option bool) -> option (option (option bool))
+            "]],
+        );
+    }
+
+    #[test]
+    fn type_vars_are_shared_by_the_definition() {
+        check(
+            "def f (x: 'a) (y: 'a) : 'a = x;\ndef g = (f 1 true);\ndef h (x: 'a) = { let y : 'a = 1; x };",
+            &expect![[r"
+                f: 'a -> 'a -> 'a
+                g: int
+                h: int -> int
+                error: expected `int`, found `bool`
+            "]],
+        );
+    }
+
+    #[test]
+    fn report_type_arity_errors() {
+        check(
+            "type option a = None | Some a;\ndef f (x: option int bool) = x;\ndef g (x: int int) = x;\ndef h (x: 'a int) = x;",
+            &expect![[r"
+                f: {unknown} -> {unknown}
+                g: {unknown} -> {unknown}
+                h: {unknown} -> {unknown}
+                error: expected 1 type argument, found 2
+                error: expected 0 type arguments, found 1
+                error: expected 0 type arguments, found 1
+            "]],
+        );
+    }
+
+    #[test]
+    fn report_unbound_type_vars_once() {
+        check(
+            "type t a = a -> 'a -> 'b;\ndef f (x: t int) (y: t bool) = x;",
+            &expect![[r"
+                f: (int -> int -> {unknown}) -> (bool -> bool -> {unknown}) -> int -> int -> {unknown}
+                error: unbound type variable `'b`
+            "]],
+        );
+    }
+
+    #[test]
+    fn report_duplicate_definitions() {
+        check(
+            "type t = int; type t = bool; def x : t = 1; def x = true; def y = x;",
+            &expect![[r"
+                x: int
+                x: bool
+                y: int
+                error: `x` is already defined
+                error: type `t` is already defined
+            "]],
+        );
+    }
+}
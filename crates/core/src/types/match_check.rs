@@ -0,0 +1,387 @@
+//! Exhaustiveness and redundancy checking of match expressions,
+//! following Maranget's "Warnings for pattern matching".
+
+use std::collections::HashSet;
+
+use super::{InferenceResult, TypeError};
+use crate::hir::{ConstructorIdx, Expr, ExprIdx, Literal, Pat, PatIdx, Scopes, TypeDefinitionBody};
+use crate::Module;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum MatchError {
+    /// Some values are matched by none of the arms.
+    /// `missing` lists patterns covering them, in the surface syntax.
+    NonExhaustive { expr: ExprIdx, missing: Vec<String> },
+    /// The arm only matches values which are matched by earlier arms.
+    RedundantArm { pat: PatIdx },
+}
+
+impl MatchError {
+    #[must_use]
+    pub fn message(&self) -> String {
+        match self {
+            MatchError::NonExhaustive { missing, .. } => {
+                let patterns: Vec<_> = missing.iter().map(|pat| format!("`{pat}`")).collect();
+                format!(
+                    "non-exhaustive match: missing pattern{} {}",
+                    if missing.len() == 1 { "" } else { "s" },
+                    patterns.join(", ")
+                )
+            }
+            MatchError::RedundantArm { .. } => "unreachable match arm".into(),
+        }
+    }
+}
+
+/// Checks all match expressions of the module.
+/// Matches with ill-typed or missing patterns are skipped, as the errors already reported
+/// for them would make these ones misleading.
+#[must_use]
+pub fn check_matches(
+    module: &Module,
+    scopes: &Scopes,
+    inference: &InferenceResult,
+) -> Vec<MatchError> {
+    let ill_typed: HashSet<_> = inference
+        .errors()
+        .iter()
+        .filter_map(TypeError::pat)
+        .collect();
+    let checker = MatchChecker {
+        module,
+        scopes,
+        ill_typed,
+    };
+
+    let mut errors = Vec::new();
+    for (expr, body) in module.exprs() {
+        if let Expr::MatchExpr { arms, .. } = body {
+            let pats: Vec<_> = arms.iter().map(|arm| arm.pat).collect();
+            checker.check_match(expr, &pats, &mut errors);
+        }
+    }
+    errors
+}
+
+/// Head of a pattern or of a value not matched by a pattern.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Ctor {
+    Int(i64),
+    Bool(bool),
+    Variant(ConstructorIdx),
+    /// Matches every value, like wildcards and bindings.
+    Wild,
+}
+
+/// A pattern stripped of names, so that only its shape is left.
+#[derive(Clone, Debug)]
+struct DeconstructedPat {
+    ctor: Ctor,
+    fields: Vec<DeconstructedPat>,
+}
+
+impl DeconstructedPat {
+    fn wild() -> Self {
+        Self {
+            ctor: Ctor::Wild,
+            fields: Vec::new(),
+        }
+    }
+}
+
+type Row = Vec<DeconstructedPat>;
+
+/// Heads which values of the type of a column may have.
+enum Signature {
+    Finite(Vec<(Ctor, usize)>),
+    /// Integers, which no finite set of literals covers.
+    Infinite,
+}
+
+struct MatchChecker<'a> {
+    module: &'a Module,
+    scopes: &'a Scopes,
+    ill_typed: HashSet<PatIdx>,
+}
+
+impl MatchChecker<'_> {
+    fn check_match(&self, expr: ExprIdx, pats: &[PatIdx], errors: &mut Vec<MatchError>) {
+        if pats.iter().any(|pat| !self.is_well_formed(*pat)) {
+            return;
+        }
+
+        let mut rows: Vec<Row> = Vec::new();
+        for pat in pats {
+            let row = vec![self.deconstruct(*pat)];
+            if !self.is_useful(&rows, &row) {
+                errors.push(MatchError::RedundantArm { pat: *pat });
+            }
+            rows.push(row);
+        }
+
+        let missing = self.witnesses(&rows, 1);
+        if !missing.is_empty() {
+            let missing = missing.iter().map(|row| self.render(&row[0])).collect();
+            errors.push(MatchError::NonExhaustive { expr, missing });
+        }
+    }
+
+    fn is_well_formed(&self, pat: PatIdx) -> bool {
+        if self.ill_typed.contains(&pat) {
+            return false;
+        }
+        match self.module.get_pat(pat) {
+            Pat::Missing => false,
+            Pat::Wildcard | Pat::Literal(_) => true,
+            Pat::Ident { args, .. } => args.iter().all(|arg| self.is_well_formed(*arg)),
+        }
+    }
+
+    fn deconstruct(&self, pat: PatIdx) -> DeconstructedPat {
+        match self.module.get_pat(pat) {
+            Pat::Missing | Pat::Wildcard => DeconstructedPat::wild(),
+            Pat::Literal(Literal::IntLiteral(value)) => DeconstructedPat {
+                ctor: Ctor::Int(*value),
+                fields: Vec::new(),
+            },
+            Pat::Literal(Literal::BoolLiteral(value)) => DeconstructedPat {
+                ctor: Ctor::Bool(*value),
+                fields: Vec::new(),
+            },
+            Pat::Ident { args, .. } => match self.scopes.pat_constructor(pat) {
+                Some(constructor) => DeconstructedPat {
+                    ctor: Ctor::Variant(constructor),
+                    fields: args.iter().map(|arg| self.deconstruct(*arg)).collect(),
+                },
+                None => DeconstructedPat::wild(),
+            },
+        }
+    }
+
+    /// The signature of the type of a column, judged by the heads appearing in it.
+    /// `None` if the column only has wildcards.
+    fn signature(&self, heads: &[Ctor]) -> Option<Signature> {
+        let signature = match heads.first()? {
+            Ctor::Int(_) => Signature::Infinite,
+            Ctor::Bool(_) => Signature::Finite(vec![(Ctor::Bool(false), 0), (Ctor::Bool(true), 0)]),
+            Ctor::Variant(constructor) => {
+                let parent = self.module.get_constructor(*constructor).parent;
+                let TypeDefinitionBody::Sum(constructors) =
+                    &self.module.get_type_definition(parent).defn
+                else {
+                    unreachable!("constructors belong to sum types")
+                };
+                Signature::Finite(
+                    constructors
+                        .iter()
+                        .map(|constructor| {
+                            let arity = self.module.get_constructor(*constructor).fields.len();
+                            (Ctor::Variant(*constructor), arity)
+                        })
+                        .collect(),
+                )
+            }
+            Ctor::Wild => unreachable!("wildcards are not heads"),
+        };
+        Some(signature)
+    }
+
+    /// Values of `width` columns matched by none of the rows, as rows of patterns.
+    fn witnesses(&self, rows: &[Row], width: usize) -> Vec<Row> {
+        if width == 0 {
+            return if rows.is_empty() {
+                vec![Vec::new()]
+            } else {
+                Vec::new()
+            };
+        }
+
+        // Values of finite types are split by their head, so that the missing ones can be
+        // spelled out. Other columns are only covered by wildcards.
+        let Some(Signature::Finite(ctors)) = self.signature(&column_heads(rows)) else {
+            let mut witnesses = self.witnesses(&default_rows(rows), width - 1);
+            for witness in &mut witnesses {
+                witness.insert(0, DeconstructedPat::wild());
+            }
+            return witnesses;
+        };
+        let mut witnesses = Vec::new();
+        for (ctor, arity) in ctors {
+            let specialized = specialize(rows, ctor, arity);
+            for mut row in self.witnesses(&specialized, arity + width - 1) {
+                let rest = row.split_off(arity);
+                let mut witness = vec![DeconstructedPat { ctor, fields: row }];
+                witness.extend(rest);
+                witnesses.push(witness);
+            }
+        }
+        witnesses
+    }
+
+    /// Whether some value matched by `row` is matched by none of the `rows`.
+    fn is_useful(&self, rows: &[Row], row: &[DeconstructedPat]) -> bool {
+        let Some((head, rest)) = row.split_first() else {
+            return rows.is_empty();
+        };
+
+        if head.ctor != Ctor::Wild {
+            let specialized = specialize(rows, head.ctor, head.fields.len());
+            let mut row = head.fields.clone();
+            row.extend(rest.iter().cloned());
+            return self.is_useful(&specialized, &row);
+        }
+
+        match self.signature(&column_heads(rows)) {
+            Some(Signature::Finite(ctors)) => ctors.into_iter().any(|(ctor, arity)| {
+                let mut row = vec![DeconstructedPat::wild(); arity];
+                row.extend(rest.iter().cloned());
+                self.is_useful(&specialize(rows, ctor, arity), &row)
+            }),
+            Some(Signature::Infinite) | None => self.is_useful(&default_rows(rows), rest),
+        }
+    }
+
+    fn render(&self, pat: &DeconstructedPat) -> String {
+        match pat.ctor {
+            Ctor::Wild => "_".into(),
+            Ctor::Int(value) => value.to_string(),
+            Ctor::Bool(value) => value.to_string(),
+            Ctor::Variant(constructor) => {
+                let name = self.module.get_constructor(constructor).name;
+                let mut text = self.module.lookup_name(name).to_string();
+                for field in &pat.fields {
+                    let field_text = self.render(field);
+                    if field.fields.is_empty() {
+                        text = format!("{text} {field_text}");
+                    } else {
+                        text = format!("{text} ({field_text})");
+                    }
+                }
+                text
+            }
+        }
+    }
+}
+
+/// Heads of the first column, without wildcards and duplicates, in order of appearance.
+fn column_heads(rows: &[Row]) -> Vec<Ctor> {
+    let mut heads = Vec::new();
+    for row in rows {
+        let ctor = row[0].ctor;
+        if ctor != Ctor::Wild && !heads.contains(&ctor) {
+            heads.push(ctor);
+        }
+    }
+    heads
+}
+
+/// Rows matching values with the `ctor` head, with the fields of the head in place of the first column.
+fn specialize(rows: &[Row], ctor: Ctor, arity: usize) -> Vec<Row> {
+    rows.iter()
+        .filter_map(|row| {
+            let (head, rest) = row.split_first()?;
+            let mut specialized = if head.ctor == Ctor::Wild {
+                vec![DeconstructedPat::wild(); arity]
+            } else if head.ctor == ctor {
+                head.fields.clone()
+            } else {
+                return None;
+            };
+            specialized.extend(rest.iter().cloned());
+            Some(specialized)
+        })
+        .collect()
+}
+
+/// Rows starting with a wildcard, without it.
+fn default_rows(rows: &[Row]) -> Vec<Row> {
+    rows.iter()
+        .filter(|row| row[0].ctor == Ctor::Wild)
+        .map(|row| row[1..].to_vec())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write;
+
+    use expect_test::{expect, Expect};
+
+    use super::{check_matches, MatchError};
+    use crate::{infer, Module, Scopes};
+
+    fn check(text: &str, expected: &Expect) {
+        let mut module = Module::new();
+        module.lower_module(&parser::parse(text).module());
+        let scopes = Scopes::new(&module);
+        let inference = infer(&module);
+
+        let mut actual = String::new();
+        for error in check_matches(&module, &scopes, &inference) {
+            if let MatchError::RedundantArm { pat } = &error {
+                write!(actual, "{}: ", module.debug_pat(*pat)).unwrap();
+            }
+            writeln!(actual, "{}", error.message()).unwrap();
+        }
+        expected.assert_eq(&actual);
+    }
+
+    #[test]
+    fn exhaustive_matches() {
+        check(
+            "type option a = None | Some a;\ndef f o = match o { Some true => 1, Some false => 2, None => 3 };\ndef g n = match n { 0 => 1, m => m };",
+            &expect![""],
+        );
+    }
+
+    #[test]
+    fn report_missing_constructors() {
+        check(
+            "type option a = None | Some a;\ndef f o = match o { Some true => 1 };",
+            &expect![[r"
+                non-exhaustive match: missing patterns `None`, `Some false`
+            "]],
+        );
+    }
+
+    #[test]
+    fn report_missing_nested_patterns() {
+        check(
+            "type option a = None | Some a;\ndef f o = match o { None => 1, Some None => 2, Some (Some 0) => 3 };",
+            &expect![[r"
+                non-exhaustive match: missing pattern `Some (Some _)`
+            "]],
+        );
+    }
+
+    #[test]
+    fn report_missing_literals() {
+        check(
+            "def f b = match b { true => 1 };\ndef g n = match n { 1 => 1, 2 => 2 };",
+            &expect![[r"
+                non-exhaustive match: missing pattern `false`
+                non-exhaustive match: missing pattern `_`
+            "]],
+        );
+    }
+
+    #[test]
+    fn report_redundant_arms() {
+        check(
+            "type option a = None | Some a;\ndef f o = match o { None => 1, Some _ => 2, _ => 3, None => 4 };\ndef g n = match n { 1 => 1, x => x, 1 => 2 };",
+            &expect![[r"
+                _: unreachable match arm
+                None: unreachable match arm
+                1: unreachable match arm
+            "]],
+        );
+    }
+
+    #[test]
+    fn skip_matches_with_pattern_errors() {
+        check(
+            "type option a = None | Some a;\ndef f o = match o { Some 1 => 1, Some true => 2 };",
+            &expect![""],
+        );
+    }
+}
@@ -0,0 +1,218 @@
+mod infer;
+mod match_check;
+mod unify;
+
+use std::collections::HashMap;
+use std::fmt;
+
+pub use infer::{infer, InferenceResult, TypeError};
+pub use match_check::{check_matches, MatchError};
+
+use crate::hir::TypeDefinitionIdx;
+use crate::Module;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct TypeVar(u32);
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Type {
+    Int,
+    Bool,
+    Unit,
+    /// A sum type declared in the module, applied to its type arguments.
+    Sum(TypeDefinitionIdx, Vec<Type>),
+    Var(TypeVar),
+    Arrow(Box<Type>, Box<Type>),
+    /// Type of an expression which failed to typecheck; unifies with anything.
+    Error,
+}
+
+impl Type {
+    pub(crate) fn arrow(from: Type, to: Type) -> Self {
+        Self::Arrow(Box::new(from), Box::new(to))
+    }
+
+    /// Type variables of the type, in order of their first occurrence.
+    pub(crate) fn free_vars(&self, vars: &mut Vec<TypeVar>) {
+        match self {
+            Type::Var(var) => {
+                if !vars.contains(var) {
+                    vars.push(*var);
+                }
+            }
+            Type::Arrow(from, to) => {
+                from.free_vars(vars);
+                to.free_vars(vars);
+            }
+            Type::Sum(_, args) => args.iter().for_each(|arg| arg.free_vars(vars)),
+            Type::Int | Type::Bool | Type::Unit | Type::Error => {}
+        }
+    }
+
+    pub(crate) fn substitute(&self, substitution: &HashMap<TypeVar, Type>) -> Type {
+        match self {
+            Type::Var(var) => substitution.get(var).unwrap_or(self).clone(),
+            Type::Arrow(from, to) => {
+                Type::arrow(from.substitute(substitution), to.substitute(substitution))
+            }
+            Type::Sum(type_definition, args) => Type::Sum(
+                *type_definition,
+                args.iter()
+                    .map(|arg| arg.substitute(substitution))
+                    .collect(),
+            ),
+            Type::Int | Type::Bool | Type::Unit | Type::Error => self.clone(),
+        }
+    }
+
+    fn occurs(&self, var: TypeVar) -> bool {
+        match self {
+            Type::Var(v) => *v == var,
+            Type::Arrow(from, to) => from.occurs(var) || to.occurs(var),
+            Type::Sum(_, args) => args.iter().any(|arg| arg.occurs(var)),
+            Type::Int | Type::Bool | Type::Unit | Type::Error => false,
+        }
+    }
+
+    #[must_use]
+    pub fn display<'a>(&'a self, module: &'a Module) -> TypeDisplay<'a> {
+        TypeDisplay { typ: self, module }
+    }
+}
+
+/// A type generalized over some of its variables, like `forall a. a -> a`.
+/// Every use of a binding with such a type instantiates the variables anew.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Scheme {
+    pub vars: Vec<TypeVar>,
+    pub typ: Type,
+}
+
+impl Scheme {
+    /// A scheme without quantified variables.
+    #[must_use]
+    pub fn mono(typ: Type) -> Self {
+        Self {
+            vars: Vec::new(),
+            typ,
+        }
+    }
+}
+
+pub struct TypeDisplay<'a> {
+    typ: &'a Type,
+    module: &'a Module,
+}
+
+impl fmt::Display for TypeDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&TypePrinter::new(self.module).print(self.typ))
+    }
+}
+
+/// Prints types, naming type variables consistently across all printed types.
+pub struct TypePrinter<'a> {
+    module: &'a Module,
+    vars: HashMap<TypeVar, usize>,
+    /// Variables of the scheme being printed, named without a quote.
+    quantified: Vec<TypeVar>,
+}
+
+impl<'a> TypePrinter<'a> {
+    #[must_use]
+    pub fn new(module: &'a Module) -> Self {
+        Self {
+            module,
+            vars: HashMap::new(),
+            quantified: Vec::new(),
+        }
+    }
+
+    pub fn print(&mut self, typ: &Type) -> String {
+        let mut buf = String::new();
+        self.print_into(&mut buf, typ, false);
+        buf
+    }
+
+    /// Prints the scheme as `forall a b. a -> b`, with the variables in order of occurrence.
+    pub fn print_scheme(&mut self, scheme: &Scheme) -> String {
+        self.quantified.clone_from(&scheme.vars);
+        let typ = self.print(&scheme.typ);
+        let mut vars: Vec<_> = std::mem::take(&mut self.quantified)
+            .into_iter()
+            .filter_map(|var| self.vars.get(&var).copied())
+            .collect();
+        if vars.is_empty() {
+            return typ;
+        }
+        vars.sort_unstable();
+
+        let mut buf = "forall".to_string();
+        for idx in vars {
+            buf.push(' ');
+            push_var_name(&mut buf, idx, false);
+        }
+        buf.push_str(". ");
+        buf.push_str(&typ);
+        buf
+    }
+
+    fn print_into(&mut self, buf: &mut String, typ: &Type, parenthesize_arrow: bool) {
+        match typ {
+            Type::Sum(type_definition, args) => {
+                let name = self.module.get_type_definition(*type_definition).name;
+                buf.push_str(self.module.lookup_name(name));
+                for arg in args {
+                    buf.push(' ');
+                    self.print_arg_into(buf, arg);
+                }
+            }
+            Type::Int => buf.push_str("int"),
+            Type::Bool => buf.push_str("bool"),
+            Type::Unit => buf.push_str("unit"),
+            Type::Var(var) => {
+                let next = self.vars.len();
+                let idx = *self.vars.entry(*var).or_insert(next);
+                push_var_name(buf, idx, !self.quantified.contains(var));
+            }
+            Type::Error => buf.push_str("{unknown}"),
+            Type::Arrow(from, to) => {
+                if parenthesize_arrow {
+                    buf.push('(');
+                }
+                self.print_into(buf, from, true);
+                buf.push_str(" -> ");
+                self.print_into(buf, to, false);
+                if parenthesize_arrow {
+                    buf.push(')');
+                }
+            }
+        }
+    }
+
+    /// Type arguments are parenthesized unless they are a single name.
+    fn print_arg_into(&mut self, buf: &mut String, typ: &Type) {
+        let parenthesize = match typ {
+            Type::Arrow(..) => true,
+            Type::Sum(_, args) => !args.is_empty(),
+            _ => false,
+        };
+        if parenthesize {
+            buf.push('(');
+        }
+        self.print_into(buf, typ, false);
+        if parenthesize {
+            buf.push(')');
+        }
+    }
+}
+
+fn push_var_name(buf: &mut String, idx: usize, quoted: bool) {
+    if quoted {
+        buf.push('\'');
+    }
+    buf.push(char::from(b'a' + u8::try_from(idx % 26).unwrap()));
+    if idx >= 26 {
+        buf.push_str(&(idx / 26).to_string());
+    }
+}
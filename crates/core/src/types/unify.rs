@@ -0,0 +1,77 @@
+use super::{Type, TypeVar};
+
+pub(crate) enum UnifyError {
+    Mismatch,
+    Occurs(TypeVar, Type),
+}
+
+/// Substitution of type variables, built incrementally by unification.
+#[derive(Default)]
+pub(crate) struct UnificationTable {
+    bindings: Vec<Option<Type>>,
+}
+
+impl UnificationTable {
+    pub(crate) fn fresh(&mut self) -> Type {
+        let var = TypeVar(self.bindings.len().try_into().unwrap());
+        self.bindings.push(None);
+        Type::Var(var)
+    }
+
+    fn binding(&self, var: TypeVar) -> Option<&Type> {
+        self.bindings[var.0 as usize].as_ref()
+    }
+
+    /// Follows bound variables until reaching an unbound one or a non-variable type.
+    pub(crate) fn shallow_resolve(&self, typ: &Type) -> Type {
+        let mut typ = typ;
+        while let Type::Var(var) = typ {
+            match self.binding(*var) {
+                Some(bound) => typ = bound,
+                None => break,
+            }
+        }
+        typ.clone()
+    }
+
+    /// Substitutes all bound variables in the type.
+    pub(crate) fn resolve(&self, typ: &Type) -> Type {
+        match self.shallow_resolve(typ) {
+            Type::Arrow(from, to) => Type::arrow(self.resolve(&from), self.resolve(&to)),
+            Type::Sum(type_definition, args) => Type::Sum(
+                type_definition,
+                args.iter().map(|arg| self.resolve(arg)).collect(),
+            ),
+            typ => typ,
+        }
+    }
+
+    pub(crate) fn unify(&mut self, a: &Type, b: &Type) -> Result<(), UnifyError> {
+        let a = self.shallow_resolve(a);
+        let b = self.shallow_resolve(b);
+        match (a, b) {
+            (Type::Error, _) | (_, Type::Error) => Ok(()),
+            (Type::Var(a), Type::Var(b)) if a == b => Ok(()),
+            (Type::Var(var), typ) | (typ, Type::Var(var)) => self.bind(var, &typ),
+            (Type::Arrow(a_from, a_to), Type::Arrow(b_from, b_to)) => {
+                self.unify(&a_from, &b_from)?;
+                self.unify(&a_to, &b_to)
+            }
+            (Type::Sum(a, a_args), Type::Sum(b, b_args)) if a == b => a_args
+                .iter()
+                .zip(&b_args)
+                .try_for_each(|(a_arg, b_arg)| self.unify(a_arg, b_arg)),
+            (a, b) if a == b => Ok(()),
+            _ => Err(UnifyError::Mismatch),
+        }
+    }
+
+    fn bind(&mut self, var: TypeVar, typ: &Type) -> Result<(), UnifyError> {
+        let typ = self.resolve(typ);
+        if typ.occurs(var) {
+            return Err(UnifyError::Occurs(var, typ));
+        }
+        self.bindings[var.0 as usize] = Some(typ);
+        Ok(())
+    }
+}
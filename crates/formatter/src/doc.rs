@@ -0,0 +1,185 @@
+/// Lines are broken to stay within this many columns, when possible.
+pub(crate) const WIDTH: usize = 80;
+pub(crate) const INDENT: usize = 4;
+
+/// Layout of the formatted code, before choosing where lines are broken.
+#[derive(Debug)]
+pub(crate) enum Doc {
+    Text(String),
+    /// A space, or a line break if the enclosing group does not fit on one line.
+    Line,
+    /// A line break, always.
+    HardLine,
+    /// A line break, unless the current line is still empty.
+    FreshLine,
+    /// Text kept at the end of the current line, like a trailing comment.
+    LineSuffix(String),
+    /// Forces the enclosing groups to break.
+    BreakParent,
+    Indent(Box<Doc>),
+    /// Laid out on a single line if it fits, otherwise with all its lines broken.
+    Group(Box<Doc>),
+    Concat(Vec<Doc>),
+}
+
+impl Doc {
+    pub(crate) fn text(text: impl Into<String>) -> Self {
+        Self::Text(text.into())
+    }
+
+    pub(crate) fn indent(doc: Doc) -> Self {
+        Self::Indent(Box::new(doc))
+    }
+
+    pub(crate) fn group(doc: Doc) -> Self {
+        Self::Group(Box::new(doc))
+    }
+
+    pub(crate) fn nil() -> Self {
+        Self::Concat(Vec::new())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Flat,
+    Break,
+}
+
+type Command<'a> = (usize, Mode, &'a Doc);
+
+/// Prints the document, breaking the groups which do not fit in [`WIDTH`].
+pub(crate) fn render(doc: &Doc) -> String {
+    let mut renderer = Renderer {
+        out: String::new(),
+        column: 0,
+        line_is_empty: true,
+        pending_indent: None,
+        suffixes: Vec::new(),
+    };
+    let mut commands: Vec<Command> = vec![(0, Mode::Break, doc)];
+    while let Some((indent, mode, doc)) = commands.pop() {
+        match doc {
+            Doc::Text(text) => renderer.text(indent, text),
+            Doc::Line => match mode {
+                Mode::Flat => renderer.text(indent, " "),
+                Mode::Break => renderer.newline(indent),
+            },
+            Doc::HardLine => renderer.newline(indent),
+            Doc::FreshLine => {
+                if renderer.line_is_empty {
+                    renderer.pending_indent = Some(indent);
+                    renderer.column = indent;
+                } else {
+                    renderer.newline(indent);
+                }
+            }
+            Doc::LineSuffix(suffix) => renderer.suffixes.push(suffix.clone()),
+            Doc::BreakParent => {}
+            Doc::Indent(doc) => commands.push((indent + INDENT, mode, doc)),
+            Doc::Group(doc) => {
+                let flat = mode == Mode::Flat
+                    || fits(
+                        WIDTH.saturating_sub(renderer.column),
+                        indent,
+                        doc,
+                        &commands,
+                    );
+                let mode = if flat { Mode::Flat } else { Mode::Break };
+                commands.push((indent, mode, doc));
+            }
+            Doc::Concat(docs) => {
+                commands.extend(docs.iter().rev().map(|doc| (indent, mode, doc)));
+            }
+        }
+    }
+    renderer.flush_suffixes();
+    renderer.out.truncate(renderer.out.trim_end().len());
+    renderer.out
+}
+
+/// Whether the group fits on the rest of the line when laid out flat,
+/// together with whatever follows it up to the next line break.
+fn fits(width: usize, indent: usize, group: &Doc, rest: &[Command]) -> bool {
+    let mut width = width;
+    let mut rest = rest.iter().rev();
+    let mut commands = vec![(indent, Mode::Flat, group, false)];
+    loop {
+        let (indent, mode, doc, in_rest) = match commands.pop() {
+            Some(command) => command,
+            None => match rest.next() {
+                Some(&(indent, mode, doc)) => (indent, mode, doc, true),
+                None => return true,
+            },
+        };
+        match doc {
+            Doc::Text(text) => match width.checked_sub(text.chars().count()) {
+                Some(rest) => width = rest,
+                None => return false,
+            },
+            Doc::Line => match mode {
+                Mode::Flat => match width.checked_sub(1) {
+                    Some(rest) => width = rest,
+                    None => return false,
+                },
+                Mode::Break => return true,
+            },
+            Doc::HardLine | Doc::FreshLine => return true,
+            Doc::LineSuffix(_) => {}
+            Doc::BreakParent => {
+                if !in_rest {
+                    return false;
+                }
+            }
+            Doc::Indent(doc) => commands.push((indent + INDENT, mode, doc, in_rest)),
+            Doc::Group(doc) => commands.push((indent, mode, doc, in_rest)),
+            Doc::Concat(docs) => {
+                commands.extend(docs.iter().rev().map(|doc| (indent, mode, doc, in_rest)));
+            }
+        }
+    }
+}
+
+struct Renderer {
+    out: String,
+    column: usize,
+    line_is_empty: bool,
+    /// Indentation is written lazily, so that empty lines have none.
+    pending_indent: Option<usize>,
+    suffixes: Vec<String>,
+}
+
+impl Renderer {
+    fn text(&mut self, indent: usize, text: &str) {
+        // Nothing may follow a comment on its line, and spaces are not needed after it.
+        if !self.suffixes.is_empty() {
+            if text.trim().is_empty() {
+                return;
+            }
+            self.newline(indent);
+        }
+        if let Some(indent) = self.pending_indent.take() {
+            self.out.extend(std::iter::repeat_n(' ', indent));
+        }
+        self.out.push_str(text);
+        self.column += text.chars().count();
+        self.line_is_empty = false;
+    }
+
+    fn newline(&mut self, indent: usize) {
+        self.flush_suffixes();
+        self.out.truncate(self.out.trim_end_matches(' ').len());
+        self.out.push('\n');
+        self.pending_indent = Some(indent);
+        self.column = indent;
+        self.line_is_empty = true;
+    }
+
+    fn flush_suffixes(&mut self) {
+        for suffix in std::mem::take(&mut self.suffixes) {
+            self.out.truncate(self.out.trim_end_matches(' ').len());
+            self.out.push(' ');
+            self.out.push_str(&suffix);
+        }
+    }
+}
@@ -0,0 +1,330 @@
+mod doc;
+mod printer;
+
+use rowan::TextRange;
+
+use crate::doc::{render, Doc};
+use crate::printer::{item_range, Printer};
+
+/// Formats the module, `None` if it has syntax errors or comments it could not place.
+/// Comments are kept, as are single blank lines between items and statements.
+#[must_use]
+pub fn format(text: &str) -> Option<String> {
+    let parse = parser::parse(text);
+    if !parse.errors.is_empty() {
+        return None;
+    }
+    let root = parse.syntax();
+    let items: Vec<_> = root.children().collect();
+
+    let mut printer = Printer::default();
+    let doc = Doc::Concat(vec![printer.items(&items), printer.end_comments(&root)]);
+    if !printer.printed_comments_in(&root, root.text_range()) {
+        return None;
+    }
+    let mut formatted = render(&doc);
+    if !formatted.is_empty() {
+        formatted.push('\n');
+    }
+    Some(formatted)
+}
+
+/// Formats the module items touching the range, `None` if there are none,
+/// the module has syntax errors or comments could not be placed.
+/// Returns the range of the items with their comments and its formatted text.
+#[must_use]
+pub fn format_range(text: &str, range: TextRange) -> Option<(TextRange, String)> {
+    let parse = parser::parse(text);
+    if !parse.errors.is_empty() {
+        return None;
+    }
+    let root = parse.syntax();
+    let items: Vec<_> = root
+        .children()
+        .filter_map(|item| Some((item_range(&item)?, item)))
+        .filter(|(item_range, _)| item_range.intersect(range).is_some())
+        .collect();
+    let (first, _) = items.first()?;
+    let (last, _) = items.last()?;
+    let replaced = first.cover(*last);
+
+    let items: Vec<_> = items.into_iter().map(|(_, item)| item).collect();
+    let mut printer = Printer::default();
+    let doc = printer.items(&items);
+    if !printer.printed_comments_in(&root, replaced) {
+        return None;
+    }
+    Some((replaced, render(&doc)))
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+    use rowan::{TextRange, TextSize};
+
+    use super::{format, format_range};
+
+    fn check(text: &str, expected: &Expect) {
+        let formatted = format(text).expect("no syntax errors");
+        expected.assert_eq(&formatted);
+        assert_eq!(
+            format(&formatted).as_deref(),
+            Some(formatted.as_str()),
+            "idempotent"
+        );
+    }
+
+    #[test]
+    fn module_items() {
+        check(
+            "open   list ;type t=int->( int->bool ) ;\ndef a=1;def f (x:int)y:int=(add x y);",
+            &expect![[r"
+                open list;
+                type t = int -> (int -> bool);
+                def a = 1;
+                def f (x: int) y : int = (add x y);
+            "]],
+        );
+    }
+
+    #[test]
+    fn blank_lines_are_collapsed() {
+        check(
+            "\n\ndef a = 1;\n\n\n\ndef b = 2;\ndef c {\n\n    let x = 1;\n\n\n    x\n}\n\n",
+            &expect![[r"
+                def a = 1;
+
+                def b = 2;
+                def c {
+                    let x = 1;
+
+                    x
+                }
+            "]],
+        );
+    }
+
+    #[test]
+    fn blocks_and_lambdas() {
+        check(
+            "def f {let g=\\x (y:int)->{let z=(add x y);z};(g 1 2)}\ndef e {}",
+            &expect![[r"
+                def f {
+                    let g = \x (y: int) -> {
+                        let z = (add x y);
+                        z
+                    };
+                    (g 1 2)
+                }
+                def e {}
+            "]],
+        );
+    }
+
+    #[test]
+    fn block_arguments_break_applications() {
+        check(
+            "def a = (map (\\x -> { (add x 1) }) l);",
+            &expect![[r"
+                def a = (map
+                    (\x -> {
+                        (add x 1)
+                    })
+                    l);
+            "]],
+        );
+    }
+
+    #[test]
+    fn if_expressions() {
+        check(
+            "def f x = if (eq x 0) {true}else if x{ false } else {\n  # other\n  x\n};",
+            &expect![[r"
+                def f x = if (eq x 0) {
+                    true
+                } else if x {
+                    false
+                } else {
+                    # other
+                    x
+                };
+            "]],
+        );
+    }
+
+    #[test]
+    fn match_expressions() {
+        check(
+            "def f o = match o {Some (Some  x)=>x,\n  # nothing\n  None=>0, _ => {1}};\ndef g = match x {};",
+            &expect![[r"
+                def f o = match o {
+                    Some (Some x) => x,
+                    # nothing
+                    None => 0,
+                    _ => {
+                        1
+                    },
+                };
+                def g = match x {};
+            "]],
+        );
+    }
+
+    #[test]
+    fn comments() {
+        check(
+            "# module\n\n# about a\ndef a = 1; # one\ndef f {\n  # inside\n  let x = 1; # x\n  x\n  # last\n}\n# end\n",
+            &expect![[r"
+                # module
+
+                # about a
+                def a = 1; # one
+                def f {
+                    # inside
+                    let x = 1; # x
+                    x
+                    # last
+                }
+                # end
+            "]],
+        );
+    }
+
+    #[test]
+    fn comments_inside_expressions() {
+        check(
+            "def a = (f # first\n  1\n  # before two\n  2);\ndef b = { # empty\n};",
+            &expect![[r"
+                def a = (f # first
+                    1
+                    # before two
+                    2);
+                def b = { # empty
+                };
+            "]],
+        );
+    }
+
+    #[test]
+    fn comments_before_separators() {
+        check(
+            "type t\n  # c\n= a;\ndef f { let a # one\n    # two\n    = 1; a }\n\
+            type u = a # to\n -> b;\ntype v = A\n# c\n | B;\n\
+            def g x = match x { 1 # one\n => 2, _\n # other\n => \\y # y\n -> y };",
+            &expect![[r"
+                type t
+                # c
+                = a;
+                def f {
+                    let a # one
+                    # two
+                    = 1;
+                    a
+                }
+                type u =
+                    a # to
+                        ->
+                        b;
+                type v =
+                    A
+                    # c
+                    | B;
+                def g x = match x {
+                    1 # one
+                    => 2,
+                    _
+                    # other
+                    => \y # y
+                    -> y,
+                };
+            "]],
+        );
+    }
+
+    #[test]
+    fn long_applications_are_broken() {
+        check(
+            "def a = (function_with_a_long_name first_argument second_argument (nested_function x y));",
+            &expect![[r"
+                def a = (function_with_a_long_name
+                    first_argument
+                    second_argument
+                    (nested_function x y));
+            "]],
+        );
+    }
+
+    #[test]
+    fn long_types_are_broken() {
+        check(
+            "type long_function_type = first_argument_type -> second_argument_type -> result_type;",
+            &expect![[r"
+                type long_function_type =
+                    first_argument_type -> second_argument_type -> result_type;
+            "]],
+        );
+    }
+
+    #[test]
+    fn sum_types() {
+        check(
+            "type option  a=None|Some a;\ntype shape = | Circle int | Rectangle int int | Polygon (int -> int) int int | Empty;",
+            &expect![[r"
+                type option a = None | Some a;
+                type shape =
+                    | Circle int
+                    | Rectangle int int
+                    | Polygon (int -> int) int int
+                    | Empty;
+            "]],
+        );
+    }
+
+    #[test]
+    fn type_applications() {
+        check(
+            "type pair  a = a->a->a;\ndef f (x :  option (pair  'a)) :'a = x;",
+            &expect![[r"
+                type pair a = a -> a -> a;
+                def f (x: option (pair 'a)) : 'a = x;
+            "]],
+        );
+    }
+
+    #[test]
+    fn long_expressions_are_broken() {
+        check(
+            "def a = first_operand_of_a_very_long_addition + second_operand_of_a_very_long_addition + third_operand;",
+            &expect![[r"
+                def a =
+                    first_operand_of_a_very_long_addition
+                        + second_operand_of_a_very_long_addition
+                        + third_operand;
+            "]],
+        );
+    }
+
+    #[test]
+    fn applications_as_operands() {
+        check(
+            "def a = (f  x+g y*2);",
+            &expect![[r"
+                def a = (f x + g y * 2);
+            "]],
+        );
+    }
+
+    #[test]
+    fn syntax_errors_are_not_formatted() {
+        assert_eq!(format("def a = ;"), None);
+    }
+
+    #[test]
+    fn range_formats_touched_items() {
+        let text = "def a=1;\n# about b\ndef b=2; # two\ndef c=3;\n";
+        let start = TextSize::try_from(text.find("b=").unwrap()).unwrap();
+        let (range, formatted) = format_range(text, TextRange::empty(start)).unwrap();
+        assert_eq!(&text[range], "# about b\ndef b=2; # two");
+        assert_eq!(formatted, "# about b\ndef b = 2; # two");
+    }
+}
@@ -0,0 +1,619 @@
+use std::collections::HashSet;
+
+use parser::{SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken};
+use rowan::{TextRange, TextSize};
+
+use crate::doc::Doc;
+
+/// Builds the layout of syntax trees without errors, keeping their comments.
+#[derive(Default)]
+pub(crate) struct Printer {
+    printed_comments: HashSet<TextSize>,
+}
+
+impl Printer {
+    /// Module items separated by line breaks, keeping single blank lines between them.
+    pub(crate) fn items(&mut self, items: &[SyntaxNode]) -> Doc {
+        let mut docs = Vec::new();
+        for (index, item) in items.iter().enumerate() {
+            if index > 0 {
+                docs.push(Doc::HardLine);
+            }
+            if let Some(first) = first_token(item) {
+                docs.push(self.leading_comments(&first, Blanks::Kept { first: index == 0 }));
+            }
+            docs.push(self.node(item));
+        }
+        Doc::Concat(docs)
+    }
+
+    /// Comments after the last item of the module.
+    pub(crate) fn end_comments(&mut self, root: &SyntaxNode) -> Doc {
+        let mut trivia: Vec<_> = std::iter::successors(last_descendant_token(root), token_before)
+            .take_while(|token| token.kind().is_trivial())
+            .collect();
+        trivia.reverse();
+        let has_previous = last_token(root).is_some();
+        let blanks = Blanks::Kept {
+            first: !has_previous,
+        };
+        self.comments(&trivia, has_previous, blanks).0
+    }
+
+    /// Whether all the comments of the node in the range were printed.
+    pub(crate) fn printed_comments_in(&self, node: &SyntaxNode, range: TextRange) -> bool {
+        node.descendants_with_tokens()
+            .filter_map(SyntaxElement::into_token)
+            .filter(|token| token.kind() == SyntaxKind::COMMENT)
+            .filter(|comment| range.contains_range(comment.text_range()))
+            .all(|comment| {
+                self.printed_comments
+                    .contains(&comment.text_range().start())
+            })
+    }
+
+    fn node(&mut self, node: &SyntaxNode) -> Doc {
+        match node.kind() {
+            SyntaxKind::DEFINITION | SyntaxKind::LET_STMT | SyntaxKind::LAMBDA_EXPR => {
+                self.binder(node)
+            }
+            SyntaxKind::TYPE_DEFINITION => self.type_definition(node),
+            SyntaxKind::TYPE_ANNOTATION => {
+                let mut elements = significant_children(node);
+                let Some(colon) = elements.next() else {
+                    return Doc::nil();
+                };
+                let mut docs = vec![self.element(&colon)];
+                for element in elements {
+                    docs.push(Doc::text(" "));
+                    docs.push(self.element(&element));
+                }
+                Doc::Concat(docs)
+            }
+            SyntaxKind::TYPE_ARROW => self.type_arrow(node),
+            SyntaxKind::BLOCK_EXPR => self.block(node),
+            SyntaxKind::MATCH_EXPR => self.match_expr(node),
+            SyntaxKind::MATCH_ARM => self.match_arm(node),
+            SyntaxKind::PAREN_EXPR => self.paren(node),
+            SyntaxKind::BINARY_EXPR => self.binary(node),
+            SyntaxKind::PARAM
+            | SyntaxKind::TYPE_PAREN
+            | SyntaxKind::TYPE_IDENT
+            | SyntaxKind::TYPE_VAR
+            | SyntaxKind::IDENT_EXPR
+            | SyntaxKind::LITERAL_EXPR
+            | SyntaxKind::EXPR_STMT => self.glued(node),
+            _ => self.spaced(node),
+        }
+    }
+
+    fn element(&mut self, element: &SyntaxElement) -> Doc {
+        match element {
+            SyntaxElement::Node(node) => self.node(node),
+            SyntaxElement::Token(token) => self.token(token),
+        }
+    }
+
+    /// Elements without whitespace between them, like `(x: int)` or `expr;`.
+    fn glued(&mut self, node: &SyntaxNode) -> Doc {
+        Doc::Concat(
+            significant_children(node)
+                .map(|element| self.element(&element))
+                .collect(),
+        )
+    }
+
+    /// Elements separated by spaces, like `open list;`.
+    fn spaced(&mut self, node: &SyntaxNode) -> Doc {
+        let mut docs = Vec::new();
+        let mut previous = None;
+        for element in significant_children(node) {
+            let glued = matches!(element.kind(), SyntaxKind::SEMICOLON | SyntaxKind::R_PAREN)
+                || previous == Some(SyntaxKind::L_PAREN);
+            if previous.is_some() && !glued {
+                docs.push(Doc::text(" "));
+            }
+            previous = Some(element.kind());
+            docs.push(self.element(&element));
+        }
+        Doc::Concat(docs)
+    }
+
+    /// Definitions, `let` statements and lambdas: a head with the name, parameters and
+    /// annotation, and the bound expression after `=` or `->`.
+    fn binder(&mut self, node: &SyntaxNode) -> Doc {
+        let mut docs = Vec::new();
+        let mut bound = false;
+        for element in significant_children(node) {
+            match &element {
+                SyntaxElement::Token(token) => match token.kind() {
+                    SyntaxKind::DEF_KW
+                    | SyntaxKind::LET_KW
+                    | SyntaxKind::BACKSLASH
+                    | SyntaxKind::LAMBDA
+                    | SyntaxKind::SEMICOLON => docs.push(self.token(token)),
+                    SyntaxKind::EQUAL | SyntaxKind::ARROW => {
+                        docs.push(Doc::text(" "));
+                        docs.push(self.token(token));
+                        bound = true;
+                    }
+                    _ => {
+                        docs.push(Doc::text(" "));
+                        docs.push(self.token(token));
+                    }
+                },
+                SyntaxElement::Node(child) => match child.kind() {
+                    SyntaxKind::PARAMS => {
+                        let is_lambda = node.kind() == SyntaxKind::LAMBDA_EXPR;
+                        for (index, param) in child.children().enumerate() {
+                            if index > 0 || !is_lambda {
+                                docs.push(Doc::text(" "));
+                            }
+                            docs.push(self.node(&param));
+                        }
+                    }
+                    SyntaxKind::DEF_BODY => docs.push(self.def_body(child)),
+                    _ if bound => docs.push(self.bound_expr(child)),
+                    _ => {
+                        docs.push(Doc::text(" "));
+                        docs.push(self.node(child));
+                    }
+                },
+            }
+        }
+        Doc::Concat(docs)
+    }
+
+    fn def_body(&mut self, body: &SyntaxNode) -> Doc {
+        let mut docs = Vec::new();
+        for element in significant_children(body) {
+            match &element {
+                SyntaxElement::Node(expr) if expr.kind() == SyntaxKind::BLOCK_EXPR => {
+                    docs.push(Doc::text(" "));
+                    docs.push(self.node(expr));
+                }
+                SyntaxElement::Node(expr) => docs.push(self.bound_expr(expr)),
+                SyntaxElement::Token(token) if token.kind() == SyntaxKind::EQUAL => {
+                    docs.push(Doc::text(" "));
+                    docs.push(self.token(token));
+                }
+                SyntaxElement::Token(token) => docs.push(self.token(token)),
+            }
+        }
+        Doc::Concat(docs)
+    }
+
+    /// Expression after `=` or `->`, moved to the next line if it does not fit,
+    /// unless it opens with a bracket or keyword and can be broken inside.
+    fn bound_expr(&mut self, expr: &SyntaxNode) -> Doc {
+        let doc = self.node(expr);
+        match expr.kind() {
+            SyntaxKind::BLOCK_EXPR
+            | SyntaxKind::PAREN_EXPR
+            | SyntaxKind::LAMBDA_EXPR
+            | SyntaxKind::IF_EXPR
+            | SyntaxKind::MATCH_EXPR => Doc::Concat(vec![Doc::text(" "), doc]),
+            _ => Doc::group(Doc::indent(Doc::Concat(vec![Doc::Line, doc]))),
+        }
+    }
+
+    fn type_definition(&mut self, node: &SyntaxNode) -> Doc {
+        let mut docs = Vec::new();
+        for element in significant_children(node) {
+            match &element {
+                SyntaxElement::Node(params) if params.kind() == SyntaxKind::TYPE_PARAMS => {
+                    if params.children().next().is_some() {
+                        docs.push(Doc::text(" "));
+                        docs.push(self.spaced(params));
+                    }
+                }
+                SyntaxElement::Node(variants) if variants.kind() == SyntaxKind::VARIANTS => {
+                    let mut lines = Vec::new();
+                    for variant in variants.children() {
+                        lines.push(Doc::Line);
+                        lines.push(self.spaced(&variant));
+                    }
+                    docs.push(Doc::group(Doc::indent(Doc::Concat(lines))));
+                }
+                SyntaxElement::Node(typ) => {
+                    let typ = self.node(typ);
+                    docs.push(Doc::group(Doc::indent(Doc::Concat(vec![Doc::Line, typ]))));
+                }
+                SyntaxElement::Token(token) => {
+                    if !matches!(token.kind(), SyntaxKind::TYPE_KW | SyntaxKind::SEMICOLON) {
+                        docs.push(Doc::text(" "));
+                    }
+                    docs.push(self.token(token));
+                }
+            }
+        }
+        Doc::Concat(docs)
+    }
+
+    /// `a -> b -> c`, broken after the arrows.
+    fn type_arrow(&mut self, node: &SyntaxNode) -> Doc {
+        let mut head = Doc::nil();
+        let mut tail = Vec::new();
+        let mut arrow = Some(node.clone());
+        while let Some(node) = arrow.take() {
+            let mut after_arrow = false;
+            for element in significant_children(&node) {
+                match &element {
+                    SyntaxElement::Token(token) => {
+                        tail.push(Doc::text(" "));
+                        tail.push(self.token(token));
+                        tail.push(Doc::Line);
+                        after_arrow = true;
+                    }
+                    SyntaxElement::Node(to)
+                        if after_arrow && to.kind() == SyntaxKind::TYPE_ARROW =>
+                    {
+                        arrow = Some(to.clone());
+                    }
+                    SyntaxElement::Node(typ) if tail.is_empty() => head = self.node(typ),
+                    SyntaxElement::Node(typ) => tail.push(self.node(typ)),
+                }
+            }
+        }
+        Doc::group(Doc::Concat(vec![head, Doc::indent(Doc::Concat(tail))]))
+    }
+
+    /// Statements on separate lines, indented.
+    /// Applications with such blocks in their arguments are broken too.
+    fn block(&mut self, node: &SyntaxNode) -> Doc {
+        let mut docs = Vec::new();
+        let mut inner = Vec::new();
+        let mut statements = 0;
+        for element in significant_children(node) {
+            match &element {
+                SyntaxElement::Token(token) if token.kind() == SyntaxKind::L_BRACE => {
+                    docs.push(self.token(token));
+                }
+                SyntaxElement::Token(token) if token.kind() == SyntaxKind::R_BRACE => {
+                    let inner = std::mem::take(&mut inner);
+                    docs.push(self.closing_brace(token, inner, statements == 0));
+                }
+                SyntaxElement::Token(token) => inner.push(self.token(token)),
+                SyntaxElement::Node(statement) => {
+                    inner.push(Doc::HardLine);
+                    if let Some(first) = first_token(statement) {
+                        let blanks = Blanks::Kept {
+                            first: statements == 0,
+                        };
+                        inner.push(self.leading_comments(&first, blanks));
+                    }
+                    inner.push(self.node(statement));
+                    statements += 1;
+                }
+            }
+        }
+        Doc::Concat(docs)
+    }
+
+    /// The indented lines before `}`, with the comments preceding it.
+    /// Braces with nothing between them are kept together.
+    fn closing_brace(&mut self, brace: &SyntaxToken, mut inner: Vec<Doc>, first: bool) -> Doc {
+        let (comments, _) = self.comments(&trivia_before(brace), true, Blanks::Kept { first });
+        inner.push(comments);
+        if inner.iter().all(is_empty) {
+            return self.token(brace);
+        }
+        Doc::Concat(vec![
+            Doc::BreakParent,
+            Doc::indent(Doc::Concat(inner)),
+            Doc::FreshLine,
+            self.token(brace),
+        ])
+    }
+
+    /// Arms on separate lines, indented, like the statements of blocks.
+    fn match_expr(&mut self, node: &SyntaxNode) -> Doc {
+        let mut docs = Vec::new();
+        let mut arms = Vec::new();
+        let mut count = 0;
+        for element in significant_children(node) {
+            match &element {
+                SyntaxElement::Token(token) if token.kind() == SyntaxKind::R_BRACE => {
+                    let arms = std::mem::take(&mut arms);
+                    docs.push(self.closing_brace(token, arms, count == 0));
+                }
+                SyntaxElement::Token(token) if token.kind() == SyntaxKind::MATCH_KW => {
+                    docs.push(self.token(token));
+                }
+                SyntaxElement::Node(arm) if arm.kind() == SyntaxKind::MATCH_ARM => {
+                    arms.push(Doc::HardLine);
+                    if let Some(first) = first_token(arm) {
+                        let blanks = Blanks::Kept { first: count == 0 };
+                        arms.push(self.leading_comments(&first, blanks));
+                    }
+                    arms.push(self.node(arm));
+                    count += 1;
+                }
+                _ => {
+                    docs.push(Doc::text(" "));
+                    docs.push(self.element(&element));
+                }
+            }
+        }
+        Doc::Concat(docs)
+    }
+
+    /// `pat => expr,`, with the comma added to the last arm.
+    fn match_arm(&mut self, node: &SyntaxNode) -> Doc {
+        let mut docs = Vec::new();
+        let mut bound = false;
+        for element in significant_children(node) {
+            match &element {
+                SyntaxElement::Token(token) if token.kind() == SyntaxKind::FAT_ARROW => {
+                    docs.push(Doc::text(" "));
+                    docs.push(self.token(token));
+                    bound = true;
+                }
+                SyntaxElement::Node(expr) if bound => docs.push(self.bound_expr(expr)),
+                _ => docs.push(self.element(&element)),
+            }
+        }
+        if last_token(node).map(|token| token.kind()) != Some(SyntaxKind::COMMA) {
+            docs.push(Doc::text(","));
+        }
+        Doc::Concat(docs)
+    }
+
+    /// Parenthesized expressions, with the arguments of applications
+    /// on separate lines if they do not fit.
+    fn paren(&mut self, node: &SyntaxNode) -> Doc {
+        let mut docs = Vec::new();
+        for element in significant_children(node) {
+            match &element {
+                SyntaxElement::Node(app) if app.kind() == SyntaxKind::APP_EXPR => {
+                    let mut spine: Vec<_> = std::iter::successors(Some(app.clone()), |node| {
+                        node.first_child()
+                            .filter(|func| func.kind() == SyntaxKind::APP_EXPR)
+                    })
+                    .collect();
+                    spine.reverse();
+                    let Some(func) = spine.first().and_then(SyntaxNode::first_child) else {
+                        return self.spaced(node);
+                    };
+                    docs.push(self.node(&func));
+                    let mut args = Vec::new();
+                    for app in &spine {
+                        if let Some(arg) = app.children().nth(1) {
+                            args.push(Doc::Line);
+                            args.push(self.node(&arg));
+                        }
+                    }
+                    docs.push(Doc::indent(Doc::Concat(args)));
+                }
+                _ => docs.push(self.element(&element)),
+            }
+        }
+        Doc::group(Doc::Concat(docs))
+    }
+
+    /// `a + b`, broken before the operator.
+    fn binary(&mut self, node: &SyntaxNode) -> Doc {
+        let mut elements = significant_children(node);
+        let Some(lhs) = elements.next() else {
+            return Doc::nil();
+        };
+        let lhs = self.element(&lhs);
+        let mut rhs = vec![Doc::Line];
+        for (index, element) in elements.enumerate() {
+            if index > 0 {
+                rhs.push(Doc::text(" "));
+            }
+            rhs.push(self.element(&element));
+        }
+        Doc::group(Doc::Concat(vec![lhs, Doc::indent(Doc::Concat(rhs))]))
+    }
+
+    fn token(&mut self, token: &SyntaxToken) -> Doc {
+        Doc::Concat(vec![
+            self.leading_comments(token, Blanks::Removed),
+            Doc::text(token.text()),
+            self.trailing_comment(token),
+        ])
+    }
+
+    /// Comments on their own lines before the token.
+    fn leading_comments(&mut self, token: &SyntaxToken, blanks: Blanks) -> Doc {
+        let trivia = trivia_before(token);
+        let has_previous = previous_token(token).is_some();
+        let (comments, newlines) = self.comments(&trivia, has_previous, blanks);
+        let blank_line = matches!(blanks, Blanks::Kept { first } if !first || !is_empty(&comments));
+        if blank_line && newlines > 1 {
+            Doc::Concat(vec![comments, Doc::HardLine])
+        } else {
+            comments
+        }
+    }
+
+    /// Comments in the trivia which are not trailing the previous token,
+    /// and the number of line breaks after the last of them.
+    fn comments(
+        &mut self,
+        trivia: &[SyntaxToken],
+        has_previous: bool,
+        blanks: Blanks,
+    ) -> (Doc, usize) {
+        let mut docs = Vec::new();
+        let mut newlines = 0;
+        for (index, token) in trivia.iter().enumerate() {
+            match token.kind() {
+                SyntaxKind::COMMENT => {
+                    let is_trailing = index == trailing_comment_index(trivia, has_previous);
+                    let is_new = self.printed_comments.insert(token.text_range().start());
+                    if !is_trailing && is_new {
+                        let blank_line = match blanks {
+                            Blanks::Kept { first } => newlines > 1 && (!first || !docs.is_empty()),
+                            Blanks::Removed => false,
+                        };
+                        docs.push(Doc::BreakParent);
+                        docs.push(Doc::FreshLine);
+                        if blank_line {
+                            docs.push(Doc::HardLine);
+                        }
+                        docs.push(Doc::text(token.text().trim_end()));
+                        docs.push(Doc::HardLine);
+                    }
+                    newlines = usize::from(token.text().ends_with('\n'));
+                }
+                _ => newlines += token.text().matches('\n').count(),
+            }
+        }
+        (Doc::Concat(docs), newlines)
+    }
+
+    /// Comment on the same line as the token, after it.
+    fn trailing_comment(&mut self, token: &SyntaxToken) -> Doc {
+        match trailing_comment_token(token) {
+            Some(comment) if self.printed_comments.insert(comment.text_range().start()) => {
+                Doc::Concat(vec![
+                    Doc::LineSuffix(comment.text().trim_end().to_string()),
+                    Doc::BreakParent,
+                ])
+            }
+            _ => Doc::nil(),
+        }
+    }
+}
+
+/// Whether blank lines before comments and the token they precede are kept.
+#[derive(Clone, Copy)]
+enum Blanks {
+    /// Between items and statements, except before the `first` of them.
+    Kept {
+        first: bool,
+    },
+    Removed,
+}
+
+fn is_empty(doc: &Doc) -> bool {
+    match doc {
+        Doc::Concat(docs) => docs.iter().all(is_empty),
+        _ => false,
+    }
+}
+
+fn significant_children(node: &SyntaxNode) -> impl Iterator<Item = SyntaxElement> {
+    node.children_with_tokens()
+        .filter(|element| !element.kind().is_trivial())
+}
+
+pub(crate) fn first_token(node: &SyntaxNode) -> Option<SyntaxToken> {
+    node.descendants_with_tokens()
+        .filter_map(SyntaxElement::into_token)
+        .find(|token| !token.kind().is_trivial())
+}
+
+pub(crate) fn last_token(node: &SyntaxNode) -> Option<SyntaxToken> {
+    std::iter::successors(last_descendant_token(node), token_before)
+        .take_while(|token| node.text_range().contains_range(token.text_range()))
+        .find(|token| !token.kind().is_trivial())
+}
+
+/// Token before this one, not stopped by empty nodes like [`SyntaxToken::prev_token`] is.
+fn token_before(token: &SyntaxToken) -> Option<SyntaxToken> {
+    let mut element = SyntaxElement::Token(token.clone());
+    loop {
+        element = match element.prev_sibling_or_token() {
+            Some(SyntaxElement::Token(token)) => return Some(token),
+            Some(SyntaxElement::Node(node)) => match last_descendant_token(&node) {
+                Some(token) => return Some(token),
+                None => SyntaxElement::Node(node),
+            },
+            None => SyntaxElement::Node(element.parent()?),
+        };
+    }
+}
+
+/// Token after this one, not stopped by empty nodes like [`SyntaxToken::next_token`] is.
+fn token_after(token: &SyntaxToken) -> Option<SyntaxToken> {
+    let mut element = SyntaxElement::Token(token.clone());
+    loop {
+        element = match element.next_sibling_or_token() {
+            Some(SyntaxElement::Token(token)) => return Some(token),
+            Some(SyntaxElement::Node(node)) => match first_descendant_token(&node) {
+                Some(token) => return Some(token),
+                None => SyntaxElement::Node(node),
+            },
+            None => SyntaxElement::Node(element.parent()?),
+        };
+    }
+}
+
+fn first_descendant_token(node: &SyntaxNode) -> Option<SyntaxToken> {
+    std::iter::successors(
+        node.first_child_or_token(),
+        SyntaxElement::next_sibling_or_token,
+    )
+    .find_map(|element| match element {
+        SyntaxElement::Token(token) => Some(token),
+        SyntaxElement::Node(node) => first_descendant_token(&node),
+    })
+}
+
+fn last_descendant_token(node: &SyntaxNode) -> Option<SyntaxToken> {
+    std::iter::successors(
+        node.last_child_or_token(),
+        SyntaxElement::prev_sibling_or_token,
+    )
+    .find_map(|element| match element {
+        SyntaxElement::Token(token) => Some(token),
+        SyntaxElement::Node(node) => last_descendant_token(&node),
+    })
+}
+
+fn previous_token(token: &SyntaxToken) -> Option<SyntaxToken> {
+    std::iter::successors(token_before(token), token_before)
+        .find(|token| !token.kind().is_trivial())
+}
+
+/// Whitespace and comments between the previous token and this one.
+fn trivia_before(token: &SyntaxToken) -> Vec<SyntaxToken> {
+    let mut trivia: Vec<_> = std::iter::successors(token_before(token), token_before)
+        .take_while(|token| token.kind().is_trivial())
+        .collect();
+    trivia.reverse();
+    trivia
+}
+
+/// Index of the comment trailing the previous token, if any.
+fn trailing_comment_index(trivia: &[SyntaxToken], has_previous: bool) -> usize {
+    if !has_previous {
+        return usize::MAX;
+    }
+    trivia
+        .iter()
+        .position(|token| token.kind() != SyntaxKind::WHITESPACE || token.text().contains('\n'))
+        .filter(|&index| trivia[index].kind() == SyntaxKind::COMMENT)
+        .unwrap_or(usize::MAX)
+}
+
+pub(crate) fn trailing_comment_token(token: &SyntaxToken) -> Option<SyntaxToken> {
+    std::iter::successors(token_after(token), token_after)
+        .find(|token| token.kind() != SyntaxKind::WHITESPACE || token.text().contains('\n'))
+        .filter(|token| token.kind() == SyntaxKind::COMMENT)
+}
+
+/// Range of the item with its comments: the ones on their own lines before it
+/// and the one at the end of its last line.
+pub(crate) fn item_range(item: &SyntaxNode) -> Option<TextRange> {
+    let first = first_token(item)?;
+    let last = last_token(item)?;
+    let trivia = trivia_before(&first);
+    let trailing = trailing_comment_index(&trivia, previous_token(&first).is_some());
+    let start = trivia
+        .iter()
+        .enumerate()
+        .find(|(index, token)| token.kind() == SyntaxKind::COMMENT && *index != trailing)
+        .map_or(first.text_range().start(), |(_, comment)| {
+            comment.text_range().start()
+        });
+    let end = trailing_comment_token(&last).map_or(last.text_range().end(), |comment| {
+        let len = TextSize::of(comment.text().trim_end());
+        comment.text_range().start() + len
+    });
+    Some(TextRange::new(start, end))
+}
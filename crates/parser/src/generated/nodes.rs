@@ -94,6 +94,14 @@ impl TypeDefinition {
     pub fn type_expr(&self) -> Option<TypeExpr> {
         support::child(&self.syntax)
     }
+    #[must_use]
+    pub fn type_params(&self) -> Option<TypeParams> {
+        support::child(&self.syntax)
+    }
+    #[must_use]
+    pub fn variants(&self) -> Option<Variants> {
+        support::child(&self.syntax)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -145,6 +153,58 @@ impl DefBody {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TypeParams {
+    pub(crate) syntax: SyntaxNode,
+}
+impl TypeParams {
+    #[must_use]
+    pub fn type_params(&self) -> AstChildren<TypeParam> {
+        support::children(&self.syntax)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Variants {
+    pub(crate) syntax: SyntaxNode,
+}
+impl Variants {
+    #[must_use]
+    pub fn variants(&self) -> AstChildren<Variant> {
+        support::children(&self.syntax)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TypeParam {
+    pub(crate) syntax: SyntaxNode,
+}
+impl TypeParam {
+    #[must_use]
+    pub fn ident_lit(&self) -> Option<SyntaxToken> {
+        support::token(&self.syntax, IDENT)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Variant {
+    pub(crate) syntax: SyntaxNode,
+}
+impl Variant {
+    #[must_use]
+    pub fn pipe_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.syntax, PIPE)
+    }
+    #[must_use]
+    pub fn ident_lit(&self) -> Option<SyntaxToken> {
+        support::token(&self.syntax, IDENT)
+    }
+    #[must_use]
+    pub fn fields(&self) -> AstChildren<TypeExpr> {
+        support::children(&self.syntax)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct BlockExpr {
     pub(crate) syntax: SyntaxNode,
@@ -179,6 +239,32 @@ impl TypeIdent {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TypeVar {
+    pub(crate) syntax: SyntaxNode,
+}
+impl TypeVar {
+    #[must_use]
+    pub fn quote_ident_lit(&self) -> Option<SyntaxToken> {
+        support::token(&self.syntax, QUOTE_IDENT)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TypeApp {
+    pub(crate) syntax: SyntaxNode,
+}
+impl TypeApp {
+    #[must_use]
+    pub fn func(&self) -> Option<TypeExpr> {
+        crate::handwritten_ast::type_app_func(&self.syntax)
+    }
+    #[must_use]
+    pub fn arg(&self) -> Option<TypeExpr> {
+        crate::handwritten_ast::type_app_arg(&self.syntax)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct TypeArrow {
     pub(crate) syntax: SyntaxNode,
@@ -362,6 +448,60 @@ impl BinaryExpr {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IfExpr {
+    pub(crate) syntax: SyntaxNode,
+}
+impl IfExpr {
+    #[must_use]
+    pub fn condition(&self) -> Option<Expr> {
+        crate::handwritten_ast::if_expr_condition(&self.syntax)
+    }
+    #[must_use]
+    pub fn then_branch(&self) -> Option<Expr> {
+        crate::handwritten_ast::if_expr_then_branch(&self.syntax)
+    }
+    #[must_use]
+    pub fn else_branch(&self) -> Option<Expr> {
+        crate::handwritten_ast::if_expr_else_branch(&self.syntax)
+    }
+    #[must_use]
+    pub fn if_kw_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.syntax, IF_KW)
+    }
+    #[must_use]
+    pub fn else_kw_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.syntax, ELSE_KW)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MatchExpr {
+    pub(crate) syntax: SyntaxNode,
+}
+impl MatchExpr {
+    #[must_use]
+    pub fn scrutinee(&self) -> Option<Expr> {
+        support::child(&self.syntax)
+    }
+    #[must_use]
+    pub fn arms(&self) -> AstChildren<MatchArm> {
+        support::children(&self.syntax)
+    }
+    #[must_use]
+    pub fn match_kw_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.syntax, MATCH_KW)
+    }
+    #[must_use]
+    pub fn l_brace_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.syntax, L_BRACE)
+    }
+    #[must_use]
+    pub fn r_brace_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.syntax, R_BRACE)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct AppExpr {
     pub(crate) syntax: SyntaxNode,
@@ -381,6 +521,85 @@ impl AppExpr {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MatchArm {
+    pub(crate) syntax: SyntaxNode,
+}
+impl MatchArm {
+    #[must_use]
+    pub fn expr(&self) -> Option<Expr> {
+        support::child(&self.syntax)
+    }
+    #[must_use]
+    pub fn pat(&self) -> Option<Pat> {
+        support::child(&self.syntax)
+    }
+    #[must_use]
+    pub fn fat_arrow_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.syntax, FAT_ARROW)
+    }
+    #[must_use]
+    pub fn comma_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.syntax, COMMA)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WildcardPat {
+    pub(crate) syntax: SyntaxNode,
+}
+impl WildcardPat {
+    #[must_use]
+    pub fn underscore_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.syntax, UNDERSCORE)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LiteralPat {
+    pub(crate) syntax: SyntaxNode,
+}
+impl LiteralPat {
+    #[must_use]
+    pub fn literal(&self) -> Option<Literal> {
+        support::token_child(&self.syntax)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IdentPat {
+    pub(crate) syntax: SyntaxNode,
+}
+impl IdentPat {
+    #[must_use]
+    pub fn args(&self) -> AstChildren<Pat> {
+        support::children(&self.syntax)
+    }
+    #[must_use]
+    pub fn ident_lit(&self) -> Option<SyntaxToken> {
+        support::token(&self.syntax, IDENT)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ParenPat {
+    pub(crate) syntax: SyntaxNode,
+}
+impl ParenPat {
+    #[must_use]
+    pub fn pat(&self) -> Option<Pat> {
+        support::child(&self.syntax)
+    }
+    #[must_use]
+    pub fn l_paren_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.syntax, L_PAREN)
+    }
+    #[must_use]
+    pub fn r_paren_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.syntax, R_PAREN)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Param {
     pub(crate) syntax: SyntaxNode,
@@ -414,6 +633,8 @@ pub enum ModuleItem {
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum TypeExpr {
     TypeIdent(TypeIdent),
+    TypeVar(TypeVar),
+    TypeApp(TypeApp),
     TypeArrow(TypeArrow),
     TypeParen(TypeParen),
 }
@@ -426,6 +647,8 @@ pub enum Expr {
     ParenExpr(ParenExpr),
     BinaryExpr(BinaryExpr),
     BlockExpr(BlockExpr),
+    IfExpr(IfExpr),
+    MatchExpr(MatchExpr),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -434,6 +657,14 @@ pub enum Stmt {
     LetStmt(LetStmt),
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Pat {
+    WildcardPat(WildcardPat),
+    LiteralPat(LiteralPat),
+    IdentPat(IdentPat),
+    ParenPat(ParenPat),
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Literal {
     syntax: SyntaxToken,
@@ -443,7 +674,8 @@ pub struct Literal {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum LiteralKind {
     Int,
-    DummyKw,
+    TrueKw,
+    FalseKw,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -564,6 +796,66 @@ impl AstNode for DefBody {
         &self.syntax
     }
 }
+impl AstNode for TypeParams {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == TYPE_PARAMS
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl AstNode for Variants {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == VARIANTS
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl AstNode for TypeParam {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == TYPE_PARAM
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl AstNode for Variant {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == VARIANT
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
 impl AstNode for BlockExpr {
     fn can_cast(kind: SyntaxKind) -> bool {
         kind == BLOCK_EXPR
@@ -594,6 +886,36 @@ impl AstNode for TypeIdent {
         &self.syntax
     }
 }
+impl AstNode for TypeVar {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == TYPE_VAR
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl AstNode for TypeApp {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == TYPE_APP
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
 impl AstNode for TypeArrow {
     fn can_cast(kind: SyntaxKind) -> bool {
         kind == TYPE_ARROW
@@ -729,6 +1051,36 @@ impl AstNode for BinaryExpr {
         &self.syntax
     }
 }
+impl AstNode for IfExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == IF_EXPR
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl AstNode for MatchExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == MATCH_EXPR
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
 impl AstNode for AppExpr {
     fn can_cast(kind: SyntaxKind) -> bool {
         kind == APP_EXPR
@@ -744,6 +1096,81 @@ impl AstNode for AppExpr {
         &self.syntax
     }
 }
+impl AstNode for MatchArm {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == MATCH_ARM
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl AstNode for WildcardPat {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == WILDCARD_PAT
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl AstNode for LiteralPat {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == LITERAL_PAT
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl AstNode for IdentPat {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == IDENT_PAT
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
+impl AstNode for ParenPat {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == PAREN_PAT
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(syntax.kind()) {
+            Some(Self { syntax })
+        } else {
+            None
+        }
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        &self.syntax
+    }
+}
 impl AstNode for Param {
     fn can_cast(kind: SyntaxKind) -> bool {
         kind == PARAM
@@ -803,6 +1230,16 @@ impl From<TypeIdent> for TypeExpr {
         TypeExpr::TypeIdent(node)
     }
 }
+impl From<TypeVar> for TypeExpr {
+    fn from(node: TypeVar) -> TypeExpr {
+        TypeExpr::TypeVar(node)
+    }
+}
+impl From<TypeApp> for TypeExpr {
+    fn from(node: TypeApp) -> TypeExpr {
+        TypeExpr::TypeApp(node)
+    }
+}
 impl From<TypeArrow> for TypeExpr {
     fn from(node: TypeArrow) -> TypeExpr {
         TypeExpr::TypeArrow(node)
@@ -816,13 +1253,15 @@ impl From<TypeParen> for TypeExpr {
 impl AstNode for TypeExpr {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            TYPE_IDENT | TYPE_ARROW | TYPE_PAREN => true,
+            TYPE_IDENT | TYPE_VAR | TYPE_APP | TYPE_ARROW | TYPE_PAREN => true,
             _ => false,
         }
     }
     fn cast(syntax: SyntaxNode) -> Option<Self> {
         let res = match syntax.kind() {
             TYPE_IDENT => TypeExpr::TypeIdent(TypeIdent { syntax }),
+            TYPE_VAR => TypeExpr::TypeVar(TypeVar { syntax }),
+            TYPE_APP => TypeExpr::TypeApp(TypeApp { syntax }),
             TYPE_ARROW => TypeExpr::TypeArrow(TypeArrow { syntax }),
             TYPE_PAREN => TypeExpr::TypeParen(TypeParen { syntax }),
             _ => return None,
@@ -832,6 +1271,8 @@ impl AstNode for TypeExpr {
     fn syntax(&self) -> &SyntaxNode {
         match self {
             TypeExpr::TypeIdent(it) => &it.syntax,
+            TypeExpr::TypeVar(it) => &it.syntax,
+            TypeExpr::TypeApp(it) => &it.syntax,
             TypeExpr::TypeArrow(it) => &it.syntax,
             TypeExpr::TypeParen(it) => &it.syntax,
         }
@@ -867,10 +1308,21 @@ impl From<BlockExpr> for Expr {
         Expr::BlockExpr(node)
     }
 }
+impl From<IfExpr> for Expr {
+    fn from(node: IfExpr) -> Expr {
+        Expr::IfExpr(node)
+    }
+}
+impl From<MatchExpr> for Expr {
+    fn from(node: MatchExpr) -> Expr {
+        Expr::MatchExpr(node)
+    }
+}
 impl AstNode for Expr {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            IDENT_EXPR | LITERAL_EXPR | LAMBDA_EXPR | PAREN_EXPR | BINARY_EXPR | BLOCK_EXPR => true,
+            IDENT_EXPR | LITERAL_EXPR | LAMBDA_EXPR | PAREN_EXPR | BINARY_EXPR | BLOCK_EXPR
+            | IF_EXPR | MATCH_EXPR => true,
             _ => false,
         }
     }
@@ -882,6 +1334,8 @@ impl AstNode for Expr {
             PAREN_EXPR => Expr::ParenExpr(ParenExpr { syntax }),
             BINARY_EXPR => Expr::BinaryExpr(BinaryExpr { syntax }),
             BLOCK_EXPR => Expr::BlockExpr(BlockExpr { syntax }),
+            IF_EXPR => Expr::IfExpr(IfExpr { syntax }),
+            MATCH_EXPR => Expr::MatchExpr(MatchExpr { syntax }),
             _ => return None,
         };
         Some(res)
@@ -894,6 +1348,8 @@ impl AstNode for Expr {
             Expr::ParenExpr(it) => &it.syntax,
             Expr::BinaryExpr(it) => &it.syntax,
             Expr::BlockExpr(it) => &it.syntax,
+            Expr::IfExpr(it) => &it.syntax,
+            Expr::MatchExpr(it) => &it.syntax,
         }
     }
 }
@@ -929,6 +1385,52 @@ impl AstNode for Stmt {
         }
     }
 }
+impl From<WildcardPat> for Pat {
+    fn from(node: WildcardPat) -> Pat {
+        Pat::WildcardPat(node)
+    }
+}
+impl From<LiteralPat> for Pat {
+    fn from(node: LiteralPat) -> Pat {
+        Pat::LiteralPat(node)
+    }
+}
+impl From<IdentPat> for Pat {
+    fn from(node: IdentPat) -> Pat {
+        Pat::IdentPat(node)
+    }
+}
+impl From<ParenPat> for Pat {
+    fn from(node: ParenPat) -> Pat {
+        Pat::ParenPat(node)
+    }
+}
+impl AstNode for Pat {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        match kind {
+            WILDCARD_PAT | LITERAL_PAT | IDENT_PAT | PAREN_PAT => true,
+            _ => false,
+        }
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        let res = match syntax.kind() {
+            WILDCARD_PAT => Pat::WildcardPat(WildcardPat { syntax }),
+            LITERAL_PAT => Pat::LiteralPat(LiteralPat { syntax }),
+            IDENT_PAT => Pat::IdentPat(IdentPat { syntax }),
+            PAREN_PAT => Pat::ParenPat(ParenPat { syntax }),
+            _ => return None,
+        };
+        Some(res)
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            Pat::WildcardPat(it) => &it.syntax,
+            Pat::LiteralPat(it) => &it.syntax,
+            Pat::IdentPat(it) => &it.syntax,
+            Pat::ParenPat(it) => &it.syntax,
+        }
+    }
+}
 impl AstToken for Literal {
     fn can_cast(kind: SyntaxKind) -> bool {
         LiteralKind::can_cast(kind)
@@ -944,7 +1446,7 @@ impl AstToken for Literal {
 impl LiteralKind {
     fn can_cast(kind: SyntaxKind) -> bool {
         match kind {
-            INT | DUMMY_KW => true,
+            INT | TRUE_KW | FALSE_KW => true,
             _ => false,
         }
     }
@@ -952,7 +1454,8 @@ impl LiteralKind {
     pub fn cast(kind: SyntaxKind) -> Option<Self> {
         let res = match kind {
             INT => Self::Int,
-            DUMMY_KW => Self::DummyKw,
+            TRUE_KW => Self::TrueKw,
+            FALSE_KW => Self::FalseKw,
             _ => return None,
         };
         Some(res)
@@ -1031,6 +1534,11 @@ impl std::fmt::Display for Stmt {
         std::fmt::Display::fmt(self.syntax(), f)
     }
 }
+impl std::fmt::Display for Pat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self.syntax(), f)
+    }
+}
 impl std::fmt::Display for Module {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         std::fmt::Display::fmt(self.syntax(), f)
@@ -1066,6 +1574,26 @@ impl std::fmt::Display for DefBody {
         std::fmt::Display::fmt(self.syntax(), f)
     }
 }
+impl std::fmt::Display for TypeParams {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self.syntax(), f)
+    }
+}
+impl std::fmt::Display for Variants {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self.syntax(), f)
+    }
+}
+impl std::fmt::Display for TypeParam {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self.syntax(), f)
+    }
+}
+impl std::fmt::Display for Variant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self.syntax(), f)
+    }
+}
 impl std::fmt::Display for BlockExpr {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         std::fmt::Display::fmt(self.syntax(), f)
@@ -1076,6 +1604,16 @@ impl std::fmt::Display for TypeIdent {
         std::fmt::Display::fmt(self.syntax(), f)
     }
 }
+impl std::fmt::Display for TypeVar {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self.syntax(), f)
+    }
+}
+impl std::fmt::Display for TypeApp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self.syntax(), f)
+    }
+}
 impl std::fmt::Display for TypeArrow {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         std::fmt::Display::fmt(self.syntax(), f)
@@ -1121,11 +1659,46 @@ impl std::fmt::Display for BinaryExpr {
         std::fmt::Display::fmt(self.syntax(), f)
     }
 }
+impl std::fmt::Display for IfExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self.syntax(), f)
+    }
+}
+impl std::fmt::Display for MatchExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self.syntax(), f)
+    }
+}
 impl std::fmt::Display for AppExpr {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         std::fmt::Display::fmt(self.syntax(), f)
     }
 }
+impl std::fmt::Display for MatchArm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self.syntax(), f)
+    }
+}
+impl std::fmt::Display for WildcardPat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self.syntax(), f)
+    }
+}
+impl std::fmt::Display for LiteralPat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self.syntax(), f)
+    }
+}
+impl std::fmt::Display for IdentPat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self.syntax(), f)
+    }
+}
+impl std::fmt::Display for ParenPat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self.syntax(), f)
+    }
+}
 impl std::fmt::Display for Param {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         std::fmt::Display::fmt(self.syntax(), f)
@@ -48,12 +48,20 @@ pub enum SyntaxKind {
     BACKSLASH,
     #[token("λ")]
     LAMBDA,
+    #[token("|")]
+    PIPE,
+    #[token("=>")]
+    FAT_ARROW,
+    #[token("_")]
+    UNDERSCORE,
     #[regex("(?:0|[1-9][0-9]*)")]
     INT,
     #[regex("\"(?s:[^\"\\\\]|\\\\.)*\"")]
     STRING,
     #[regex("[_a-zA-Z][_a-zA-Z0-9]*")]
     IDENT,
+    #[regex("'[_a-zA-Z][_a-zA-Z0-9]*")]
+    QUOTE_IDENT,
     #[regex("[ \\t\\n\\r]+")]
     WHITESPACE,
     #[regex("#[^\\r\\n]*(\\r\\n|\\n)?")]
@@ -66,9 +74,17 @@ pub enum SyntaxKind {
     TYPE_KW,
     #[token("let")]
     LET_KW,
-    #[token("dummy")]
-    DUMMY_KW,
-    #[doc = r" Also acts as __LAST_TOKEN"]
+    #[token("if")]
+    IF_KW,
+    #[token("else")]
+    ELSE_KW,
+    #[token("match")]
+    MATCH_KW,
+    #[token("true")]
+    TRUE_KW,
+    #[token("false")]
+    FALSE_KW,
+    #[doc = r" Also acts as `__LAST_TOKEN`"]
     #[error]
     LEXING_ERROR,
     ERROR,
@@ -79,8 +95,14 @@ pub enum SyntaxKind {
     PARAMS,
     TYPE_ANNOTATION,
     DEF_BODY,
+    TYPE_PARAMS,
+    VARIANTS,
+    TYPE_PARAM,
+    VARIANT,
     BLOCK_EXPR,
     TYPE_IDENT,
+    TYPE_VAR,
+    TYPE_APP,
     TYPE_ARROW,
     TYPE_PAREN,
     EXPR_STMT,
@@ -90,12 +112,20 @@ pub enum SyntaxKind {
     LAMBDA_EXPR,
     PAREN_EXPR,
     BINARY_EXPR,
+    IF_EXPR,
+    MATCH_EXPR,
     APP_EXPR,
+    MATCH_ARM,
+    WILDCARD_PAT,
+    LITERAL_PAT,
+    IDENT_PAT,
+    PAREN_PAT,
     PARAM,
     MODULE_ITEM,
     TYPE_EXPR,
     EXPR,
     STMT,
+    PAT,
     LITERAL,
     INFIX_SYMBOL,
     #[doc(hidden)]
@@ -106,7 +136,8 @@ impl SyntaxKind {
     #[must_use]
     pub fn is_keyword(self) -> bool {
         match self {
-            DEF_KW | OPEN_KW | TYPE_KW | LET_KW | DUMMY_KW => true,
+            DEF_KW | OPEN_KW | TYPE_KW | LET_KW | IF_KW | ELSE_KW | MATCH_KW | TRUE_KW
+            | FALSE_KW => true,
             _ => false,
         }
     }
@@ -114,14 +145,16 @@ impl SyntaxKind {
     pub fn is_operator(self) -> bool {
         match self {
             L_PAREN | R_PAREN | L_BRACE | R_BRACE | COLON | SEMICOLON | COMMA | EQUAL | ARROW
-            | PLUS | MINUS | STAR | SLASH | BACKSLASH | LAMBDA => true,
+            | PLUS | MINUS | STAR | SLASH | BACKSLASH | LAMBDA | PIPE | FAT_ARROW | UNDERSCORE => {
+                true
+            }
             _ => false,
         }
     }
     #[must_use]
     pub fn is_enum(self) -> bool {
         match self {
-            MODULE_ITEM | TYPE_EXPR | EXPR | STMT | LITERAL | INFIX_SYMBOL => true,
+            MODULE_ITEM | TYPE_EXPR | EXPR | STMT | PAT | LITERAL | INFIX_SYMBOL => true,
             _ => false,
         }
     }
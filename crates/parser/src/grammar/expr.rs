@@ -1,28 +1,81 @@
 use super::{block::block, params::params};
 use crate::{
-    grammar::{module_item, type_expr},
+    grammar::{module_item, pat, type_expr},
     parser::{CompletedMarker, Parser},
     token_set::TokenSet,
     SyntaxKind,
 };
 
 const LAMBDA_TOKENS: TokenSet = TokenSet::new(&[SyntaxKind::LAMBDA, SyntaxKind::BACKSLASH]);
-const LITERAL_EXPR_FIRST: TokenSet = TokenSet::new(&[SyntaxKind::INT]);
+pub(crate) const LITERAL_EXPR_FIRST: TokenSet =
+    TokenSet::new(&[SyntaxKind::INT, SyntaxKind::TRUE_KW, SyntaxKind::FALSE_KW]);
 const ATOM_EXPR_FIRST: TokenSet =
     TokenSet::new(&[SyntaxKind::L_PAREN, SyntaxKind::L_BRACE, SyntaxKind::IDENT])
         .union(LITERAL_EXPR_FIRST);
 
-pub(crate) const EXPR_FIRST: TokenSet = LAMBDA_TOKENS.union(ATOM_EXPR_FIRST);
+const INFIX_SYMBOLS: TokenSet = TokenSet::new(&[
+    SyntaxKind::PLUS,
+    SyntaxKind::MINUS,
+    SyntaxKind::STAR,
+    SyntaxKind::SLASH,
+]);
+
+pub(crate) const EXPR_FIRST: TokenSet = LAMBDA_TOKENS
+    .union(ATOM_EXPR_FIRST)
+    .union(TokenSet::new(&[SyntaxKind::IF_KW, SyntaxKind::MATCH_KW]));
 
 pub(crate) fn expr(parser: &mut Parser) -> CompletedMarker {
     if parser.at_any(LAMBDA_TOKENS) {
         lambda_expr(parser)
+    } else if parser.at(SyntaxKind::IF_KW) {
+        if_expr(parser)
+    } else if parser.at(SyntaxKind::MATCH_KW) {
+        match_expr(parser)
     } else if parser.at_any(ATOM_EXPR_FIRST) {
-        delimited_expr(parser)
+        let lhs = delimited_expr(parser);
+        binary_expr(parser, lhs, 0, delimited_expr)
     } else {
         parser.error("Expected expression".into())
     }
 }
+
+/// Left and right binding powers of the infix operator at the current token.
+fn infix_binding_power(parser: &mut Parser) -> Option<(u8, u8)> {
+    if parser.at(SyntaxKind::PLUS) || parser.at(SyntaxKind::MINUS) {
+        Some((1, 2))
+    } else if parser.at(SyntaxKind::STAR) || parser.at(SyntaxKind::SLASH) {
+        Some((3, 4))
+    } else {
+        None
+    }
+}
+
+/// Parses operators following the already parsed `lhs` with Pratt's algorithm,
+/// consuming only the ones binding tighter than `min_power`.
+/// The right operands are parsed with `operand`.
+fn binary_expr(
+    parser: &mut Parser,
+    mut lhs: CompletedMarker,
+    min_power: u8,
+    operand: fn(&mut Parser) -> CompletedMarker,
+) -> CompletedMarker {
+    while let Some((left_power, right_power)) = infix_binding_power(parser) {
+        if left_power < min_power {
+            break;
+        }
+        let mark = parser.open_before(lhs);
+        parser.advance();
+
+        if parser.at_any(ATOM_EXPR_FIRST) {
+            let rhs = operand(parser);
+            binary_expr(parser, rhs, right_power, operand);
+        } else {
+            parser.error("Expected expression".into());
+        }
+        lhs = parser.close(mark, SyntaxKind::BINARY_EXPR);
+    }
+    lhs
+}
 fn delimited_expr(parser: &mut Parser) -> CompletedMarker {
     if parser.at(SyntaxKind::IDENT) {
         ident_expr(parser)
@@ -73,6 +126,72 @@ fn lambda_expr(parser: &mut Parser) -> CompletedMarker {
     parser.close(mark, SyntaxKind::LAMBDA_EXPR)
 }
 
+/// Both branches are blocks, the else branch can also be another `if` expression.
+fn if_expr(parser: &mut Parser) -> CompletedMarker {
+    assert!(parser.at(SyntaxKind::IF_KW));
+
+    let mark = parser.open();
+    parser.advance();
+    expr(parser);
+    branch(parser);
+
+    if parser.eat(SyntaxKind::ELSE_KW) {
+        if parser.at(SyntaxKind::IF_KW) {
+            if_expr(parser);
+        } else {
+            branch(parser);
+        }
+    } else {
+        parser.expect(SyntaxKind::ELSE_KW);
+    }
+    parser.close(mark, SyntaxKind::IF_EXPR)
+}
+
+fn branch(parser: &mut Parser) {
+    if parser.at(SyntaxKind::L_BRACE) {
+        block(parser);
+    } else {
+        parser.error("Expected block".into());
+    }
+}
+
+/// Arms are separated by commas, which are optional after the last one.
+fn match_expr(parser: &mut Parser) -> CompletedMarker {
+    assert!(parser.at(SyntaxKind::MATCH_KW));
+
+    let mark = parser.open();
+    parser.advance();
+    expr(parser);
+    if !parser.at(SyntaxKind::L_BRACE) {
+        parser.error("Expected match arms".into());
+        return parser.close(mark, SyntaxKind::MATCH_EXPR);
+    }
+    parser.advance();
+
+    let arms_end = TokenSet::new(&[SyntaxKind::R_BRACE, SyntaxKind::EOF])
+        .union(module_item::MODULE_ITEM_START);
+    while !parser.at_any(arms_end) {
+        if parser.at_any(pat::PAT_FIRST) {
+            match_arm(parser);
+        } else {
+            parser.eat_error_until(pat::PAT_FIRST.union(arms_end), "Expected pattern".into());
+        }
+    }
+    parser.expect(SyntaxKind::R_BRACE);
+    parser.close(mark, SyntaxKind::MATCH_EXPR)
+}
+
+fn match_arm(parser: &mut Parser) -> CompletedMarker {
+    let mark = parser.open();
+    pat::pat(parser);
+    parser.expect(SyntaxKind::FAT_ARROW);
+    expr(parser);
+    if !parser.at(SyntaxKind::R_BRACE) {
+        parser.expect(SyntaxKind::COMMA);
+    }
+    parser.close(mark, SyntaxKind::MATCH_ARM)
+}
+
 fn paren_expr(parser: &mut Parser) -> CompletedMarker {
     assert!(parser.at(SyntaxKind::L_PAREN));
 
@@ -86,11 +205,22 @@ fn paren_expr(parser: &mut Parser) -> CompletedMarker {
 
     if parser.at_any(LAMBDA_TOKENS) {
         lambda_expr(parser);
+    } else if parser.at(SyntaxKind::IF_KW) {
+        if_expr(parser);
+    } else if parser.at(SyntaxKind::MATCH_KW) {
+        match_expr(parser);
     } else {
         let mut prev_mark = None;
         while !parser.at_any(paren_expr_end) {
             if parser.at_any(ATOM_EXPR_FIRST) {
                 prev_mark = Some(parse_app_part(parser, prev_mark));
+            } else if let Some(lhs) = prev_mark.filter(|_| parser.at_any(INFIX_SYMBOLS)) {
+                // In parentheses, applications can be operands without parentheses of their own.
+                binary_expr(parser, lhs, 0, app_expr);
+                // The operands take all expressions following them, so nothing else can.
+                if !parser.at_any(paren_expr_end) {
+                    parser.eat_error_until(paren_expr_end, "Expected R_PAREN".into());
+                }
             } else {
                 parser.eat_error_until(
                     ATOM_EXPR_FIRST.union(paren_expr_end),
@@ -98,12 +228,21 @@ fn paren_expr(parser: &mut Parser) -> CompletedMarker {
                 );
             }
         }
-    };
+    }
 
     parser.expect(SyntaxKind::R_PAREN);
     parser.close(mark, SyntaxKind::PAREN_EXPR)
 }
 
+/// An expression applied to the following delimited ones, if there are any.
+fn app_expr(parser: &mut Parser) -> CompletedMarker {
+    let mut app = delimited_expr(parser);
+    while parser.at_any(ATOM_EXPR_FIRST) {
+        app = parse_app_part(parser, Some(app));
+    }
+    app
+}
+
 fn parse_app_part(parser: &mut Parser, prev_mark: Option<CompletedMarker>) -> CompletedMarker {
     match prev_mark {
         Some(prev) => {
@@ -117,7 +256,7 @@ fn parse_app_part(parser: &mut Parser, prev_mark: Option<CompletedMarker>) -> Co
 
 #[cfg(test)]
 mod tests {
-    use crate::{check, PrefixEntryPoint};
+    use crate::{check, check_err, PrefixEntryPoint};
     use expect_test::expect;
 
     #[test]
@@ -194,6 +333,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_bool_literal() {
+        check(
+            PrefixEntryPoint::Expr,
+            r"true",
+            &expect![[r#"
+                LITERAL_EXPR@0..4
+                  TRUE_KW@0..4 "true"
+            "#]],
+        );
+    }
+
     #[test]
     fn parse_ident() {
         check(
@@ -340,4 +491,419 @@ mod tests {
             "#]],
         );
     }
+
+    #[test]
+    fn parse_binary() {
+        check(
+            PrefixEntryPoint::Expr,
+            "1 + x",
+            &expect![[r#"
+                BINARY_EXPR@0..5
+                  LITERAL_EXPR@0..2
+                    INT@0..1 "1"
+                    WHITESPACE@1..2 " "
+                  PLUS@2..3 "+"
+                  WHITESPACE@3..4 " "
+                  IDENT_EXPR@4..5
+                    IDENT@4..5 "x"
+            "#]],
+        );
+    }
+
+    #[test]
+    fn binary_precedence() {
+        check(
+            PrefixEntryPoint::Expr,
+            "1 + 2 * 3 - 4 / 5",
+            &expect![[r#"
+                BINARY_EXPR@0..17
+                  BINARY_EXPR@0..10
+                    LITERAL_EXPR@0..2
+                      INT@0..1 "1"
+                      WHITESPACE@1..2 " "
+                    PLUS@2..3 "+"
+                    WHITESPACE@3..4 " "
+                    BINARY_EXPR@4..10
+                      LITERAL_EXPR@4..6
+                        INT@4..5 "2"
+                        WHITESPACE@5..6 " "
+                      STAR@6..7 "*"
+                      WHITESPACE@7..8 " "
+                      LITERAL_EXPR@8..10
+                        INT@8..9 "3"
+                        WHITESPACE@9..10 " "
+                  MINUS@10..11 "-"
+                  WHITESPACE@11..12 " "
+                  BINARY_EXPR@12..17
+                    LITERAL_EXPR@12..14
+                      INT@12..13 "4"
+                      WHITESPACE@13..14 " "
+                    SLASH@14..15 "/"
+                    WHITESPACE@15..16 " "
+                    LITERAL_EXPR@16..17
+                      INT@16..17 "5"
+            "#]],
+        );
+    }
+
+    #[test]
+    fn binary_is_left_associative() {
+        check(
+            PrefixEntryPoint::Expr,
+            "1 - 2 - 3",
+            &expect![[r#"
+                BINARY_EXPR@0..9
+                  BINARY_EXPR@0..6
+                    LITERAL_EXPR@0..2
+                      INT@0..1 "1"
+                      WHITESPACE@1..2 " "
+                    MINUS@2..3 "-"
+                    WHITESPACE@3..4 " "
+                    LITERAL_EXPR@4..6
+                      INT@4..5 "2"
+                      WHITESPACE@5..6 " "
+                  MINUS@6..7 "-"
+                  WHITESPACE@7..8 " "
+                  LITERAL_EXPR@8..9
+                    INT@8..9 "3"
+            "#]],
+        );
+    }
+
+    #[test]
+    fn binary_in_paren() {
+        check(
+            PrefixEntryPoint::Expr,
+            "(1 + 2) * (f x)",
+            &expect![[r#"
+                BINARY_EXPR@0..15
+                  PAREN_EXPR@0..8
+                    L_PAREN@0..1 "("
+                    BINARY_EXPR@1..6
+                      LITERAL_EXPR@1..3
+                        INT@1..2 "1"
+                        WHITESPACE@2..3 " "
+                      PLUS@3..4 "+"
+                      WHITESPACE@4..5 " "
+                      LITERAL_EXPR@5..6
+                        INT@5..6 "2"
+                    R_PAREN@6..7 ")"
+                    WHITESPACE@7..8 " "
+                  STAR@8..9 "*"
+                  WHITESPACE@9..10 " "
+                  PAREN_EXPR@10..15
+                    L_PAREN@10..11 "("
+                    APP_EXPR@11..14
+                      IDENT_EXPR@11..13
+                        IDENT@11..12 "f"
+                        WHITESPACE@12..13 " "
+                      IDENT_EXPR@13..14
+                        IDENT@13..14 "x"
+                    R_PAREN@14..15 ")"
+            "#]],
+        );
+    }
+
+    #[test]
+    fn binary_missing_rhs() {
+        check_err(
+            PrefixEntryPoint::Expr,
+            "1 +",
+            &expect![[r#"
+                BINARY_EXPR@0..3
+                  LITERAL_EXPR@0..2
+                    INT@0..1 "1"
+                    WHITESPACE@1..2 " "
+                  PLUS@2..3 "+"
+                  ERROR@3..3
+            "#]],
+            &["Expected expression"],
+        );
+    }
+
+    #[test]
+    fn binary_of_app_in_paren() {
+        check(
+            PrefixEntryPoint::Expr,
+            "(f x + 1 * g y z)",
+            &expect![[r#"
+                PAREN_EXPR@0..17
+                  L_PAREN@0..1 "("
+                  BINARY_EXPR@1..16
+                    APP_EXPR@1..5
+                      IDENT_EXPR@1..3
+                        IDENT@1..2 "f"
+                        WHITESPACE@2..3 " "
+                      IDENT_EXPR@3..5
+                        IDENT@3..4 "x"
+                        WHITESPACE@4..5 " "
+                    PLUS@5..6 "+"
+                    WHITESPACE@6..7 " "
+                    BINARY_EXPR@7..16
+                      LITERAL_EXPR@7..9
+                        INT@7..8 "1"
+                        WHITESPACE@8..9 " "
+                      STAR@9..10 "*"
+                      WHITESPACE@10..11 " "
+                      APP_EXPR@11..16
+                        APP_EXPR@11..15
+                          IDENT_EXPR@11..13
+                            IDENT@11..12 "g"
+                            WHITESPACE@12..13 " "
+                          IDENT_EXPR@13..15
+                            IDENT@13..14 "y"
+                            WHITESPACE@14..15 " "
+                        IDENT_EXPR@15..16
+                          IDENT@15..16 "z"
+                  R_PAREN@16..17 ")"
+            "#]],
+        );
+    }
+
+    #[test]
+    fn parse_if() {
+        check(
+            PrefixEntryPoint::Expr,
+            "if (f x) { 1 } else { 2 }",
+            &expect![[r#"
+                IF_EXPR@0..25
+                  IF_KW@0..2 "if"
+                  WHITESPACE@2..3 " "
+                  PAREN_EXPR@3..9
+                    L_PAREN@3..4 "("
+                    APP_EXPR@4..7
+                      IDENT_EXPR@4..6
+                        IDENT@4..5 "f"
+                        WHITESPACE@5..6 " "
+                      IDENT_EXPR@6..7
+                        IDENT@6..7 "x"
+                    R_PAREN@7..8 ")"
+                    WHITESPACE@8..9 " "
+                  BLOCK_EXPR@9..15
+                    L_BRACE@9..10 "{"
+                    WHITESPACE@10..11 " "
+                    LITERAL_EXPR@11..13
+                      INT@11..12 "1"
+                      WHITESPACE@12..13 " "
+                    R_BRACE@13..14 "}"
+                    WHITESPACE@14..15 " "
+                  ELSE_KW@15..19 "else"
+                  WHITESPACE@19..20 " "
+                  BLOCK_EXPR@20..25
+                    L_BRACE@20..21 "{"
+                    WHITESPACE@21..22 " "
+                    LITERAL_EXPR@22..24
+                      INT@22..23 "2"
+                      WHITESPACE@23..24 " "
+                    R_BRACE@24..25 "}"
+            "#]],
+        );
+    }
+
+    #[test]
+    fn parse_else_if() {
+        check(
+            PrefixEntryPoint::Expr,
+            "if a { 1 } else if false { 2 } else { 3 }",
+            &expect![[r#"
+                IF_EXPR@0..41
+                  IF_KW@0..2 "if"
+                  WHITESPACE@2..3 " "
+                  IDENT_EXPR@3..5
+                    IDENT@3..4 "a"
+                    WHITESPACE@4..5 " "
+                  BLOCK_EXPR@5..11
+                    L_BRACE@5..6 "{"
+                    WHITESPACE@6..7 " "
+                    LITERAL_EXPR@7..9
+                      INT@7..8 "1"
+                      WHITESPACE@8..9 " "
+                    R_BRACE@9..10 "}"
+                    WHITESPACE@10..11 " "
+                  ELSE_KW@11..15 "else"
+                  WHITESPACE@15..16 " "
+                  IF_EXPR@16..41
+                    IF_KW@16..18 "if"
+                    WHITESPACE@18..19 " "
+                    LITERAL_EXPR@19..25
+                      FALSE_KW@19..24 "false"
+                      WHITESPACE@24..25 " "
+                    BLOCK_EXPR@25..31
+                      L_BRACE@25..26 "{"
+                      WHITESPACE@26..27 " "
+                      LITERAL_EXPR@27..29
+                        INT@27..28 "2"
+                        WHITESPACE@28..29 " "
+                      R_BRACE@29..30 "}"
+                      WHITESPACE@30..31 " "
+                    ELSE_KW@31..35 "else"
+                    WHITESPACE@35..36 " "
+                    BLOCK_EXPR@36..41
+                      L_BRACE@36..37 "{"
+                      WHITESPACE@37..38 " "
+                      LITERAL_EXPR@38..40
+                        INT@38..39 "3"
+                        WHITESPACE@39..40 " "
+                      R_BRACE@40..41 "}"
+            "#]],
+        );
+    }
+
+    #[test]
+    fn parse_match() {
+        check(
+            PrefixEntryPoint::Expr,
+            "match x { Some 0 => true, _ => { false }, }",
+            &expect![[r#"
+                MATCH_EXPR@0..43
+                  MATCH_KW@0..5 "match"
+                  WHITESPACE@5..6 " "
+                  IDENT_EXPR@6..8
+                    IDENT@6..7 "x"
+                    WHITESPACE@7..8 " "
+                  L_BRACE@8..9 "{"
+                  WHITESPACE@9..10 " "
+                  MATCH_ARM@10..26
+                    IDENT_PAT@10..17
+                      IDENT@10..14 "Some"
+                      WHITESPACE@14..15 " "
+                      LITERAL_PAT@15..17
+                        INT@15..16 "0"
+                        WHITESPACE@16..17 " "
+                    FAT_ARROW@17..19 "=>"
+                    WHITESPACE@19..20 " "
+                    LITERAL_EXPR@20..24
+                      TRUE_KW@20..24 "true"
+                    COMMA@24..25 ","
+                    WHITESPACE@25..26 " "
+                  MATCH_ARM@26..42
+                    WILDCARD_PAT@26..28
+                      UNDERSCORE@26..27 "_"
+                      WHITESPACE@27..28 " "
+                    FAT_ARROW@28..30 "=>"
+                    WHITESPACE@30..31 " "
+                    BLOCK_EXPR@31..40
+                      L_BRACE@31..32 "{"
+                      WHITESPACE@32..33 " "
+                      LITERAL_EXPR@33..39
+                        FALSE_KW@33..38 "false"
+                        WHITESPACE@38..39 " "
+                      R_BRACE@39..40 "}"
+                    COMMA@40..41 ","
+                    WHITESPACE@41..42 " "
+                  R_BRACE@42..43 "}"
+            "#]],
+        );
+    }
+
+    #[test]
+    fn parse_match_missing_comma() {
+        check_err(
+            PrefixEntryPoint::Expr,
+            "match x { 1 => a 2 => b }",
+            &expect![[r#"
+                MATCH_EXPR@0..25
+                  MATCH_KW@0..5 "match"
+                  WHITESPACE@5..6 " "
+                  IDENT_EXPR@6..8
+                    IDENT@6..7 "x"
+                    WHITESPACE@7..8 " "
+                  L_BRACE@8..9 "{"
+                  WHITESPACE@9..10 " "
+                  MATCH_ARM@10..17
+                    LITERAL_PAT@10..12
+                      INT@10..11 "1"
+                      WHITESPACE@11..12 " "
+                    FAT_ARROW@12..14 "=>"
+                    WHITESPACE@14..15 " "
+                    IDENT_EXPR@15..16
+                      IDENT@15..16 "a"
+                    ERROR@16..16
+                    WHITESPACE@16..17 " "
+                  MATCH_ARM@17..24
+                    LITERAL_PAT@17..19
+                      INT@17..18 "2"
+                      WHITESPACE@18..19 " "
+                    FAT_ARROW@19..21 "=>"
+                    WHITESPACE@21..22 " "
+                    IDENT_EXPR@22..24
+                      IDENT@22..23 "b"
+                      WHITESPACE@23..24 " "
+                  R_BRACE@24..25 "}"
+            "#]],
+            &["Expected COMMA but found INT"],
+        );
+    }
+
+    #[test]
+    fn parse_if_in_paren() {
+        check(
+            PrefixEntryPoint::Expr,
+            "(if a {} else {})",
+            &expect![[r#"
+                PAREN_EXPR@0..17
+                  L_PAREN@0..1 "("
+                  IF_EXPR@1..16
+                    IF_KW@1..3 "if"
+                    WHITESPACE@3..4 " "
+                    IDENT_EXPR@4..6
+                      IDENT@4..5 "a"
+                      WHITESPACE@5..6 " "
+                    BLOCK_EXPR@6..9
+                      L_BRACE@6..7 "{"
+                      R_BRACE@7..8 "}"
+                      WHITESPACE@8..9 " "
+                    ELSE_KW@9..13 "else"
+                    WHITESPACE@13..14 " "
+                    BLOCK_EXPR@14..16
+                      L_BRACE@14..15 "{"
+                      R_BRACE@15..16 "}"
+                  R_PAREN@16..17 ")"
+            "#]],
+        );
+    }
+
+    #[test]
+    fn if_missing_else() {
+        check_err(
+            PrefixEntryPoint::Expr,
+            "if a { 1 }",
+            &expect![[r#"
+                IF_EXPR@0..10
+                  IF_KW@0..2 "if"
+                  WHITESPACE@2..3 " "
+                  IDENT_EXPR@3..5
+                    IDENT@3..4 "a"
+                    WHITESPACE@4..5 " "
+                  BLOCK_EXPR@5..10
+                    L_BRACE@5..6 "{"
+                    WHITESPACE@6..7 " "
+                    LITERAL_EXPR@7..9
+                      INT@7..8 "1"
+                      WHITESPACE@8..9 " "
+                    R_BRACE@9..10 "}"
+                  ERROR@10..10
+            "#]],
+            &["Expected ELSE_KW but found EOF"],
+        );
+    }
+
+    #[test]
+    fn if_branch_not_a_block() {
+        check_err(
+            PrefixEntryPoint::Expr,
+            "if a 1 else { 2 }",
+            &expect![[r#"
+                IF_EXPR@0..5
+                  IF_KW@0..2 "if"
+                  WHITESPACE@2..3 " "
+                  IDENT_EXPR@3..4
+                    IDENT@3..4 "a"
+                  ERROR@4..4
+                  ERROR@4..4
+                  WHITESPACE@4..5 " "
+            "#]],
+            &["Expected block", "Expected ELSE_KW but found INT"],
+        );
+    }
 }
@@ -7,6 +7,7 @@ mod block;
 mod expr;
 mod module_item;
 mod params;
+mod pat;
 mod type_expr;
 
 pub(crate) fn module(parser: &mut Parser) -> parser::CompletedMarker {
@@ -22,10 +23,17 @@ pub(crate) fn module(parser: &mut Parser) -> parser::CompletedMarker {
 pub(crate) fn parse(mut parser: Parser, entry_point: PrefixEntryPoint) -> Vec<crate::event::Event> {
     match entry_point {
         PrefixEntryPoint::Module => module(&mut parser),
+        PrefixEntryPoint::ModuleItem => {
+            module_item::module_item(&mut parser);
+            return parser.finish();
+        }
+        PrefixEntryPoint::Block => block::block(&mut parser),
         #[cfg(test)]
         PrefixEntryPoint::TypeExpr => type_expr::type_expr(&mut parser),
         #[cfg(test)]
         PrefixEntryPoint::Expr => expr::expr(&mut parser),
+        #[cfg(test)]
+        PrefixEntryPoint::Pat => pat::pat(&mut parser),
     };
     parser.finish()
 }
@@ -53,6 +61,7 @@ mod tests {
                     WHITESPACE@12..13 " "
                     IDENT@13..14 "t"
                     WHITESPACE@14..15 " "
+                    TYPE_PARAMS@15..15
                     EQUAL@15..16 "="
                     WHITESPACE@16..17 " "
                     TYPE_IDENT@17..18
@@ -41,13 +41,55 @@ fn type_definition(parser: &mut Parser) {
     let mark = parser.open();
     parser.expect(SyntaxKind::TYPE_KW);
     parser.expect(SyntaxKind::IDENT);
+    type_params(parser);
     parser.expect(SyntaxKind::EQUAL);
-    type_expr::type_expr(parser);
+    if at_variants(parser) {
+        variants(parser);
+    } else {
+        type_expr::type_expr(parser);
+    }
     parser.expect(SyntaxKind::SEMICOLON);
 
     parser.close(mark, SyntaxKind::TYPE_DEFINITION);
 }
 
+fn type_params(parser: &mut Parser) {
+    let mark = parser.open();
+    while parser.at(SyntaxKind::IDENT) {
+        let param = parser.open();
+        parser.advance();
+        parser.close(param, SyntaxKind::TYPE_PARAM);
+    }
+    parser.close(mark, SyntaxKind::TYPE_PARAMS);
+}
+
+/// Constructors are told apart from an aliased type by the `|` between them,
+/// so a single constructor needs a leading one, as in `type box a = | Box a;`.
+fn at_variants(parser: &Parser) -> bool {
+    parser
+        .lookahead()
+        .take_while(|kind| !MODULE_ITEM_START.union(MODULE_ITEM_END).contains(*kind))
+        .any(|kind| kind == SyntaxKind::PIPE)
+}
+
+fn variants(parser: &mut Parser) {
+    let mark = parser.open();
+    while parser.at_any(TokenSet::new(&[SyntaxKind::PIPE, SyntaxKind::IDENT])) {
+        variant(parser);
+    }
+    parser.close(mark, SyntaxKind::VARIANTS);
+}
+
+fn variant(parser: &mut Parser) {
+    let mark = parser.open();
+    parser.eat(SyntaxKind::PIPE);
+    parser.expect(SyntaxKind::IDENT);
+    while parser.at_any(type_expr::DELIMITED_TYPE_EXPR_FIRST) {
+        type_expr::delimited_type_expr(parser);
+    }
+    parser.close(mark, SyntaxKind::VARIANT);
+}
+
 fn def(parser: &mut Parser) {
     assert!(parser.at(SyntaxKind::DEF_KW));
 
@@ -81,20 +123,20 @@ mod tests {
             PrefixEntryPoint::Module,
             "def f = x;",
             &expect![[r#"
-            MODULE@0..10
-              DEFINITION@0..10
-                DEF_KW@0..3 "def"
-                WHITESPACE@3..4 " "
-                IDENT@4..5 "f"
-                WHITESPACE@5..6 " "
-                PARAMS@6..6
-                DEF_BODY@6..10
-                  EQUAL@6..7 "="
-                  WHITESPACE@7..8 " "
-                  IDENT_EXPR@8..9
-                    IDENT@8..9 "x"
-                  SEMICOLON@9..10 ";"
-        "#]],
+                MODULE@0..10
+                  DEFINITION@0..10
+                    DEF_KW@0..3 "def"
+                    WHITESPACE@3..4 " "
+                    IDENT@4..5 "f"
+                    WHITESPACE@5..6 " "
+                    PARAMS@6..6
+                    DEF_BODY@6..10
+                      EQUAL@6..7 "="
+                      WHITESPACE@7..8 " "
+                      IDENT_EXPR@8..9
+                        IDENT@8..9 "x"
+                      SEMICOLON@9..10 ";"
+            "#]],
         );
     }
 
@@ -239,4 +281,188 @@ mod tests {
             "#]],
         );
     }
+
+    #[test]
+    fn parse_sum_type() {
+        check(
+            PrefixEntryPoint::Module,
+            "type option a = None | Some a;",
+            &expect![[r#"
+                MODULE@0..30
+                  TYPE_DEFINITION@0..30
+                    TYPE_KW@0..4 "type"
+                    WHITESPACE@4..5 " "
+                    IDENT@5..11 "option"
+                    WHITESPACE@11..12 " "
+                    TYPE_PARAMS@12..14
+                      TYPE_PARAM@12..14
+                        IDENT@12..13 "a"
+                        WHITESPACE@13..14 " "
+                    EQUAL@14..15 "="
+                    WHITESPACE@15..16 " "
+                    VARIANTS@16..29
+                      VARIANT@16..21
+                        IDENT@16..20 "None"
+                        WHITESPACE@20..21 " "
+                      VARIANT@21..29
+                        PIPE@21..22 "|"
+                        WHITESPACE@22..23 " "
+                        IDENT@23..27 "Some"
+                        WHITESPACE@27..28 " "
+                        TYPE_IDENT@28..29
+                          IDENT@28..29 "a"
+                    SEMICOLON@29..30 ";"
+            "#]],
+        );
+    }
+
+    #[test]
+    fn parse_single_constructor() {
+        check(
+            PrefixEntryPoint::Module,
+            "type pair = | Pair int (int -> int);",
+            &expect![[r#"
+                MODULE@0..36
+                  TYPE_DEFINITION@0..36
+                    TYPE_KW@0..4 "type"
+                    WHITESPACE@4..5 " "
+                    IDENT@5..9 "pair"
+                    WHITESPACE@9..10 " "
+                    TYPE_PARAMS@10..10
+                    EQUAL@10..11 "="
+                    WHITESPACE@11..12 " "
+                    VARIANTS@12..35
+                      VARIANT@12..35
+                        PIPE@12..13 "|"
+                        WHITESPACE@13..14 " "
+                        IDENT@14..18 "Pair"
+                        WHITESPACE@18..19 " "
+                        TYPE_IDENT@19..23
+                          IDENT@19..22 "int"
+                          WHITESPACE@22..23 " "
+                        TYPE_PAREN@23..35
+                          L_PAREN@23..24 "("
+                          TYPE_ARROW@24..34
+                            TYPE_IDENT@24..28
+                              IDENT@24..27 "int"
+                              WHITESPACE@27..28 " "
+                            ARROW@28..30 "->"
+                            WHITESPACE@30..31 " "
+                            TYPE_IDENT@31..34
+                              IDENT@31..34 "int"
+                          R_PAREN@34..35 ")"
+                    SEMICOLON@35..36 ";"
+            "#]],
+        );
+    }
+
+    #[test]
+    fn parse_alias_with_params() {
+        check(
+            PrefixEntryPoint::Module,
+            "type endo a = a -> a;",
+            &expect![[r#"
+                MODULE@0..21
+                  TYPE_DEFINITION@0..21
+                    TYPE_KW@0..4 "type"
+                    WHITESPACE@4..5 " "
+                    IDENT@5..9 "endo"
+                    WHITESPACE@9..10 " "
+                    TYPE_PARAMS@10..12
+                      TYPE_PARAM@10..12
+                        IDENT@10..11 "a"
+                        WHITESPACE@11..12 " "
+                    EQUAL@12..13 "="
+                    WHITESPACE@13..14 " "
+                    TYPE_ARROW@14..20
+                      TYPE_IDENT@14..16
+                        IDENT@14..15 "a"
+                        WHITESPACE@15..16 " "
+                      ARROW@16..18 "->"
+                      WHITESPACE@18..19 " "
+                      TYPE_IDENT@19..20
+                        IDENT@19..20 "a"
+                    SEMICOLON@20..21 ";"
+            "#]],
+        );
+    }
+
+    #[test]
+    fn parse_capitalized_alias() {
+        check(
+            PrefixEntryPoint::Module,
+            "type t = Foo -> Bar;\ntype u = | C int;",
+            &expect![[r#"
+                MODULE@0..38
+                  TYPE_DEFINITION@0..21
+                    TYPE_KW@0..4 "type"
+                    WHITESPACE@4..5 " "
+                    IDENT@5..6 "t"
+                    WHITESPACE@6..7 " "
+                    TYPE_PARAMS@7..7
+                    EQUAL@7..8 "="
+                    WHITESPACE@8..9 " "
+                    TYPE_ARROW@9..19
+                      TYPE_IDENT@9..13
+                        IDENT@9..12 "Foo"
+                        WHITESPACE@12..13 " "
+                      ARROW@13..15 "->"
+                      WHITESPACE@15..16 " "
+                      TYPE_IDENT@16..19
+                        IDENT@16..19 "Bar"
+                    SEMICOLON@19..20 ";"
+                    WHITESPACE@20..21 "\n"
+                  TYPE_DEFINITION@21..38
+                    TYPE_KW@21..25 "type"
+                    WHITESPACE@25..26 " "
+                    IDENT@26..27 "u"
+                    WHITESPACE@27..28 " "
+                    TYPE_PARAMS@28..28
+                    EQUAL@28..29 "="
+                    WHITESPACE@29..30 " "
+                    VARIANTS@30..37
+                      VARIANT@30..37
+                        PIPE@30..31 "|"
+                        WHITESPACE@31..32 " "
+                        IDENT@32..33 "C"
+                        WHITESPACE@33..34 " "
+                        TYPE_IDENT@34..37
+                          IDENT@34..37 "int"
+                    SEMICOLON@37..38 ";"
+            "#]],
+        );
+    }
+
+    #[test]
+    fn parse_variant_missing_name() {
+        check_err(
+            PrefixEntryPoint::Module,
+            "type t = A | | B;",
+            &expect![[r#"
+                MODULE@0..17
+                  TYPE_DEFINITION@0..17
+                    TYPE_KW@0..4 "type"
+                    WHITESPACE@4..5 " "
+                    IDENT@5..6 "t"
+                    WHITESPACE@6..7 " "
+                    TYPE_PARAMS@7..7
+                    EQUAL@7..8 "="
+                    WHITESPACE@8..9 " "
+                    VARIANTS@9..16
+                      VARIANT@9..11
+                        IDENT@9..10 "A"
+                        WHITESPACE@10..11 " "
+                      VARIANT@11..13
+                        PIPE@11..12 "|"
+                        ERROR@12..12
+                        WHITESPACE@12..13 " "
+                      VARIANT@13..16
+                        PIPE@13..14 "|"
+                        WHITESPACE@14..15 " "
+                        IDENT@15..16 "B"
+                    SEMICOLON@16..17 ";"
+            "#]],
+            &["Expected IDENT but found PIPE"],
+        );
+    }
 }
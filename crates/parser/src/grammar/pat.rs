@@ -0,0 +1,101 @@
+use crate::{
+    grammar::expr::LITERAL_EXPR_FIRST,
+    parser::{CompletedMarker, Parser},
+    token_set::TokenSet,
+    SyntaxKind,
+};
+
+pub(crate) const PAT_FIRST: TokenSet = TokenSet::new(&[
+    SyntaxKind::UNDERSCORE,
+    SyntaxKind::IDENT,
+    SyntaxKind::L_PAREN,
+])
+.union(LITERAL_EXPR_FIRST);
+
+/// A name may be followed by argument patterns, like `Some (Some x)`.
+pub(crate) fn pat(parser: &mut Parser) -> CompletedMarker {
+    if parser.at(SyntaxKind::IDENT) {
+        let mark = parser.open();
+        parser.advance();
+        while parser.at_any(PAT_FIRST) {
+            delimited_pat(parser);
+        }
+        parser.close(mark, SyntaxKind::IDENT_PAT)
+    } else if parser.at_any(PAT_FIRST) {
+        delimited_pat(parser)
+    } else {
+        parser.error("Expected pattern".into())
+    }
+}
+
+fn delimited_pat(parser: &mut Parser) -> CompletedMarker {
+    let mark = parser.open();
+    if parser.eat(SyntaxKind::UNDERSCORE) {
+        parser.close(mark, SyntaxKind::WILDCARD_PAT)
+    } else if parser.eat(SyntaxKind::IDENT) {
+        parser.close(mark, SyntaxKind::IDENT_PAT)
+    } else if parser.eat_any(LITERAL_EXPR_FIRST) {
+        parser.close(mark, SyntaxKind::LITERAL_PAT)
+    } else if parser.eat(SyntaxKind::L_PAREN) {
+        pat(parser);
+        parser.expect(SyntaxKind::R_PAREN);
+        parser.close(mark, SyntaxKind::PAREN_PAT)
+    } else {
+        unreachable!()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{check, check_err, PrefixEntryPoint};
+    use expect_test::expect;
+
+    #[test]
+    fn parse_nested_pattern() {
+        check(
+            PrefixEntryPoint::Pat,
+            "Pair (Some _) 1 x true",
+            &expect![[r#"
+                IDENT_PAT@0..22
+                  IDENT@0..4 "Pair"
+                  WHITESPACE@4..5 " "
+                  PAREN_PAT@5..14
+                    L_PAREN@5..6 "("
+                    IDENT_PAT@6..12
+                      IDENT@6..10 "Some"
+                      WHITESPACE@10..11 " "
+                      WILDCARD_PAT@11..12
+                        UNDERSCORE@11..12 "_"
+                    R_PAREN@12..13 ")"
+                    WHITESPACE@13..14 " "
+                  LITERAL_PAT@14..16
+                    INT@14..15 "1"
+                    WHITESPACE@15..16 " "
+                  IDENT_PAT@16..18
+                    IDENT@16..17 "x"
+                    WHITESPACE@17..18 " "
+                  LITERAL_PAT@18..22
+                    TRUE_KW@18..22 "true"
+            "#]],
+        );
+    }
+
+    #[test]
+    fn parse_unclosed_paren_pattern() {
+        check_err(
+            PrefixEntryPoint::Pat,
+            "(Some x",
+            &expect![[r#"
+                PAREN_PAT@0..7
+                  L_PAREN@0..1 "("
+                  IDENT_PAT@1..7
+                    IDENT@1..5 "Some"
+                    WHITESPACE@5..6 " "
+                    IDENT_PAT@6..7
+                      IDENT@6..7 "x"
+                  ERROR@7..7
+            "#]],
+            &["Expected R_PAREN but found EOF"],
+        );
+    }
+}
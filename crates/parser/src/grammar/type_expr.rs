@@ -1,13 +1,24 @@
 use crate::{
     parser::{CompletedMarker, Parser},
+    token_set::TokenSet,
     SyntaxKind,
 };
 
+pub(crate) const DELIMITED_TYPE_EXPR_FIRST: TokenSet = TokenSet::new(&[
+    SyntaxKind::IDENT,
+    SyntaxKind::QUOTE_IDENT,
+    SyntaxKind::L_PAREN,
+]);
+
 pub(crate) fn delimited_type_expr(parser: &mut Parser) -> CompletedMarker {
     if parser.at(SyntaxKind::IDENT) {
         let mark = parser.open();
         parser.advance();
         parser.close(mark, SyntaxKind::TYPE_IDENT)
+    } else if parser.at(SyntaxKind::QUOTE_IDENT) {
+        let mark = parser.open();
+        parser.advance();
+        parser.close(mark, SyntaxKind::TYPE_VAR)
     } else if parser.at(SyntaxKind::L_PAREN) {
         let mark = parser.open();
         parser.advance();
@@ -20,7 +31,7 @@ pub(crate) fn delimited_type_expr(parser: &mut Parser) -> CompletedMarker {
 }
 
 pub(crate) fn type_expr(parser: &mut Parser) -> CompletedMarker {
-    let lhs_mark = delimited_type_expr(parser);
+    let lhs_mark = type_app(parser);
 
     if parser.eat(SyntaxKind::ARROW) {
         let marker = parser.open_before(lhs_mark);
@@ -31,6 +42,17 @@ pub(crate) fn type_expr(parser: &mut Parser) -> CompletedMarker {
     }
 }
 
+/// Type arguments follow the applied type, as in `option (list int)`.
+fn type_app(parser: &mut Parser) -> CompletedMarker {
+    let mut func = delimited_type_expr(parser);
+    while parser.at_any(DELIMITED_TYPE_EXPR_FIRST) {
+        let marker = parser.open_before(func);
+        delimited_type_expr(parser);
+        func = parser.close(marker, SyntaxKind::TYPE_APP);
+    }
+    func
+}
+
 pub(crate) fn type_annotation(parser: &mut Parser) -> Option<CompletedMarker> {
     if parser.at(SyntaxKind::COLON) {
         let marker = parser.open();
@@ -59,6 +81,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_type_var() {
+        check(
+            PrefixEntryPoint::TypeExpr,
+            "'a",
+            &expect![[r#"
+                TYPE_VAR@0..2
+                  QUOTE_IDENT@0..2 "'a"
+            "#]],
+        );
+    }
+
+    #[test]
+    fn parse_type_app() {
+        check(
+            PrefixEntryPoint::TypeExpr,
+            "either 'a (list int) -> 'a",
+            &expect![[r#"
+                TYPE_ARROW@0..26
+                  TYPE_APP@0..21
+                    TYPE_APP@0..10
+                      TYPE_IDENT@0..7
+                        IDENT@0..6 "either"
+                        WHITESPACE@6..7 " "
+                      TYPE_VAR@7..10
+                        QUOTE_IDENT@7..9 "'a"
+                        WHITESPACE@9..10 " "
+                    TYPE_PAREN@10..21
+                      L_PAREN@10..11 "("
+                      TYPE_APP@11..19
+                        TYPE_IDENT@11..16
+                          IDENT@11..15 "list"
+                          WHITESPACE@15..16 " "
+                        TYPE_IDENT@16..19
+                          IDENT@16..19 "int"
+                      R_PAREN@19..20 ")"
+                      WHITESPACE@20..21 " "
+                  ARROW@21..23 "->"
+                  WHITESPACE@23..24 " "
+                  TYPE_VAR@24..26
+                    QUOTE_IDENT@24..26 "'a"
+            "#]],
+        );
+    }
+
     #[test]
     fn parse_type_paren() {
         check(
@@ -12,6 +12,14 @@ pub(crate) fn app_expr_arg(parent: &rowan::SyntaxNode<crate::CamlotLanguage>) ->
     parent.children().skip(1).find_map(Expr::cast)
 }
 
+pub(crate) fn type_app_func(parent: &rowan::SyntaxNode<crate::CamlotLanguage>) -> Option<TypeExpr> {
+    support::child(parent)
+}
+
+pub(crate) fn type_app_arg(parent: &rowan::SyntaxNode<crate::CamlotLanguage>) -> Option<TypeExpr> {
+    parent.children().skip(1).find_map(TypeExpr::cast)
+}
+
 pub(crate) fn type_arrow_from(
     parent: &rowan::SyntaxNode<crate::CamlotLanguage>,
 ) -> Option<crate::nodes::TypeExpr> {
@@ -47,3 +55,38 @@ pub(crate) fn binary_expr_rhs(parent: &rowan::SyntaxNode<crate::CamlotLanguage>)
             rowan::NodeOrToken::Token(_) => None,
         })
 }
+
+/// The condition and the then branch precede the `else` keyword.
+fn if_expr_before_else(
+    parent: &rowan::SyntaxNode<crate::CamlotLanguage>,
+) -> impl Iterator<Item = Expr> {
+    parent
+        .children_with_tokens()
+        .take_while(|child| child.kind() != crate::SyntaxKind::ELSE_KW)
+        .filter_map(|child| match child {
+            rowan::NodeOrToken::Node(n) => Expr::cast(n),
+            rowan::NodeOrToken::Token(_) => None,
+        })
+}
+
+pub(crate) fn if_expr_condition(parent: &rowan::SyntaxNode<crate::CamlotLanguage>) -> Option<Expr> {
+    if_expr_before_else(parent).next()
+}
+
+pub(crate) fn if_expr_then_branch(
+    parent: &rowan::SyntaxNode<crate::CamlotLanguage>,
+) -> Option<Expr> {
+    if_expr_before_else(parent).nth(1)
+}
+
+pub(crate) fn if_expr_else_branch(
+    parent: &rowan::SyntaxNode<crate::CamlotLanguage>,
+) -> Option<Expr> {
+    parent
+        .children_with_tokens()
+        .skip_while(|child| child.kind() != crate::SyntaxKind::ELSE_KW)
+        .find_map(|child| match child {
+            rowan::NodeOrToken::Node(n) => Expr::cast(n),
+            rowan::NodeOrToken::Token(_) => None,
+        })
+}
@@ -22,3 +22,4 @@ pub type SyntaxElement = rowan::SyntaxElement<CamlotLanguage>;
 pub type SyntaxNodeChildren = rowan::SyntaxNodeChildren<CamlotLanguage>;
 pub type SyntaxElementChildren = rowan::SyntaxElementChildren<CamlotLanguage>;
 pub type PreorderWithTokens = rowan::api::PreorderWithTokens<CamlotLanguage>;
+pub type SyntaxNodePtr = rowan::ast::SyntaxNodePtr<CamlotLanguage>;
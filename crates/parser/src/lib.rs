@@ -6,6 +6,7 @@ mod handwritten_ast;
 mod language;
 mod lexer;
 mod parser;
+mod reparsing;
 mod sink;
 mod source;
 mod token_set;
@@ -16,7 +17,7 @@ pub use language::*;
 
 use rowan::GreenNode;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct SyntaxError {
     pub message: String,
 }
@@ -41,13 +42,27 @@ fn parse_internal(input: &str, entry_point: PrefixEntryPoint) -> Parse {
     sink.finish()
 }
 
+/// Whether the text lexes as a single identifier, keywords excluded.
+#[must_use]
+pub fn is_identifier(text: &str) -> bool {
+    let mut tokens = lexer::Lexer::new(text);
+    matches!(
+        (tokens.next(), tokens.next()),
+        (Some(token), None) if token.kind == SyntaxKind::IDENT
+    )
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum PrefixEntryPoint {
     Module,
+    ModuleItem,
+    Block,
     #[cfg(test)]
     TypeExpr,
     #[cfg(test)]
     Expr,
+    #[cfg(test)]
+    Pat,
 }
 
 impl Parse {
@@ -77,7 +92,7 @@ impl Parse {
     }
 
     #[must_use]
-    pub fn module(self) -> nodes::Module {
+    pub fn module(&self) -> nodes::Module {
         self.tree()
     }
 }
@@ -108,3 +123,17 @@ fn check_err(
     let actual_errors: Vec<_> = parse.errors.iter().map(|it| it.message.as_str()).collect();
     assert_eq!(actual_errors, expected_errors);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::is_identifier;
+
+    #[test]
+    fn identifiers() {
+        assert!(is_identifier("foo_bar1"));
+        assert!(!is_identifier("def"));
+        assert!(!is_identifier("foo bar"));
+        assert!(!is_identifier("1x"));
+        assert!(!is_identifier(""));
+    }
+}
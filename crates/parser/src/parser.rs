@@ -78,6 +78,11 @@ impl<'t, 'input> Parser<'t, 'input> {
         kinds.contains(self.current())
     }
 
+    /// Kinds of the current token and the ones after it, ignoring trivia
+    pub(crate) fn lookahead(&self) -> impl Iterator<Item = SyntaxKind> + '_ {
+        self.source.lookahead()
+    }
+
     /// Get the current token, ignoring trivia
     fn current(&mut self) -> SyntaxKind {
         self.source.current()
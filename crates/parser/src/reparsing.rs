@@ -0,0 +1,412 @@
+use rowan::{
+    GreenNode, GreenToken, Language, NodeOrToken, TextRange, TextSize, TokenAtOffset, WalkEvent,
+};
+
+use crate::{
+    lexer::Lexer, parse, parse_internal, CamlotLanguage, Parse, PrefixEntryPoint, SyntaxError,
+    SyntaxKind, SyntaxNode, SyntaxToken,
+};
+
+impl Parse {
+    /// Parses the text with `delete` replaced by `insert`.
+    /// Only the edited token, or the smallest block or module item containing the edit,
+    /// is parsed again when possible; the rest of the tree is reused.
+    #[must_use]
+    pub fn reparse(&self, delete: TextRange, insert: &str) -> Parse {
+        if let Some((green_node, errors, _)) =
+            incremental_reparse(&self.syntax(), &self.errors, delete, insert)
+        {
+            return Parse { green_node, errors };
+        }
+        let mut text = self.syntax().text().to_string();
+        text.replace_range(std::ops::Range::<usize>::from(delete), insert);
+        parse(&text)
+    }
+}
+
+/// The new tree, its errors and the range of the reparsed element in the new text.
+fn incremental_reparse(
+    root: &SyntaxNode,
+    errors: &[SyntaxError],
+    delete: TextRange,
+    insert: &str,
+) -> Option<(GreenNode, Vec<SyntaxError>, TextRange)> {
+    if !root.text_range().contains_range(delete) {
+        return None;
+    }
+    if let NodeOrToken::Token(token) = root.covering_element(delete) {
+        if let Some((green, range)) = reparse_token(&token, delete, insert) {
+            return Some((green, errors.to_vec(), range));
+        }
+    }
+    reparse_node(root, errors, delete, insert)
+}
+
+/// Relexes a token changed by the edit, as long as it stays a single token of the same kind.
+fn reparse_token(
+    token: &SyntaxToken,
+    delete: TextRange,
+    insert: &str,
+) -> Option<(GreenNode, TextRange)> {
+    let kind = token.kind();
+    if !matches!(
+        kind,
+        SyntaxKind::WHITESPACE
+            | SyntaxKind::COMMENT
+            | SyntaxKind::IDENT
+            | SyntaxKind::INT
+            | SyntaxKind::STRING
+    ) {
+        return None;
+    }
+    let text = edited_text(token.text(), token.text_range(), delete, insert);
+
+    let mut tokens = Lexer::new(&text);
+    if !matches!(
+        (tokens.next(), tokens.next()),
+        (Some(new_token), None) if new_token.kind == kind
+    ) {
+        return None;
+    }
+    let root = token.parent_ancestors().last()?;
+    if !lexes_apart(&root, token.text_range(), &text) {
+        return None;
+    }
+
+    let range = TextRange::at(token.text_range().start(), TextSize::of(text.as_str()));
+    let green = token.replace_with(GreenToken::new(CamlotLanguage::kind_to_raw(kind), &text));
+    Some((green, range))
+}
+
+/// Parses again the smallest block or module item containing the edit.
+fn reparse_node(
+    root: &SyntaxNode,
+    errors: &[SyntaxError],
+    delete: TextRange,
+    insert: &str,
+) -> Option<(GreenNode, Vec<SyntaxError>, TextRange)> {
+    let covering = match root.covering_element(delete) {
+        NodeOrToken::Node(node) => node,
+        NodeOrToken::Token(token) => token.parent()?,
+    };
+    let node = covering.ancestors().find(|node| {
+        let range = node.text_range();
+        entry_point(node.kind()).is_some()
+            && range.start() < delete.start()
+            && delete.end() < range.end()
+    })?;
+    let entry_point = entry_point(node.kind())?;
+
+    let text = edited_text(&node.text().to_string(), node.text_range(), delete, insert);
+    let first_token = node.first_token()?;
+    let mut tokens = Lexer::new(&text);
+    if tokens
+        .next()
+        .is_none_or(|token| token.kind != first_token.kind() || token.text != first_token.text())
+    {
+        // Preceding nodes could have been parsed differently looking ahead at it.
+        return None;
+    }
+    if node.kind() == SyntaxKind::BLOCK_EXPR && !is_balanced(&text) {
+        return None;
+    }
+    if !lexes_apart(root, node.text_range(), &text) {
+        return None;
+    }
+
+    if ends_with_trivia(&text) && is_followed_by_error(&node) {
+        // The trivia would be attached to the error instead, see `Sink::is_good_place_for_trivia`.
+        return None;
+    }
+
+    let parse = parse_internal(&text, entry_point);
+    let new_node = parse.syntax();
+    if new_node.kind() != node.kind() || new_node.text_range().len() != TextSize::of(text.as_str())
+    {
+        return None;
+    }
+    // A module item is parsed looking ahead at the tokens following it, unless it ends
+    // with its terminator. So do its errors, like "Expected SEMICOLON but found DEF_KW".
+    // A block is already checked to end with its closing brace.
+    if entry_point == PrefixEntryPoint::ModuleItem
+        && (!parse.errors.is_empty() || !ends_with_terminator(&new_node))
+    {
+        return None;
+    }
+
+    let (before, inside) = count_errors(root, &node);
+    let errors = errors[..before]
+        .iter()
+        .cloned()
+        .chain(parse.errors)
+        .chain(errors[before + inside..].iter().cloned())
+        .collect();
+    let range = TextRange::at(node.text_range().start(), new_node.text_range().len());
+    Some((node.replace_with(parse.green_node), errors, range))
+}
+
+fn entry_point(kind: SyntaxKind) -> Option<PrefixEntryPoint> {
+    match kind {
+        SyntaxKind::BLOCK_EXPR => Some(PrefixEntryPoint::Block),
+        SyntaxKind::DEFINITION | SyntaxKind::OPEN | SyntaxKind::TYPE_DEFINITION => {
+            Some(PrefixEntryPoint::ModuleItem)
+        }
+        _ => None,
+    }
+}
+
+fn edited_text(text: &str, range: TextRange, delete: TextRange, insert: &str) -> String {
+    let mut text = text.to_string();
+    let delete = delete - range.start();
+    text.replace_range(std::ops::Range::<usize>::from(delete), insert);
+    text
+}
+
+/// Whether the text replacing the range, lexed between the tokens around it, leaves them intact.
+fn lexes_apart(root: &SyntaxNode, range: TextRange, text: &str) -> bool {
+    // Not `SyntaxToken::prev_token`, which stops at empty nodes.
+    let prev = match root.token_at_offset(range.start()) {
+        TokenAtOffset::Between(prev, _) => Some(prev),
+        TokenAtOffset::Single(token) if token.text_range().end() == range.start() => Some(token),
+        _ => None,
+    };
+    let next = match root.token_at_offset(range.end()) {
+        TokenAtOffset::Between(_, next) => Some(next),
+        TokenAtOffset::Single(token) if token.text_range().start() == range.end() => Some(token),
+        _ => None,
+    };
+    let prev = prev.as_ref().map_or("", SyntaxToken::text);
+    let next = next.as_ref().map_or("", SyntaxToken::text);
+
+    let joined = format!("{prev}{text}{next}");
+    let boundaries: Vec<usize> = Lexer::new(&joined)
+        .scan(0, |end, token| {
+            *end += token.text.len();
+            Some(*end)
+        })
+        .collect();
+    let is_boundary = |offset: usize| offset == 0 || boundaries.contains(&offset);
+    is_boundary(prev.len()) && is_boundary(prev.len() + text.len())
+}
+
+/// Whether the block's braces only close at its end, so that it is parsed up to there.
+fn is_balanced(text: &str) -> bool {
+    let mut depth = 0_u32;
+    let mut closed = false;
+    for token in Lexer::new(text).filter(|token| !token.kind.is_trivial()) {
+        if closed {
+            return false;
+        }
+        match token.kind {
+            SyntaxKind::L_BRACE => depth += 1,
+            SyntaxKind::R_BRACE => {
+                let Some(rest) = depth.checked_sub(1) else {
+                    return false;
+                };
+                depth = rest;
+                closed = depth == 0;
+            }
+            _ => {}
+        }
+    }
+    closed
+}
+
+fn ends_with_trivia(text: &str) -> bool {
+    Lexer::new(text)
+        .last()
+        .is_some_and(|token| token.kind.is_trivial())
+}
+
+fn is_followed_by_error(node: &SyntaxNode) -> bool {
+    node.ancestors()
+        .find_map(|node| node.next_sibling_or_token())
+        .is_some_and(|next| next.kind() == SyntaxKind::ERROR)
+}
+
+fn ends_with_terminator(node: &SyntaxNode) -> bool {
+    node.descendants_with_tokens()
+        .filter_map(NodeOrToken::into_token)
+        .filter(|token| !token.kind().is_trivial())
+        .last()
+        .is_some_and(|token| matches!(token.kind(), SyntaxKind::SEMICOLON | SyntaxKind::R_BRACE))
+}
+
+/// Errors are reported in the order of the error elements of the tree.
+/// Counts those preceding the node and those inside it.
+fn count_errors(root: &SyntaxNode, node: &SyntaxNode) -> (usize, usize) {
+    let (mut before, mut inside) = (0, 0);
+    let mut entered = false;
+    for event in root.preorder_with_tokens() {
+        let element = match event {
+            WalkEvent::Enter(element) => element,
+            WalkEvent::Leave(element) => {
+                if element.as_node() == Some(node) {
+                    break;
+                }
+                continue;
+            }
+        };
+        if element.as_node() == Some(node) {
+            entered = true;
+        }
+        if matches!(element.kind(), SyntaxKind::ERROR | SyntaxKind::LEXING_ERROR) {
+            if entered {
+                inside += 1;
+            } else {
+                before += 1;
+            }
+        }
+    }
+    (before, inside)
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+    use rowan::{TextRange, TextSize};
+
+    use super::incremental_reparse;
+    use crate::parse;
+
+    /// The deleted text is marked with a pair of `$0`.
+    /// Shows the reparsed element, and checks that the result is the same as a full parse.
+    fn check(before: &str, insert: &str, expected: &Expect) {
+        let start = before.find("$0").unwrap();
+        let end = before[start + 2..].find("$0").unwrap() + start;
+        let delete = TextRange::new(
+            TextSize::try_from(start).unwrap(),
+            TextSize::try_from(end).unwrap(),
+        );
+        let before = before.replace("$0", "");
+        let mut after = before.clone();
+        after.replace_range(start..end, insert);
+
+        let old = parse(&before);
+        let full = parse(&after);
+        let actual = match incremental_reparse(&old.syntax(), &old.errors, delete, insert) {
+            Some((green, errors, range)) => {
+                let incremental = crate::Parse {
+                    green_node: green,
+                    errors,
+                };
+                assert_eq!(incremental.debug_tree(), full.debug_tree());
+                assert_eq!(incremental.errors, full.errors);
+                let element = incremental.syntax().covering_element(range);
+                format!("{:?} {:?}", element.kind(), &after[range])
+            }
+            None => "full reparse".to_string(),
+        };
+        expected.assert_eq(&actual);
+    }
+
+    #[test]
+    fn tokens_are_relexed() {
+        check(
+            "def f x = (add $0x$0 1);",
+            "xs",
+            &expect![[r#"IDENT "xs""#]],
+        );
+        check("def f = 1$0$0;", "0", &expect![[r#"INT "10""#]]);
+        check(
+            "def f = 1;$0 $0\ndef g = 2;",
+            "\n",
+            &expect![[r#"WHITESPACE "\n\n""#]],
+        );
+        check(
+            "# a $0comment$0\ndef f = 1;",
+            "note",
+            &expect![[r##"COMMENT "# a note\n""##]],
+        );
+    }
+
+    #[test]
+    fn tokens_changing_kind_are_not_relexed() {
+        check("def f x = (add $0x$0 1);", "def", &expect!["full reparse"]);
+        check(
+            "def f x = (add $0x$0 1);",
+            "x y",
+            &expect![[r#"DEFINITION "def f x = (add x y 1);""#]],
+        );
+        check(
+            "def f = x; $0# comment$0\ndef g = 1;",
+            "2",
+            &expect!["full reparse"],
+        );
+    }
+
+    #[test]
+    fn blocks_are_reparsed() {
+        check(
+            "def a = 1;\ndef f {\n    let x = 1;\n    $0x$0\n}\ndef g = 2;",
+            "(add x 1)",
+            &expect![[r#"BLOCK_EXPR "{\n    let x = 1;\n    (add x 1)\n}\n""#]],
+        );
+        check(
+            "def f {\n    let g = { 1 };\n    $0$0g\n}",
+            "let h = { 2 };\n    ",
+            &expect![[r#"BLOCK_EXPR "{\n    let g = { 1 };\n    let h = { 2 };\n    g\n}""#]],
+        );
+        check(
+            "def f {\n    let g = { $01$0 };\n    g\n}",
+            "(add 1",
+            &expect![[r#"BLOCK_EXPR "{ (add 1 }""#]],
+        );
+    }
+
+    #[test]
+    fn unbalanced_blocks_are_not_reparsed() {
+        check(
+            "def f {\n    let g = { 1 };\n    $0$0g\n}\ndef h = 1;",
+            "}",
+            &expect!["full reparse"],
+        );
+        check(
+            "def f {\n    $0$01\n}\ndef h = 1;",
+            "{",
+            &expect!["full reparse"],
+        );
+    }
+
+    #[test]
+    fn module_items_are_reparsed() {
+        check(
+            "def a = 1;\ndef f x = (add x $01$0);\ndef g = 2;",
+            "2 3",
+            &expect![[r#"DEFINITION "def f x = (add x 2 3);\n""#]],
+        );
+        check(
+            "open a;\ntype t = $0int$0;\n",
+            "int -> int",
+            &expect![[r#"TYPE_DEFINITION "type t = int -> int;\n""#]],
+        );
+    }
+
+    #[test]
+    fn module_items_with_errors_are_not_reparsed() {
+        check(
+            "def a = 1;\ndef f = (add 1 2)$0;$0\ndef g = 2;",
+            "",
+            &expect!["full reparse"],
+        );
+        check(
+            "def f = (add 1 2);\n$0def$0 g = 2;",
+            "open",
+            &expect!["full reparse"],
+        );
+    }
+
+    #[test]
+    fn errors_are_kept_in_order() {
+        check(
+            "def a = ;\ndef f {\n    let x = ;\n    $0x$0\n}\ndef g = ;",
+            "y",
+            &expect![[r#"IDENT "y""#]],
+        );
+        check(
+            "def a = ;\ndef f {\n    let x = ;\n    $0x$0\n}\ndef g = ;",
+            "let y = ;",
+            &expect![[r#"BLOCK_EXPR "{\n    let x = ;\n    let y = ;\n}\n""#]],
+        );
+    }
+}
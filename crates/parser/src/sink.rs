@@ -116,7 +116,7 @@ impl<'t, 'input> Sink<'t, 'input> {
             match event {
                 Event::Open { .. } | Event::UnmatchedOpen | Event::Advance => return true,
                 Event::OpenError { .. } | Event::CloseError => return false,
-                Event::Close => continue,
+                Event::Close => {}
             }
         }
 
@@ -42,6 +42,14 @@ impl<'t, 'input> Source<'t, 'input> {
     //     self.peek_token_raw().unwrap_or_default()
     // }
 
+    /// Kinds of the tokens from the current one on, ignoring trivia.
+    pub(crate) fn lookahead(&self) -> impl Iterator<Item = SyntaxKind> + '_ {
+        self.tokens[self.cursor..]
+            .iter()
+            .map(|token| token.kind)
+            .filter(|kind| !kind.is_trivial())
+    }
+
     fn eat_trivia(&mut self) {
         while self.at_trivia() {
             self.cursor += 1;
@@ -49,7 +57,7 @@ impl<'t, 'input> Source<'t, 'input> {
     }
 
     fn at_trivia(&self) -> bool {
-        self.peek_kind_raw().map_or(false, SyntaxKind::is_trivial)
+        self.peek_kind_raw().is_some_and(SyntaxKind::is_trivial)
     }
 
     // pub fn last_token_range(&self) -> Option<&Range<usize>> {
@@ -60,7 +68,7 @@ impl<'t, 'input> Source<'t, 'input> {
         self.peek_token_raw().map(|Token { kind, .. }| *kind)
     }
 
-    fn peek_token_raw(&self) -> Option<&Token> {
+    fn peek_token_raw(&self) -> Option<&Token<'input>> {
         self.tokens.get(self.cursor)
     }
 }
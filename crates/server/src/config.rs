@@ -0,0 +1,53 @@
+use analysis::InlayHintsConfig;
+
+/// Settings sent by the client, in the initialization options
+/// and in `workspace/didChangeConfiguration` notifications.
+#[derive(serde::Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct Config {
+    inlay_hints: InlayHintsSettings,
+}
+
+#[derive(serde::Deserialize, Debug)]
+#[serde(rename_all = "camelCase", default)]
+struct InlayHintsSettings {
+    type_hints: bool,
+    parameter_hints: bool,
+}
+
+impl Default for InlayHintsSettings {
+    fn default() -> Self {
+        let InlayHintsConfig {
+            type_hints,
+            parameter_hints,
+        } = InlayHintsConfig::default();
+        Self {
+            type_hints,
+            parameter_hints,
+        }
+    }
+}
+
+impl Config {
+    /// Reads the settings, either as they are or under the `camlot` section.
+    /// Missing settings keep their defaults, malformed ones reset all of them.
+    pub(crate) fn from_json(value: Option<serde_json::Value>) -> Self {
+        let Some(mut value) = value.filter(|value| !value.is_null()) else {
+            return Self::default();
+        };
+        if let Some(section) = value.get_mut("camlot") {
+            value = section.take();
+        }
+        serde_json::from_value(value).unwrap_or_else(|e| {
+            eprintln!("invalid configuration: {e}");
+            Self::default()
+        })
+    }
+
+    pub(crate) fn inlay_hints(&self) -> InlayHintsConfig {
+        InlayHintsConfig {
+            type_hints: self.inlay_hints.type_hints,
+            parameter_hints: self.inlay_hints.parameter_hints,
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use std::io::{Read, Write};
+use std::path::PathBuf;
+
+/// Formats the files in place, or the standard input to the standard output without files.
+/// With `check`, only reports what is not formatted.
+/// Returns `false` if some input has syntax errors or, with `check`, is not formatted.
+pub(crate) fn main(check: bool, files: &[PathBuf]) -> bool {
+    if files.is_empty() {
+        let mut input = String::new();
+        if let Err(e) = std::io::stdin().read_to_string(&mut input) {
+            eprintln!("failed to read stdin: {e}");
+            return false;
+        }
+        let Some(formatted) = formatter::format(&input) else {
+            eprintln!("<stdin>: cannot format code with syntax errors");
+            return false;
+        };
+        if check {
+            if formatted != input {
+                eprintln!("<stdin> is not formatted");
+                return false;
+            }
+        } else {
+            print!("{formatted}");
+            std::io::stdout().flush().expect("stdout");
+        }
+        return true;
+    }
+
+    let mut ok = true;
+    for path in files {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) => {
+                eprintln!("{}: {e}", path.display());
+                ok = false;
+                continue;
+            }
+        };
+        let Some(formatted) = formatter::format(&text) else {
+            eprintln!("{}: cannot format code with syntax errors", path.display());
+            ok = false;
+            continue;
+        };
+        if formatted == text {
+            continue;
+        }
+        if check {
+            println!("{}", path.display());
+            ok = false;
+        } else if let Err(e) = std::fs::write(path, formatted) {
+            eprintln!("{}: {e}", path.display());
+            ok = false;
+        }
+    }
+    ok
+}
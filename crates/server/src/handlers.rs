@@ -7,9 +7,16 @@ use lsp_types::{
     DocumentDiagnosticReport, DocumentDiagnosticReportResult, PublishDiagnosticsParams,
 };
 
-use analysis::{get_diagnostics, get_semantic_tokens};
+use analysis::{
+    get_completions, get_definition, get_diagnostics, get_document_highlights,
+    get_document_symbols, get_folding_ranges, get_formatting, get_hover, get_inlay_hints,
+    get_range_formatting, get_references, get_selection_ranges, get_semantic_tokens,
+    get_signature_help, get_type_definition, prepare_rename, rename, Document, RenameError,
+};
 
+use crate::config::Config;
 use crate::server::{Context, Server};
+use crate::workspace;
 
 pub(crate) fn handle_document_diagnostic_request(
     req: &lsp_types::DocumentDiagnosticParams,
@@ -21,7 +28,7 @@ pub(crate) fn handle_document_diagnostic_request(
         .get_document(&path)
         .ok_or_else(|| doc_not_found_error(&path))?;
 
-    let diagnostics = get_diagnostics(doc);
+    let diagnostics = get_diagnostics(doc, &req.text_document.uri);
     Ok(DocumentDiagnosticReportResult::Report(
         DocumentDiagnosticReport::Full(lsp_types::RelatedFullDocumentDiagnosticReport {
             related_documents: None,
@@ -42,10 +49,11 @@ pub(crate) fn handle_did_open_text_document_params(
         params.text_document.uri.path().to_string(),
         params.text_document.text,
     );
+    ctx.reindex(&params.text_document.uri);
 
     let doc = ctx.get_document(params.text_document.uri.path()).unwrap();
 
-    let diagnostics = get_diagnostics(doc);
+    let diagnostics = get_diagnostics(doc, &params.text_document.uri);
     let params = PublishDiagnosticsParams {
         uri: params.text_document.uri,
         diagnostics,
@@ -59,12 +67,10 @@ pub(crate) fn handle_did_change_text_document_params(
     lsp: &Server,
     ctx: &mut Context,
 ) {
-    ctx.update_document(
-        params.text_document.uri.path(),
-        params.content_changes.last().unwrap().text.clone(),
-    );
+    ctx.update_document(params.text_document.uri.path(), params.content_changes);
+    ctx.reindex(&params.text_document.uri);
     let doc = ctx.get_document(params.text_document.uri.path()).unwrap();
-    let diagnostics = get_diagnostics(doc);
+    let diagnostics = get_diagnostics(doc, &params.text_document.uri);
     let params = PublishDiagnosticsParams {
         uri: params.text_document.uri,
         diagnostics,
@@ -79,6 +85,32 @@ pub(crate) fn handle_did_close_text_document_params(
     ctx: &mut Context,
 ) {
     ctx.remove_document(params.text_document.uri.path());
+    // Unsaved changes are discarded, the module is back to its contents on the disk.
+    ctx.reindex(&params.text_document.uri);
+}
+
+pub(crate) fn handle_did_change_watched_files_params(
+    params: lsp_types::DidChangeWatchedFilesParams,
+    _lsp: &Server,
+    ctx: &mut Context,
+) {
+    let modules = params.changes.into_iter().filter(|change| {
+        change
+            .uri
+            .to_file_path()
+            .is_ok_and(|path| workspace::is_module(&path))
+    });
+    for change in modules {
+        ctx.reindex(&change.uri);
+    }
+}
+
+pub(crate) fn handle_did_change_configuration_params(
+    params: lsp_types::DidChangeConfigurationParams,
+    _lsp: &Server,
+    ctx: &mut Context,
+) {
+    ctx.set_config(Config::from_json(Some(params.settings)));
 }
 
 pub(crate) enum SyntaxTree {}
@@ -122,6 +154,289 @@ pub(crate) fn handle_semantic_tokens_full_request(
     )))
 }
 
+pub(crate) fn handle_document_symbol_request(
+    req: &lsp_types::DocumentSymbolParams,
+    _lsp: &Server,
+    ctx: &Context,
+) -> Result<Option<lsp_types::DocumentSymbolResponse>, ResponseError> {
+    let path = req.text_document.uri.path().to_string();
+    let doc = ctx
+        .get_document(&path)
+        .ok_or_else(|| doc_not_found_error(&path))?;
+
+    Ok(Some(lsp_types::DocumentSymbolResponse::Nested(
+        get_document_symbols(doc),
+    )))
+}
+
+pub(crate) fn handle_folding_range_request(
+    req: &lsp_types::FoldingRangeParams,
+    _lsp: &Server,
+    ctx: &Context,
+) -> Result<Option<Vec<lsp_types::FoldingRange>>, ResponseError> {
+    let path = req.text_document.uri.path().to_string();
+    let doc = ctx
+        .get_document(&path)
+        .ok_or_else(|| doc_not_found_error(&path))?;
+
+    Ok(Some(get_folding_ranges(doc)))
+}
+
+pub(crate) fn handle_selection_range_request(
+    req: &lsp_types::SelectionRangeParams,
+    _lsp: &Server,
+    ctx: &Context,
+) -> Result<Option<Vec<lsp_types::SelectionRange>>, ResponseError> {
+    let path = req.text_document.uri.path().to_string();
+    let doc = ctx
+        .get_document(&path)
+        .ok_or_else(|| doc_not_found_error(&path))?;
+
+    Ok(Some(get_selection_ranges(doc, &req.positions)))
+}
+
+pub(crate) fn handle_workspace_symbol_request(
+    req: &lsp_types::WorkspaceSymbolParams,
+    _lsp: &Server,
+    ctx: &Context,
+) -> Result<Option<lsp_types::WorkspaceSymbolResponse>, ResponseError> {
+    Ok(Some(lsp_types::WorkspaceSymbolResponse::Flat(
+        ctx.symbol_index().search(&req.query),
+    )))
+}
+
+pub(crate) fn handle_hover_request(
+    req: &lsp_types::HoverParams,
+    _lsp: &Server,
+    ctx: &Context,
+) -> Result<Option<lsp_types::Hover>, ResponseError> {
+    let params = &req.text_document_position_params;
+    let path = params.text_document.uri.path().to_string();
+    let doc = ctx
+        .get_document(&path)
+        .ok_or_else(|| doc_not_found_error(&path))?;
+
+    Ok(get_hover(doc, params.position))
+}
+
+pub(crate) fn handle_definition_request(
+    req: &lsp_types::GotoDefinitionParams,
+    _lsp: &Server,
+    ctx: &Context,
+) -> Result<Option<lsp_types::GotoDefinitionResponse>, ResponseError> {
+    let params = &req.text_document_position_params;
+    let path = params.text_document.uri.path().to_string();
+    let doc = ctx
+        .get_document(&path)
+        .ok_or_else(|| doc_not_found_error(&path))?;
+
+    Ok(get_definition(
+        doc,
+        &params.text_document.uri,
+        params.position,
+    ))
+}
+
+pub(crate) fn handle_type_definition_request(
+    req: &lsp_types::request::GotoTypeDefinitionParams,
+    _lsp: &Server,
+    ctx: &Context,
+) -> Result<Option<lsp_types::request::GotoTypeDefinitionResponse>, ResponseError> {
+    let params = &req.text_document_position_params;
+    let path = params.text_document.uri.path().to_string();
+    let doc = ctx
+        .get_document(&path)
+        .ok_or_else(|| doc_not_found_error(&path))?;
+
+    Ok(get_type_definition(
+        doc,
+        &params.text_document.uri,
+        params.position,
+    ))
+}
+
+pub(crate) fn handle_references_request(
+    req: &lsp_types::ReferenceParams,
+    _lsp: &Server,
+    ctx: &Context,
+) -> Result<Option<Vec<lsp_types::Location>>, ResponseError> {
+    let params = &req.text_document_position;
+    let path = params.text_document.uri.path().to_string();
+    let doc = ctx
+        .get_document(&path)
+        .ok_or_else(|| doc_not_found_error(&path))?;
+
+    Ok(get_references(
+        doc,
+        &params.text_document.uri,
+        params.position,
+        req.context.include_declaration,
+    ))
+}
+
+pub(crate) fn handle_document_highlight_request(
+    req: &lsp_types::DocumentHighlightParams,
+    _lsp: &Server,
+    ctx: &Context,
+) -> Result<Option<Vec<lsp_types::DocumentHighlight>>, ResponseError> {
+    let params = &req.text_document_position_params;
+    let path = params.text_document.uri.path().to_string();
+    let doc = ctx
+        .get_document(&path)
+        .ok_or_else(|| doc_not_found_error(&path))?;
+
+    Ok(get_document_highlights(doc, params.position))
+}
+
+pub(crate) fn handle_completion_request(
+    req: &lsp_types::CompletionParams,
+    _lsp: &Server,
+    ctx: &Context,
+) -> Result<Option<lsp_types::CompletionResponse>, ResponseError> {
+    let params = &req.text_document_position;
+    let path = params.text_document.uri.path().to_string();
+    let doc = ctx
+        .get_document(&path)
+        .ok_or_else(|| doc_not_found_error(&path))?;
+
+    Ok(get_completions(doc, params.position).map(lsp_types::CompletionResponse::Array))
+}
+
+pub(crate) fn handle_signature_help_request(
+    req: &lsp_types::SignatureHelpParams,
+    _lsp: &Server,
+    ctx: &Context,
+) -> Result<Option<lsp_types::SignatureHelp>, ResponseError> {
+    let params = &req.text_document_position_params;
+    let path = params.text_document.uri.path().to_string();
+    let doc = ctx
+        .get_document(&path)
+        .ok_or_else(|| doc_not_found_error(&path))?;
+
+    Ok(get_signature_help(doc, params.position))
+}
+
+pub(crate) fn handle_inlay_hint_request(
+    req: &lsp_types::InlayHintParams,
+    _lsp: &Server,
+    ctx: &Context,
+) -> Result<Option<Vec<lsp_types::InlayHint>>, ResponseError> {
+    let path = req.text_document.uri.path().to_string();
+    let doc = ctx
+        .get_document(&path)
+        .ok_or_else(|| doc_not_found_error(&path))?;
+
+    Ok(Some(get_inlay_hints(
+        doc,
+        req.range,
+        &ctx.config().inlay_hints(),
+    )))
+}
+
+pub(crate) fn handle_formatting_request(
+    req: &lsp_types::DocumentFormattingParams,
+    _lsp: &Server,
+    ctx: &Context,
+) -> Result<Option<Vec<lsp_types::TextEdit>>, ResponseError> {
+    let path = req.text_document.uri.path().to_string();
+    let doc = ctx
+        .get_document(&path)
+        .ok_or_else(|| doc_not_found_error(&path))?;
+
+    Ok(get_formatting(doc))
+}
+
+pub(crate) fn handle_range_formatting_request(
+    req: &lsp_types::DocumentRangeFormattingParams,
+    _lsp: &Server,
+    ctx: &Context,
+) -> Result<Option<Vec<lsp_types::TextEdit>>, ResponseError> {
+    let path = req.text_document.uri.path().to_string();
+    let doc = ctx
+        .get_document(&path)
+        .ok_or_else(|| doc_not_found_error(&path))?;
+
+    Ok(get_range_formatting(doc, req.range))
+}
+
+pub(crate) fn handle_prepare_rename_request(
+    req: &lsp_types::TextDocumentPositionParams,
+    _lsp: &Server,
+    ctx: &Context,
+) -> Result<Option<lsp_types::PrepareRenameResponse>, ResponseError> {
+    let path = req.text_document.uri.path().to_string();
+    let doc = ctx
+        .get_document(&path)
+        .ok_or_else(|| doc_not_found_error(&path))?;
+
+    match prepare_rename(doc, req.position) {
+        Ok(response) => Ok(Some(response)),
+        Err(RenameError::NoIdentifier) => Ok(None),
+        Err(error) => Err(rename_error(&error)),
+    }
+}
+
+pub(crate) fn handle_rename_request(
+    req: &lsp_types::RenameParams,
+    _lsp: &Server,
+    ctx: &Context,
+) -> Result<Option<lsp_types::WorkspaceEdit>, ResponseError> {
+    let params = &req.text_document_position;
+    let uri = &params.text_document.uri;
+    let path = uri.path().to_string();
+    let doc = ctx
+        .get_document(&path)
+        .ok_or_else(|| doc_not_found_error(&path))?;
+
+    let siblings = sibling_modules(uri);
+    let loaded: Vec<_> = siblings
+        .iter()
+        .filter(|(uri, _)| ctx.get_document(uri.path()).is_none())
+        .filter_map(|(uri, path)| {
+            let text = std::fs::read_to_string(path).ok()?;
+            Some((uri.clone(), Document::new(text)))
+        })
+        .collect();
+    let workspace: Vec<_> = siblings
+        .iter()
+        .filter_map(|(uri, _)| Some((uri.clone(), ctx.get_document(uri.path())?)))
+        .chain(loaded.iter().map(|(uri, doc)| (uri.clone(), doc)))
+        .collect();
+
+    rename(doc, uri, params.position, &req.new_name, &workspace)
+        .map(Some)
+        .map_err(|error| rename_error(&error))
+}
+
+/// Modules in the directory of the file, which are the only ones able to open it.
+fn sibling_modules(uri: &lsp_types::Url) -> Vec<(lsp_types::Url, std::path::PathBuf)> {
+    let Some(dir) = uri
+        .to_file_path()
+        .ok()
+        .and_then(|path| path.parent().map(std::path::Path::to_path_buf))
+    else {
+        return Vec::new();
+    };
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| workspace::is_module(path))
+        .filter_map(|path| Some((lsp_types::Url::from_file_path(&path).ok()?, path)))
+        .collect()
+}
+
+fn rename_error(error: &RenameError) -> ResponseError {
+    ResponseError {
+        code: lsp_server::ErrorCode::RequestFailed as i32,
+        message: error.message(),
+        data: None,
+    }
+}
+
 fn doc_not_found_error(path: &str) -> ResponseError {
     ResponseError {
         code: 0,
@@ -26,6 +26,65 @@ pub(crate) fn main() -> Result<(), Box<dyn Error + Sync + Send>> {
         handlers::handle_semantic_tokens_full_request,
     );
 
+    server_builder.register_request::<lsp_types::request::DocumentSymbolRequest, _>(
+        handlers::handle_document_symbol_request,
+    );
+
+    server_builder.register_request::<lsp_types::request::FoldingRangeRequest, _>(
+        handlers::handle_folding_range_request,
+    );
+
+    server_builder.register_request::<lsp_types::request::SelectionRangeRequest, _>(
+        handlers::handle_selection_range_request,
+    );
+
+    server_builder.register_request::<lsp_types::request::WorkspaceSymbolRequest, _>(
+        handlers::handle_workspace_symbol_request,
+    );
+
+    server_builder
+        .register_request::<lsp_types::request::HoverRequest, _>(handlers::handle_hover_request);
+
+    server_builder.register_request::<lsp_types::request::GotoDefinition, _>(
+        handlers::handle_definition_request,
+    );
+
+    server_builder.register_request::<lsp_types::request::GotoTypeDefinition, _>(
+        handlers::handle_type_definition_request,
+    );
+
+    server_builder
+        .register_request::<lsp_types::request::References, _>(handlers::handle_references_request);
+
+    server_builder.register_request::<lsp_types::request::DocumentHighlightRequest, _>(
+        handlers::handle_document_highlight_request,
+    );
+
+    server_builder
+        .register_request::<lsp_types::request::Completion, _>(handlers::handle_completion_request);
+
+    server_builder.register_request::<lsp_types::request::SignatureHelpRequest, _>(
+        handlers::handle_signature_help_request,
+    );
+
+    server_builder
+        .register_request::<lsp_types::request::Formatting, _>(handlers::handle_formatting_request);
+
+    server_builder.register_request::<lsp_types::request::RangeFormatting, _>(
+        handlers::handle_range_formatting_request,
+    );
+
+    server_builder.register_request::<lsp_types::request::PrepareRenameRequest, _>(
+        handlers::handle_prepare_rename_request,
+    );
+
+    server_builder
+        .register_request::<lsp_types::request::Rename, _>(handlers::handle_rename_request);
+
+    server_builder.register_request::<lsp_types::request::InlayHintRequest, _>(
+        handlers::handle_inlay_hint_request,
+    );
+
     server_builder.register_notification::<lsp_types::notification::DidOpenTextDocument, _>(
         handlers::handle_did_open_text_document_params,
     );
@@ -38,28 +97,17 @@ pub(crate) fn main() -> Result<(), Box<dyn Error + Sync + Send>> {
         handlers::handle_did_close_text_document_params,
     );
 
-    let server_capabilities = ServerCapabilities {
-        text_document_sync: Some(lsp_types::TextDocumentSyncCapability::Kind(
-            lsp_types::TextDocumentSyncKind::FULL,
-        )),
-        semantic_tokens_provider: Some(
-            lsp_types::SemanticTokensOptions {
-                legend: lsp_types::SemanticTokensLegend {
-                    token_types: analysis::SUPPORTED_TOKENS.to_vec(),
-                    token_modifiers: vec![],
-                },
-                full: Some(lsp_types::SemanticTokensFullOptions::Bool(true)),
-                range: Some(false),
-                work_done_progress_options: WorkDoneProgressOptions::default(),
-            }
-            .into(),
-        ),
-        ..Default::default()
-    };
+    server_builder.register_notification::<lsp_types::notification::DidChangeConfiguration, _>(
+        handlers::handle_did_change_configuration_params,
+    );
+
+    server_builder.register_notification::<lsp_types::notification::DidChangeWatchedFiles, _>(
+        handlers::handle_did_change_watched_files_params,
+    );
 
     let server = server_builder.build(connection);
 
-    let params = match server.initialize(server_capabilities) {
+    let params = match server.initialize(server_capabilities()) {
         Ok(params) => params,
         Err(e) => {
             if e.channel_is_disconnected() {
@@ -76,3 +124,47 @@ pub(crate) fn main() -> Result<(), Box<dyn Error + Sync + Send>> {
     eprintln!("shutting down server");
     Ok(())
 }
+
+fn server_capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(lsp_types::TextDocumentSyncCapability::Kind(
+            lsp_types::TextDocumentSyncKind::INCREMENTAL,
+        )),
+        semantic_tokens_provider: Some(
+            lsp_types::SemanticTokensOptions {
+                legend: lsp_types::SemanticTokensLegend {
+                    token_types: analysis::SUPPORTED_TOKENS.to_vec(),
+                    token_modifiers: vec![],
+                },
+                full: Some(lsp_types::SemanticTokensFullOptions::Bool(true)),
+                range: Some(false),
+                work_done_progress_options: WorkDoneProgressOptions::default(),
+            }
+            .into(),
+        ),
+        document_symbol_provider: Some(lsp_types::OneOf::Left(true)),
+        workspace_symbol_provider: Some(lsp_types::OneOf::Left(true)),
+        folding_range_provider: Some(lsp_types::FoldingRangeProviderCapability::Simple(true)),
+        selection_range_provider: Some(lsp_types::SelectionRangeProviderCapability::Simple(true)),
+        hover_provider: Some(lsp_types::HoverProviderCapability::Simple(true)),
+        definition_provider: Some(lsp_types::OneOf::Left(true)),
+        type_definition_provider: Some(lsp_types::TypeDefinitionProviderCapability::Simple(true)),
+        references_provider: Some(lsp_types::OneOf::Left(true)),
+        document_highlight_provider: Some(lsp_types::OneOf::Left(true)),
+        completion_provider: Some(lsp_types::CompletionOptions::default()),
+        // Arguments of curried applications are separated by spaces.
+        signature_help_provider: Some(lsp_types::SignatureHelpOptions {
+            trigger_characters: Some(vec!["(".into()]),
+            retrigger_characters: Some(vec![" ".into()]),
+            work_done_progress_options: WorkDoneProgressOptions::default(),
+        }),
+        document_formatting_provider: Some(lsp_types::OneOf::Left(true)),
+        document_range_formatting_provider: Some(lsp_types::OneOf::Left(true)),
+        rename_provider: Some(lsp_types::OneOf::Right(lsp_types::RenameOptions {
+            prepare_provider: Some(true),
+            work_done_progress_options: WorkDoneProgressOptions::default(),
+        })),
+        inlay_hint_provider: Some(lsp_types::OneOf::Left(true)),
+        ..Default::default()
+    }
+}
@@ -1,9 +1,13 @@
 use std::io::Read;
 
+mod config;
+mod format;
 mod handlers;
 mod lsp;
 mod lsp_utils;
+mod repl;
 mod server;
+mod workspace;
 
 fn main() {
     let mode = parse_args();
@@ -11,14 +15,19 @@ fn main() {
 
     match mode {
         Mode::Parse => parse_input(),
-        Mode::REPL => {
-            eprintln!("Repl mode is not yet implemented.");
-            std::process::exit(1);
-        }
+        Mode::REPL => match repl::main() {
+            Ok(()) => (),
+            Err(e) => eprintln!("REPL failed: {e}"),
+        },
         Mode::LSP => match lsp::main() {
             Ok(()) => (),
             Err(e) => eprintln!("LSP failed: {e}"),
         },
+        Mode::Format { check, files } => {
+            if !format::main(check, &files) {
+                std::process::exit(1);
+            }
+        }
     }
 }
 
@@ -28,6 +37,11 @@ enum Mode {
     Parse,
     REPL,
     LSP,
+    /// `fmt [--check] [FILE]...`
+    Format {
+        check: bool,
+        files: Vec<std::path::PathBuf>,
+    },
 }
 
 fn parse_args() -> Mode {
@@ -40,6 +54,14 @@ fn parse_args() -> Mode {
             "repl" => Mode::REPL,
             "parse" => Mode::Parse,
             "lsp" => Mode::LSP,
+            "fmt" => Mode::Format {
+                check: args[2..].iter().any(|arg| arg == "--check"),
+                files: args[2..]
+                    .iter()
+                    .filter(|arg| *arg != "--check")
+                    .map(Into::into)
+                    .collect(),
+            },
             _ => Mode::REPL,
         }
     }
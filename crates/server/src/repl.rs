@@ -0,0 +1,578 @@
+use std::error::Error;
+use std::fmt::Write as _;
+use std::io::{BufRead, Write as _};
+
+use core::{infer, DefinitionIdx, InferenceResult, Interpreter, LowerError, Module, TypePrinter};
+use parser::{nodes, AstNode, SyntaxKind, SyntaxNode};
+
+const HELP: &str = "\
+Enter definitions (`def`, `type`, `open`) or expressions, terminated with `;` or `}`.
+Commands:
+  :type <expr>    show the type of an expression
+  :tree <expr>    show the syntax tree of an expression
+  :hir <expr>     show the lowered expression
+  :load <file>    load definitions from a file
+  :reset          forget all definitions
+  :help           show this message
+  :quit           exit the REPL";
+
+/// Reported when the wrapped expression does not parse as a definition.
+const NO_EXPR: &str = "expected an expression";
+
+/// Name of the definition wrapping evaluated expressions, which must lex as an identifier.
+/// Underscores are appended while it is already defined.
+const EXPR_NAME: &str = "_it";
+
+pub(crate) fn main() -> Result<(), Box<dyn Error + Sync + Send>> {
+    println!("Camlot REPL, type :help for help.");
+    let mut repl = Repl::default();
+    let mut input = String::new();
+    let mut stdin = std::io::stdin().lock();
+
+    loop {
+        print!("{}", if input.is_empty() { "> " } else { "| " });
+        std::io::stdout().flush()?;
+
+        let mut line = String::new();
+        if stdin.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        input.push_str(&line);
+        if input.trim().is_empty() {
+            input.clear();
+            continue;
+        }
+        if !is_complete(&input) {
+            continue;
+        }
+
+        let entry = std::mem::take(&mut input);
+        match repl.handle(entry.trim()) {
+            Some(output) => print!("{output}"),
+            None => return Ok(()),
+        }
+    }
+}
+
+/// Commands fit in a single line, other input ends with `;` or `}` outside of any brackets.
+fn is_complete(input: &str) -> bool {
+    let input = input.trim();
+    if input.starts_with(':') {
+        return true;
+    }
+
+    let mut depth = 0;
+    for line in input.lines() {
+        let code = line.split('#').next().unwrap_or_default();
+        for char in code.chars() {
+            match char {
+                '(' | '{' => depth += 1,
+                ')' | '}' => depth -= 1,
+                _ => {}
+            }
+        }
+    }
+    let last_line = input.lines().last().unwrap_or_default();
+    let code = last_line.split('#').next().unwrap_or_default().trim_end();
+    depth <= 0 && (code.ends_with(';') || code.ends_with('}'))
+}
+
+fn is_module_item(input: &str) -> bool {
+    matches!(
+        input.split_whitespace().next(),
+        Some("def" | "type" | "open")
+    )
+}
+
+#[derive(Default)]
+struct Repl {
+    /// All accepted module items, without the ones redefined later.
+    items: Vec<Item>,
+}
+
+/// Module item accepted by the REPL.
+#[derive(Clone)]
+struct Item {
+    /// Kind and name of a definition or a type definition, which later items with the same
+    /// kind and name replace.
+    key: Option<(SyntaxKind, String)>,
+    text: String,
+}
+
+impl Item {
+    fn new(item: &nodes::ModuleItem) -> Self {
+        let name = match item {
+            nodes::ModuleItem::Definition(definition) => definition.ident_lit(),
+            nodes::ModuleItem::TypeDefinition(type_definition) => type_definition.ident_lit(),
+            nodes::ModuleItem::Open(_) => None,
+        };
+        Self {
+            key: name.map(|name| (item.syntax().kind(), name.text().to_string())),
+            text: item.syntax().text().to_string(),
+        }
+    }
+}
+
+/// Source parsed and analysed as a whole.
+struct Analysis {
+    root: SyntaxNode,
+    module: Module,
+    inference: InferenceResult,
+    syntax_errors: Vec<String>,
+    semantic_errors: Vec<String>,
+}
+
+impl Analysis {
+    fn new(text: &str) -> Self {
+        let parse = parser::parse(text);
+        let mut module = Module::new();
+        module.lower_module(&parse.module());
+        let inference = infer(&module);
+        let semantic_errors = module
+            .lower_errors()
+            .iter()
+            .map(LowerError::message)
+            .chain(
+                inference
+                    .errors()
+                    .iter()
+                    .map(|error| error.message(&module)),
+            )
+            .collect();
+
+        Self {
+            root: parse.syntax(),
+            syntax_errors: parse
+                .errors
+                .into_iter()
+                .map(|error| error.message)
+                .collect(),
+            semantic_errors,
+            module,
+            inference,
+        }
+    }
+
+    fn errors(&self) -> impl Iterator<Item = &String> {
+        self.syntax_errors.iter().chain(&self.semantic_errors)
+    }
+
+    fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+
+    fn report_errors(&self, output: &mut String) {
+        for error in self.errors() {
+            writeln!(output, "error: {error}").unwrap();
+        }
+    }
+
+    fn definition_named(&self, name: &str) -> Option<DefinitionIdx> {
+        self.module
+            .definitions()
+            .find(|(_, definition)| self.module.lookup_name(definition.name) == name)
+            .map(|(idx, _)| idx)
+    }
+
+    fn definition_type(&self, definition: DefinitionIdx) -> String {
+        let typ = self.inference.type_of_definition(definition).unwrap();
+        TypePrinter::new(&self.module).print(typ)
+    }
+}
+
+impl Repl {
+    /// Handles a complete input, returning text to print or `None` to quit.
+    fn handle(&mut self, input: &str) -> Option<String> {
+        let output = if let Some(command) = input.strip_prefix(':') {
+            let (command, arg) = command.split_once(' ').unwrap_or((command, ""));
+            match command {
+                "type" => self.show_type(arg),
+                "tree" => self.show_tree(arg),
+                "hir" => self.show_hir(arg),
+                "load" => match std::fs::read_to_string(arg.trim()) {
+                    Ok(text) => self.add_items(&text),
+                    Err(error) => format!("error: cannot read `{}`: {error}\n", arg.trim()),
+                },
+                "reset" => {
+                    self.items.clear();
+                    "Definitions cleared.\n".into()
+                }
+                "help" => format!("{HELP}\n"),
+                "quit" | "q" => return None,
+                _ => format!("error: unknown command `:{command}`, type :help for help\n"),
+            }
+        } else if is_module_item(input) {
+            self.add_items(input)
+        } else {
+            self.eval(input)
+        };
+        Some(output)
+    }
+
+    /// Source of the items followed by `text`.
+    fn source(items: &[Item], text: &str) -> String {
+        let mut source = String::new();
+        for item in items {
+            writeln!(source, "{}", item.text).unwrap();
+        }
+        writeln!(source, "{text}").unwrap();
+        source
+    }
+
+    /// Accepts the items if they are well-typed together with the earlier ones,
+    /// which they replace if they have the same name.
+    fn add_items(&mut self, text: &str) -> String {
+        let added: Vec<_> = parser::parse(text)
+            .module()
+            .module_items()
+            .map(|item| Item::new(&item))
+            .collect();
+        let mut items: Vec<_> = self
+            .items
+            .iter()
+            .filter(|item| item.key.is_none() || added.iter().all(|added| added.key != item.key))
+            .cloned()
+            .collect();
+
+        let analysis = Analysis::new(&Self::source(&items, text));
+        let mut output = String::new();
+        if analysis.has_errors() {
+            analysis.report_errors(&mut output);
+            return output;
+        }
+        let added_definitions = added
+            .iter()
+            .filter(|item| matches!(item.key, Some((SyntaxKind::DEFINITION, _))))
+            .count();
+        items.extend(added);
+        self.items = items;
+
+        // The added definitions are the last ones.
+        let skipped = analysis.module.definitions().count() - added_definitions;
+        let mut interpreter = Interpreter::new(&analysis.module);
+        for (idx, definition) in analysis.module.definitions().skip(skipped) {
+            let name = analysis.module.lookup_name(definition.name);
+            let typ = analysis.definition_type(idx);
+            match interpreter.eval_definition(idx) {
+                Ok(value) => {
+                    let value = value.display(&analysis.module);
+                    writeln!(output, "{name} : {typ} = {value}").unwrap();
+                }
+                Err(error) => {
+                    let message = error.message(&analysis.module);
+                    writeln!(output, "{name} : {typ}\nerror: {message}").unwrap();
+                }
+            }
+        }
+        output
+    }
+
+    /// Analyses the expression as a definition added after all accepted items,
+    /// returned unless the expression failed to parse.
+    fn analyse_expr(&self, expr: &str) -> (Analysis, Option<DefinitionIdx>) {
+        let mut name = EXPR_NAME.to_string();
+        while self
+            .items
+            .iter()
+            .any(|item| matches!(&item.key, Some((SyntaxKind::DEFINITION, key)) if *key == name))
+        {
+            name.push('_');
+        }
+
+        let expr = expr.trim().trim_end_matches(';');
+        let analysis = Analysis::new(&Self::source(&self.items, &format!("def {name} = {expr};")));
+        let definition = analysis.definition_named(&name);
+        (analysis, definition)
+    }
+
+    fn eval(&self, expr: &str) -> String {
+        let (analysis, definition) = self.analyse_expr(expr);
+        let mut output = String::new();
+        if analysis.has_errors() {
+            analysis.report_errors(&mut output);
+            return output;
+        }
+
+        let Some(definition) = definition else {
+            return format!("error: {NO_EXPR}\n");
+        };
+        let typ = analysis.definition_type(definition);
+        match Interpreter::new(&analysis.module).eval_definition(definition) {
+            Ok(value) => {
+                let value = value.display(&analysis.module);
+                writeln!(output, "{value} : {typ}").unwrap();
+            }
+            Err(error) => writeln!(output, "error: {}", error.message(&analysis.module)).unwrap(),
+        }
+        output
+    }
+
+    fn show_type(&self, expr: &str) -> String {
+        let (analysis, definition) = self.analyse_expr(expr);
+        let mut output = String::new();
+        analysis.report_errors(&mut output);
+        if !analysis.syntax_errors.is_empty() {
+            return output;
+        }
+
+        let Some(definition) = definition else {
+            writeln!(output, "error: {NO_EXPR}").unwrap();
+            return output;
+        };
+        let typ = analysis.definition_type(definition);
+        writeln!(output, "{} : {typ}", expr.trim().trim_end_matches(';')).unwrap();
+        output
+    }
+
+    fn show_tree(&self, expr: &str) -> String {
+        let (analysis, definition) = self.analyse_expr(expr);
+        let mut output = String::new();
+        for error in &analysis.syntax_errors {
+            writeln!(output, "error: {error}").unwrap();
+        }
+
+        // The expression is wrapped in the last definition, if it parsed.
+        let body = definition
+            .and_then(|_| {
+                analysis
+                    .root
+                    .children()
+                    .filter_map(nodes::Definition::cast)
+                    .last()
+            })
+            .and_then(|definition| definition.def_body())
+            .and_then(|body| body.expr());
+        if let Some(body) = body {
+            // A fresh root, so that offsets are relative to the expression.
+            let tree = SyntaxNode::new_root(body.syntax().green().into_owned());
+            write!(output, "{tree:#?}").unwrap();
+        }
+        output
+    }
+
+    fn show_hir(&self, expr: &str) -> String {
+        let (analysis, definition) = self.analyse_expr(expr);
+        let mut output = String::new();
+        for error in &analysis.syntax_errors {
+            writeln!(output, "error: {error}").unwrap();
+        }
+
+        let Some(definition) = definition else {
+            writeln!(output, "error: {NO_EXPR}").unwrap();
+            return output;
+        };
+        let defn = analysis.module.get_definition(definition).defn;
+        output.push_str(&analysis.module.debug_expr(defn));
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write;
+
+    use expect_test::{expect, Expect};
+
+    use super::{is_complete, Repl, EXPR_NAME};
+
+    /// Feeds the inputs to a fresh REPL, printing each one with its output.
+    fn check(inputs: &[&str], expected: &Expect) {
+        let mut repl = Repl::default();
+        let mut actual = String::new();
+        for input in inputs {
+            let output = repl.handle(input).unwrap_or_else(|| "quit\n".into());
+            write!(actual, "> {input}\n{output}").unwrap();
+        }
+        expected.assert_eq(&actual);
+    }
+
+    #[test]
+    fn expr_name_is_an_identifier() {
+        assert!(parser::is_identifier(EXPR_NAME));
+    }
+
+    #[test]
+    fn complete_inputs() {
+        assert!(is_complete(":type 1"));
+        assert!(is_complete("1 + 2;"));
+        assert!(is_complete("def f x {\n    x\n}"));
+        assert!(is_complete("def f = match x {\n    _ => 1,\n};"));
+        assert!(!is_complete("def f x {"));
+        assert!(!is_complete("def f = (add 1;"));
+        assert!(!is_complete("def f = 1 # ;"));
+    }
+
+    #[test]
+    fn evaluate_expressions() {
+        check(
+            &[
+                "1 + 2;",
+                "if true { 1 } else { 2 };",
+                "(add 1);",
+                "1 / 0;",
+                "x;",
+            ],
+            &expect![[r"
+                > 1 + 2;
+                3 : int
+                > if true { 1 } else { 2 };
+                1 : int
+                > (add 1);
+                <builtin add> : int -> int
+                > 1 / 0;
+                error: division by zero
+                > x;
+                error: unbound variable `x`
+            "]],
+        );
+    }
+
+    #[test]
+    fn definitions_are_kept() {
+        check(
+            &[
+                "type option a = None | Some a;",
+                "def unwrap o = match o { Some x => x, _ => 0 };",
+                "(unwrap (Some 4));",
+                ":reset",
+                "(unwrap (Some 4));",
+            ],
+            &expect![[r"
+                > type option a = None | Some a;
+                > def unwrap o = match o { Some x => x, _ => 0 };
+                unwrap : option int -> int = <function>
+                > (unwrap (Some 4));
+                4 : int
+                > :reset
+                Definitions cleared.
+                > (unwrap (Some 4));
+                error: unbound variable `unwrap`
+                error: unbound variable `Some`
+            "]],
+        );
+    }
+
+    #[test]
+    fn commands() {
+        check(
+            &[
+                "def id x = x;",
+                ":type (id 1)",
+                ":hir 1 + 2",
+                ":tree 1",
+                ":type (",
+                ":nope",
+                ":quit",
+            ],
+            &expect![[r#"
+                > def id x = x;
+                id : 'a -> 'a = <function>
+                > :type (id 1)
+                (id 1) : int
+                > :hir 1 + 2
+                App
+                  App
+                    Builtin add
+                    Int 1
+                  Int 2
+                > :tree 1
+                LITERAL_EXPR@0..1
+                  INT@0..1 "1"
+                > :type (
+                error: Expected R_PAREN but found SEMICOLON
+                > :nope
+                error: unknown command `:nope`, type :help for help
+                > :quit
+                quit
+            "#]],
+        );
+    }
+
+    #[test]
+    fn redefinitions_replace_earlier_items() {
+        check(
+            &[
+                "def x = 1;",
+                "def y = x + 1;",
+                "def x = 2;",
+                "x;",
+                "y;",
+                "def x = true;",
+                "x;",
+                "type t = int;",
+                "type t = bool; def z : t = true;",
+                "def _it = 5;",
+                "_it + 1;",
+            ],
+            &expect![[r"
+                > def x = 1;
+                x : int = 1
+                > def y = x + 1;
+                y : int = 2
+                > def x = 2;
+                x : int = 2
+                > x;
+                2 : int
+                > y;
+                3 : int
+                > def x = true;
+                error: expected `int`, found `bool`
+                > x;
+                2 : int
+                > type t = int;
+                > type t = bool; def z : t = true;
+                z : bool = true
+                > def _it = 5;
+                _it : int = 5
+                > _it + 1;
+                6 : int
+            "]],
+        );
+    }
+
+    #[test]
+    fn missing_expressions_are_reported() {
+        check(
+            &[":type", ":hir )", "def;"],
+            &expect![[r"
+                > :type
+                error: Expected expression
+                > :hir )
+                error: Expected expression
+                error: Expected SEMICOLON but found R_PAREN
+                error: Expected declaration
+                error: Unexpected token: R_PAREN
+                Missing
+                > def;
+                error: Expected expression
+                error: Expected SEMICOLON but found DEF_KW
+                error: Expected IDENT but found SEMICOLON
+                error: Expected declaration
+            "]],
+        );
+    }
+
+    #[test]
+    fn int_literals_out_of_range_are_reported() {
+        check(
+            &["99999999999999999999", "def a = 99999999999999999999;"],
+            &expect![[r"
+                > 99999999999999999999
+                error: integer literal out of range
+                > def a = 99999999999999999999;
+                error: integer literal out of range
+            "]],
+        );
+    }
+
+    #[test]
+    fn int_patterns_out_of_range_are_reported() {
+        check(
+            &["match 1 { 99999999999999999999 => 1, _ => 0 }"],
+            &expect![[r"
+                > match 1 { 99999999999999999999 => 1, _ => 0 }
+                error: integer literal out of range
+            "]],
+        );
+    }
+}
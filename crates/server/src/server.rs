@@ -1,12 +1,14 @@
 use std::{collections::HashMap, error::Error};
 
-use analysis::Document;
+use analysis::{Document, SymbolIndex};
 use lsp_server::{
     Connection, ExtractError, Message, Notification, Request, Response, ResponseError,
 };
 use lsp_types::{InitializeParams, ServerCapabilities};
 
+use crate::config::Config;
 use crate::lsp_utils;
+use crate::workspace;
 
 type RequestHandler = Box<dyn Fn(Request, &Server, &Context) -> Response>;
 
@@ -20,6 +22,8 @@ pub(crate) struct Server {
 
 pub(crate) struct Context {
     documents: HashMap<String, Document>,
+    config: Config,
+    symbol_index: SymbolIndex,
 }
 
 impl Server {
@@ -49,9 +53,15 @@ impl Context {
         self.documents.insert(uri, Document::new(text));
     }
 
-    pub(crate) fn update_document(&mut self, uri: &str, text: String) {
+    pub(crate) fn update_document(
+        &mut self,
+        uri: &str,
+        changes: Vec<lsp_types::TextDocumentContentChangeEvent>,
+    ) {
         if let Some(doc) = self.documents.get_mut(uri) {
-            doc.update(text);
+            for change in changes {
+                doc.apply_change(change);
+            }
         }
     }
 
@@ -62,6 +72,44 @@ impl Context {
     pub(crate) fn remove_document(&mut self, path: &str) {
         self.documents.remove(path);
     }
+
+    pub(crate) fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub(crate) fn set_config(&mut self, config: Config) {
+        self.config = config;
+    }
+
+    pub(crate) fn symbol_index(&self) -> &SymbolIndex {
+        &self.symbol_index
+    }
+
+    /// Indexes the module from its open document or, when it is not open, from the disk.
+    pub(crate) fn reindex(&mut self, uri: &lsp_types::Url) {
+        if let Some(doc) = self.documents.get(uri.path()) {
+            self.symbol_index.insert(uri.clone(), doc);
+            return;
+        }
+        let text = uri
+            .to_file_path()
+            .ok()
+            .and_then(|path| std::fs::read_to_string(path).ok());
+        match text {
+            Some(text) => self.symbol_index.insert(uri.clone(), &Document::new(text)),
+            None => self.symbol_index.remove(uri),
+        }
+    }
+
+    fn index_workspace(&mut self, params: &InitializeParams) {
+        for root in workspace::roots(params) {
+            for path in workspace::module_files(&root) {
+                if let Ok(uri) = lsp_types::Url::from_file_path(&path) {
+                    self.reindex(&uri);
+                }
+            }
+        }
+    }
 }
 
 impl Server {
@@ -87,14 +135,14 @@ impl Server {
             .map(|it| serde_json::from_value(it).unwrap())
     }
 
-    pub(crate) fn run(
-        self,
-        _params: &InitializeParams,
-    ) -> Result<(), Box<dyn Error + Sync + Send>> {
+    pub(crate) fn run(self, params: &InitializeParams) -> Result<(), Box<dyn Error + Sync + Send>> {
         eprintln!("starting example main loop");
         let mut ctx = Context {
             documents: HashMap::new(),
+            config: Config::from_json(params.initialization_options.clone()),
+            symbol_index: SymbolIndex::default(),
         };
+        ctx.index_workspace(params);
         for msg in &self.connection.receiver {
             match msg {
                 Message::Request(req) => {
@@ -104,7 +152,7 @@ impl Server {
                     eprintln!("got request: {req:?}");
                     if let Some(resp) = self.handle_request(&ctx, req) {
                         self.send_response(resp);
-                    };
+                    }
                 }
                 Message::Response(resp) => {
                     eprintln!("got response: {resp:?}");
@@ -0,0 +1,53 @@
+use std::path::{Path, PathBuf};
+
+use lsp_types::InitializeParams;
+
+/// Directories opened in the editor.
+pub(crate) fn roots(params: &InitializeParams) -> Vec<PathBuf> {
+    #[allow(deprecated)]
+    let root_uri = params.root_uri.iter();
+    params
+        .workspace_folders
+        .iter()
+        .flatten()
+        .map(|folder| &folder.uri)
+        .chain(root_uri)
+        .filter_map(|uri| uri.to_file_path().ok())
+        .fold(Vec::new(), |mut roots, root| {
+            if !roots.contains(&root) {
+                roots.push(root);
+            }
+            roots
+        })
+}
+
+/// Camlot modules below the directory, skipping hidden directories and build outputs.
+pub(crate) fn module_files(root: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.filter_map(Result::ok) {
+            let path = entry.path();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                let name = entry.file_name();
+                let name = name.to_string_lossy();
+                if !name.starts_with('.') && name != "target" && name != "node_modules" {
+                    dirs.push(path);
+                }
+            } else if is_module(&path) {
+                files.push(path);
+            }
+        }
+    }
+    files
+}
+
+pub(crate) fn is_module(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "rml")
+}
@@ -20,7 +20,9 @@ async function initLanguageClient() {
 	};
 	const clientOptions: LanguageClientOptions = {
 		documentSelector: [{ scheme: "file", language: "camlot" }],
+		initializationOptions: vscode.workspace.getConfiguration("camlot"),
 		synchronize: {
+			configurationSection: "camlot",
 			fileEvents: vscode.workspace.createFileSystemWatcher("**/*.{rml,rmli}"),
 		},
 	};
@@ -100,7 +100,6 @@ macro_rules! define_kinds {
 }
 use std::{collections::HashSet, str::FromStr};
 
-pub(crate) use define_kinds;
 use indexmap::IndexMap;
 use proc_macro2::{Ident, TokenStream};
 use quote::{format_ident, quote};
@@ -163,11 +162,15 @@ pub(crate) fn kinds() -> KindsSrc {
         "/" => "Slash";
         "\\" => "Backslash";
         "λ" => "Lambda";
+        "|" => "Pipe";
+        "=>" => "Fat_Arrow";
+        "_" => "Underscore";
 
         // Literals
         lit("Int") => r"(?:0|[1-9][0-9]*)";
         lit("String") => "\"(?s:[^\"\\\\]|\\\\.)*\"";
         lit("Ident") => r"[_a-zA-Z][_a-zA-Z0-9]*";
+        lit("Quote_Ident") => r"'[_a-zA-Z][_a-zA-Z0-9]*";
         lit("Whitespace") => r"[ \t\n\r]+";
         lit("Comment") => r"#[^\r\n]*(\r\n|\n)?";
     ];
@@ -115,7 +115,7 @@ fn generate_syntax_kinds(kinds: &KindsSrc, grammar: &AstSrc) -> Result<String> {
             #[doc(hidden)]
             EOF,
             #(#token_kinds,)*
-            /// Also acts as __LAST_TOKEN
+            /// Also acts as `__LAST_TOKEN`
             #[error]
             LEXING_ERROR,
             ERROR,
@@ -259,7 +259,7 @@ impl<'a, T> OneOrMany<'a, T> {
                 *self = OneOrMany::Multiple(vec![prev_field, field]);
             }
             OneOrMany::Multiple(fields) => fields.push(field),
-        };
+        }
     }
 }
 